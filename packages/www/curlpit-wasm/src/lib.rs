@@ -1,20 +1,29 @@
 use std::collections::HashMap;
 
+use curlpit::env::ProvenanceMap;
 use curlpit::web::{
     import_curl_command_web as core_import_curl,
-    process_request as core_process_request,
+    process_request_with_provenance as core_process_request,
     render_export_template_web as core_render_export,
     WebProcessError, WebProcessedRequest,
 };
 use serde_wasm_bindgen::{from_value, to_value};
 use wasm_bindgen::prelude::*;
 
+/// `provenance` maps each variable to the sources that assigned it, as serialized by
+/// `ProvenanceMap`; it may be omitted.
 #[wasm_bindgen]
-pub fn process_request(curl: &str, env: JsValue) -> Result<JsValue, JsValue> {
+pub fn process_request(curl: &str, env: JsValue, provenance: JsValue) -> Result<JsValue, JsValue> {
     let env_map: HashMap<String, String> = from_value(env)
         .map_err(|err| JsValue::from_str(&format!("Invalid env map: {err}")))?;
-
-    convert_result(core_process_request(curl, &env_map))
+    let provenance: Option<ProvenanceMap> = from_value(provenance)
+        .map_err(|err| JsValue::from_str(&format!("Invalid provenance map: {err}")))?;
+
+    convert_result(core_process_request(
+        curl,
+        &env_map,
+        &provenance.unwrap_or_default(),
+    ))
 }
 
 #[wasm_bindgen]
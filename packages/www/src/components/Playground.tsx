@@ -21,6 +21,7 @@ import {
 import { playgroundExamples } from "../data/examples";
 import {
   formatImportedVariables,
+  parseEnvProvenance,
   parseEnvVariables,
   type EnvVars,
 } from "../lib/env";
@@ -32,6 +33,7 @@ import {
   type WasmImportResult,
   type WasmProcessedRequest,
   type WasmRequest,
+  type WasmVariableSource,
 } from "../lib/wasm";
 
 interface PlaygroundResponse {
@@ -127,6 +129,20 @@ function responseOk(status: number): boolean {
   return status >= 200 && status < 300;
 }
 
+const VARIABLES_FILE = "variables.env";
+
+function describeSource(source: WasmVariableSource): string {
+  switch (source.kind) {
+    case "config":
+      return `${source.path} at ${source.key}`;
+    case "envFile":
+    case "envDirective":
+      return source.line === null ? source.path : `${source.path}:${source.line}`;
+    case "processEnv":
+      return "process env";
+  }
+}
+
 function renderInterpolationDetails(details: WasmProcessedRequest["interpolation"]): JSX.Element {
   if (details.length === 0) {
     return <></>;
@@ -139,6 +155,11 @@ function renderInterpolationDetails(details: WasmProcessedRequest["interpolation
           <span className="variable-highlight">{`{${detail.key}}`}</span>
           <span className="arrow">→</span>
           <span className="syntax-header-value">{detail.value}</span>
+          {detail.chain.length > 0 && (
+            <span className="interpolation-source">
+              {`(${describeSource(detail.chain[detail.chain.length - 1])})`}
+            </span>
+          )}
         </div>
       ))}
     </div>
@@ -169,6 +190,10 @@ export function Playground(): JSX.Element {
   const importRef = useRef<HTMLTextAreaElement | null>(null);
 
   const envVars = useMemo(() => parseEnvVariables(variables), [variables]);
+  const envProvenance = useMemo(
+    () => parseEnvProvenance(variables, VARIABLES_FILE),
+    [variables],
+  );
 
   useEffect(() => {
     ensureWasmReady().catch((error) => {
@@ -187,7 +212,7 @@ export function Playground(): JSX.Element {
       }
 
       try {
-        const processed = await processRequest(curl, envVars);
+        const processed = await processRequest(curl, envVars, envProvenance);
         if (!cancelled) {
           setPreview(processed);
           setPreviewError(null);
@@ -206,7 +231,7 @@ export function Playground(): JSX.Element {
     return () => {
       cancelled = true;
     };
-  }, [curl, envVars]);
+  }, [curl, envVars, envProvenance]);
 
   useEffect(() => {
     let cancelled = false;
@@ -305,7 +330,7 @@ export function Playground(): JSX.Element {
     setRunResult({ status: "preparing", timestamp });
 
     try {
-      const processed = await processRequest(curl, envVars);
+      const processed = await processRequest(curl, envVars, envProvenance);
 
       const request = processed.request;
       if (!request.url || !/^https?:\/\//i.test(request.url)) {
@@ -360,7 +385,7 @@ export function Playground(): JSX.Element {
     } finally {
       setIsRunning(false);
     }
-  }, [curl, envVars, isRunning]);
+  }, [curl, envVars, envProvenance, isRunning]);
 
   useEffect(() => {
     const handler = (event: KeyboardEvent) => {
@@ -728,7 +753,7 @@ export function Playground(): JSX.Element {
                   <div className="window-dot red" />
                   <div className="window-dot yellow" />
                   <div className="window-dot green" />
-                  <span className="pane-title">variables.env</span>
+                  <span className="pane-title">{VARIABLES_FILE}</span>
                 </div>
                 <Button className="btn-secondary" onPress={clearVariables}>
                   Clear
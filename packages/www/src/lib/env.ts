@@ -1,12 +1,43 @@
 export type EnvVars = Record<string, string>;
 
+export interface EnvFileSource {
+  kind: "envFile";
+  path: string;
+  line: number | null;
+}
+
+export type EnvProvenance = Record<string, EnvFileSource[]>;
+
 const ENV_LINE = /^([A-Z_][A-Z0-9_\-.]*)\s*=\s*(.*)$/;
 
 export function parseEnvVariables(content: string): EnvVars {
   const vars: EnvVars = {};
+  for (const { key, rawValue } of envEntries(content)) {
+    vars[key] = evaluateBasicTemplate(rawValue);
+  }
+  return vars;
+}
+
+/** Records the lines of `path` that assigned each variable, shaped like curlpit's `ProvenanceMap`. */
+export function parseEnvProvenance(content: string, path: string): EnvProvenance {
+  const provenance: EnvProvenance = {};
+  for (const { key, line } of envEntries(content)) {
+    (provenance[key] ??= []).push({ kind: "envFile", path, line });
+  }
+  return provenance;
+}
+
+interface EnvEntry {
+  key: string;
+  rawValue: string;
+  line: number;
+}
+
+function envEntries(content: string): EnvEntry[] {
+  const entries: EnvEntry[] = [];
   const lines = content.split("\n");
 
-  for (const line of lines) {
+  for (const [index, line] of lines.entries()) {
     const trimmed = line.trim();
     if (!trimmed || trimmed.startsWith("#")) {
       continue;
@@ -18,10 +49,10 @@ export function parseEnvVariables(content: string): EnvVars {
     }
 
     const [, key, rawValue] = match;
-    vars[key] = evaluateBasicTemplate(rawValue);
+    entries.push({ key, rawValue, line: index + 1 });
   }
 
-  return vars;
+  return entries;
 }
 
 function evaluateBasicTemplate(value: string): string {
@@ -17,9 +17,16 @@ export interface WasmRequest {
   body?: string | null;
 }
 
+export type WasmVariableSource =
+  | { kind: "config"; path: string; key: string }
+  | { kind: "envFile" | "envDirective"; path: string; line: number | null }
+  | { kind: "processEnv" };
+
 export interface WasmInterpolationDetail {
   key: string;
   value: string;
+  /** Every source that assigned the variable; the last one won. */
+  chain: WasmVariableSource[];
 }
 
 export interface WasmProcessedRequest {
@@ -47,9 +54,10 @@ export function ensureWasmReady(): Promise<void> {
 export async function processRequest(
   curl: string,
   env: Record<string, string>,
+  provenance?: Record<string, WasmVariableSource[]>,
 ): Promise<WasmProcessedRequest> {
   await ensureWasmReady();
-  return wasmProcessRequest(curl, env) as WasmProcessedRequest;
+  return wasmProcessRequest(curl, env, provenance) as WasmProcessedRequest;
 }
 
 export async function importCurl(
@@ -0,0 +1,296 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use serde_json::Value;
+
+use super::{config_schema, resolve_profile, LoadedConfig};
+use crate::template::BUILTIN_TEMPLATES;
+
+/// A problem found in a loaded configuration; `key` is the dotted path it refers to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigIssue {
+    pub key: String,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.key, self.message)
+    }
+}
+
+/// Validates a loaded configuration beyond what deserialization catches.
+///
+/// Reports unknown keys (with a suggestion when one is close), env files that do not exist, a
+/// `defaultProfile` or `extends` naming no profile, export templates that are missing or shadowed
+/// by a built-in one, and output directories that cannot be used.
+pub fn check_config(loaded: &LoadedConfig) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+    let config = &loaded.config;
+    let schema = config_schema();
+    let root_keys = known_keys(&schema, "/properties");
+    let profile_keys = known_keys(&schema, "/definitions/CurlpitProfileConfig/properties");
+
+    unknown_keys(&mut issues, "", &config.extras, &root_keys);
+    if let Some(env) = &config.env {
+        missing_file(&mut issues, "env", &loaded.dir, env);
+    }
+    for (name, path) in loaded.export_templates() {
+        let key = format!("exportTemplates.{name}");
+        if BUILTIN_TEMPLATES.contains(&name.as_str()) {
+            issues.push(ConfigIssue {
+                key,
+                message: "is shadowed by the built-in template of the same name".to_string(),
+            });
+        } else if !path.is_file() {
+            issues.push(ConfigIssue {
+                key,
+                message: format!("template file {} does not exist", path.display()),
+            });
+        }
+    }
+    if let Some(dir) = &config.response_output_dir {
+        unusable_dir(&mut issues, "responseOutputDir", &loaded.dir, dir);
+    }
+    if let Some(name) = &config.default_profile {
+        if !config.profiles.contains_key(name) {
+            issues.push(ConfigIssue {
+                key: "defaultProfile".to_string(),
+                message: format!(
+                    "names unknown profile {name}{}",
+                    suggestion(name, config.profiles.keys().map(String::as_str))
+                ),
+            });
+        }
+    }
+
+    let mut names: Vec<&String> = config.profiles.keys().collect();
+    names.sort();
+    for name in names {
+        let profile = &config.profiles[name];
+        let prefix = format!("profiles.{name}");
+        unknown_keys(&mut issues, &prefix, &profile.extras, &profile_keys);
+        if let Some(env) = &profile.env {
+            missing_file(&mut issues, &format!("{prefix}.env"), &loaded.dir, env);
+        }
+        if let Some(dir) = &profile.response_output_dir {
+            unusable_dir(
+                &mut issues,
+                &format!("{prefix}.responseOutputDir"),
+                &loaded.dir,
+                dir,
+            );
+        }
+        if let Err(err) = resolve_profile(config, Some(name)) {
+            issues.push(ConfigIssue {
+                key: format!("{prefix}.extends"),
+                message: err.to_string(),
+            });
+        }
+    }
+
+    issues
+}
+
+fn known_keys(schema: &Value, pointer: &str) -> Vec<String> {
+    schema
+        .pointer(pointer)
+        .and_then(Value::as_object)
+        .map(|properties| properties.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+fn unknown_keys(
+    issues: &mut Vec<ConfigIssue>,
+    prefix: &str,
+    extras: &HashMap<String, Value>,
+    known: &[String],
+) {
+    let mut keys: Vec<&String> = extras.keys().collect();
+    keys.sort();
+    for key in keys {
+        issues.push(ConfigIssue {
+            key: join_key(prefix, key),
+            message: format!(
+                "unknown key{}",
+                suggestion(key, known.iter().map(String::as_str))
+            ),
+        });
+    }
+}
+
+fn missing_file(issues: &mut Vec<ConfigIssue>, key: &str, base: &Path, value: &str) {
+    let path = resolve(base, value);
+    if !path.is_file() {
+        issues.push(ConfigIssue {
+            key: key.to_string(),
+            message: format!("env file {} does not exist", path.display()),
+        });
+    }
+}
+
+fn unusable_dir(issues: &mut Vec<ConfigIssue>, key: &str, base: &Path, value: &str) {
+    let path = resolve(base, value);
+    // The directory is created on demand, so only its nearest existing ancestor has to be usable
+    let Some(existing) = path.ancestors().find(|ancestor| ancestor.exists()) else {
+        return;
+    };
+    let message = if !existing.is_dir() {
+        format!("{} is not a directory", existing.display())
+    } else if existing
+        .metadata()
+        .map(|meta| meta.permissions().readonly())
+        .unwrap_or(false)
+    {
+        format!("{} is not writable", existing.display())
+    } else {
+        return;
+    };
+    issues.push(ConfigIssue {
+        key: key.to_string(),
+        message: format!(
+            "output directory {} is unreachable: {message}",
+            path.display()
+        ),
+    });
+}
+
+fn resolve(base: &Path, value: &str) -> PathBuf {
+    let candidate = Path::new(value);
+    if candidate.is_absolute() {
+        candidate.to_path_buf()
+    } else {
+        base.join(candidate)
+    }
+}
+
+fn join_key(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{prefix}.{key}")
+    }
+}
+
+fn suggestion<'a>(input: &str, candidates: impl Iterator<Item = &'a str>) -> String {
+    let lowered = input.to_ascii_lowercase();
+    let threshold = (input.len() / 3).max(2);
+    candidates
+        .map(|candidate| {
+            (
+                edit_distance(&lowered, &candidate.to_ascii_lowercase()),
+                candidate,
+            )
+        })
+        .filter(|(distance, _)| *distance <= threshold)
+        .min()
+        .map(|(_, candidate)| format!(" (did you mean {candidate}?)"))
+        .unwrap_or_default()
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            current[j + 1] = (previous[j] + cost)
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::load_config;
+    use anyhow::Result;
+    use tempfile::tempdir;
+
+    #[test]
+    fn reports_typos_missing_files_and_bad_references() -> Result<()> {
+        let temp = tempdir()?;
+        std::fs::write(temp.path().join("blocker"), "")?;
+        std::fs::write(temp.path().join("dev.env"), "A=1\n")?;
+        std::fs::write(
+            temp.path().join("curlpit.json"),
+            r#"{
+  "defaultHeader": {"Accept": "application/json"},
+  "defaultProfile": "prd",
+  "env": "missing.env",
+  "responseOutputDir": "blocker/responses",
+  "profiles": {
+    "dev": {"env": "dev.env", "varaibles": {}},
+    "prod": {"extends": "staging"}
+  }
+}"#,
+        )?;
+        let loaded = load_config(temp.path())?.expect("config");
+
+        let issues: Vec<String> = check_config(&loaded)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(issues.len(), 6, "{issues:#?}");
+        assert_eq!(
+            issues[0],
+            "defaultHeader: unknown key (did you mean defaultHeaders?)"
+        );
+        assert!(issues[1].starts_with("env: env file"));
+        assert!(issues[2].starts_with("responseOutputDir: output directory"));
+        assert!(issues[2].ends_with("is not a directory"));
+        assert_eq!(
+            issues[3],
+            "defaultProfile: names unknown profile prd (did you mean prod?)"
+        );
+        assert_eq!(
+            issues[4],
+            "profiles.dev.varaibles: unknown key (did you mean variables?)"
+        );
+        assert!(issues[5].starts_with("profiles.prod.extends: "));
+        Ok(())
+    }
+
+    #[test]
+    fn reports_missing_and_shadowed_export_templates() -> Result<()> {
+        let temp = tempdir()?;
+        std::fs::write(temp.path().join("ruby.mustache"), "")?;
+        std::fs::write(
+            temp.path().join("curlpit.json"),
+            r#"{"exportTemplates": {"ruby": "ruby.mustache", "php": "php.mustache", "curl": "ruby.mustache"}}"#,
+        )?;
+        let loaded = load_config(temp.path())?.expect("config");
+
+        let issues: Vec<String> = check_config(&loaded)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(issues.len(), 2, "{issues:#?}");
+        assert_eq!(
+            issues[0],
+            "exportTemplates.curl: is shadowed by the built-in template of the same name"
+        );
+        assert!(issues[1].starts_with("exportTemplates.php: template file"));
+        Ok(())
+    }
+
+    #[test]
+    fn clean_config_has_no_issues() -> Result<()> {
+        let temp = tempdir()?;
+        std::fs::write(
+            temp.path().join("curlpit.json"),
+            r#"{"$schema": "./schema.json", "defaultProfile": "dev", "profiles": {"dev": {}}}"#,
+        )?;
+        let loaded = load_config(temp.path())?.expect("config");
+        assert!(check_config(&loaded).is_empty());
+        Ok(())
+    }
+}
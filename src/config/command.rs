@@ -0,0 +1,309 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+/// Overrides where command output is cached (defaults to the user cache directory).
+pub const CACHE_DIR_ENV: &str = "CURLPIT_CACHE_DIR";
+
+/// A config variable: either a literal string or a command producing the value.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum VariableValue {
+    Literal(String),
+    Command(CommandVariable),
+}
+
+impl VariableValue {
+    pub fn as_literal(&self) -> Option<&str> {
+        match self {
+            VariableValue::Literal(value) => Some(value),
+            VariableValue::Command(_) => None,
+        }
+    }
+}
+
+impl From<&str> for VariableValue {
+    fn from(value: &str) -> Self {
+        VariableValue::Literal(value.to_string())
+    }
+}
+
+impl PartialEq<&str> for VariableValue {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_literal() == Some(*other)
+    }
+}
+
+/// A variable resolved by running a shell command, e.g. a password manager lookup.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema)]
+pub struct CommandVariable {
+    /// Shell command whose standard output (without the trailing newline) becomes the value
+    pub command: String,
+    /// Seconds to cache the output on disk; when omitted the command runs on every use
+    #[serde(default)]
+    pub ttl: Option<u64>,
+}
+
+/// Command variables for one environment, run at most once and only when referenced.
+#[derive(Debug)]
+pub struct DeferredValues<'a> {
+    variables: &'a HashMap<String, CommandVariable>,
+    working_dir: &'a Path,
+    cache_dir: Option<PathBuf>,
+    resolved: HashMap<String, String>,
+}
+
+impl<'a> DeferredValues<'a> {
+    pub fn new(variables: &'a HashMap<String, CommandVariable>, working_dir: &'a Path) -> Self {
+        Self {
+            variables,
+            working_dir,
+            cache_dir: default_cache_dir(),
+            resolved: HashMap::new(),
+        }
+    }
+
+    /// Returns `None` when `key` is not a command variable.
+    pub fn lookup(&mut self, key: &str) -> Option<Result<String>> {
+        if let Some(value) = self.resolved.get(key) {
+            return Some(Ok(value.clone()));
+        }
+        let variable = self.variables.get(key)?;
+        let result = run_command_variable(variable, self.working_dir, self.cache_dir.as_deref())
+            .with_context(|| format!("resolving variable {key}"));
+        if let Ok(value) = &result {
+            self.resolved.insert(key.to_string(), value.clone());
+        }
+        Some(result)
+    }
+
+    /// Values resolved so far.
+    pub fn resolved(&self) -> &HashMap<String, String> {
+        &self.resolved
+    }
+}
+
+/// Runs the command (or reads a fresh cached result) from `working_dir`.
+///
+/// Results with a TTL are cached under `cache_dir`; without one they are never cached.
+pub fn run_command_variable(
+    variable: &CommandVariable,
+    working_dir: &Path,
+    cache_dir: Option<&Path>,
+) -> Result<String> {
+    let ttl = variable.ttl.filter(|ttl| *ttl > 0).map(Duration::from_secs);
+    let cache_path = ttl
+        .and(cache_dir)
+        .map(|dir| cache_path(dir, variable, working_dir));
+
+    if let (Some(path), Some(ttl)) = (&cache_path, ttl) {
+        if let Some(value) = read_fresh(path, ttl) {
+            return Ok(value);
+        }
+    }
+
+    let value = run(&variable.command, working_dir)?;
+    if let Some(path) = &cache_path {
+        // A failed cache write only costs a rerun next time
+        let _ = write_private(path, &value);
+    }
+    Ok(value)
+}
+
+fn run(command: &str, working_dir: &Path) -> Result<String> {
+    let mut process = if cfg!(windows) {
+        let mut process = Command::new("cmd");
+        process.arg("/C").arg(command);
+        process
+    } else {
+        let mut process = Command::new("sh");
+        process.arg("-c").arg(command);
+        process
+    };
+    let output = process
+        .current_dir(working_dir)
+        .output()
+        .with_context(|| format!("running `{command}`"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!(
+            "`{command}` exited with {}: {}",
+            output.status,
+            stderr.trim()
+        );
+    }
+
+    let stdout = String::from_utf8(output.stdout)
+        .map_err(|_| anyhow!("`{command}` printed non-UTF-8 output"))?;
+    Ok(stdout.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// The directory command output is cached in: [`CACHE_DIR_ENV`], else the user cache directory.
+pub fn default_cache_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os(CACHE_DIR_ENV) {
+        return Some(PathBuf::from(dir));
+    }
+    if let Some(dir) = std::env::var_os("XDG_CACHE_HOME") {
+        return Some(PathBuf::from(dir).join("curlpit"));
+    }
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache").join("curlpit"))
+}
+
+fn cache_path(cache_dir: &Path, variable: &CommandVariable, working_dir: &Path) -> PathBuf {
+    let mut hasher = Sha256::new();
+    hasher.update(working_dir.to_string_lossy().as_bytes());
+    hasher.update([0]);
+    hasher.update(variable.command.as_bytes());
+    let name = hex::encode(hasher.finalize());
+    cache_dir.join("commands").join(name)
+}
+
+fn read_fresh(path: &Path, ttl: Duration) -> Option<String> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    let age = SystemTime::now().duration_since(modified).ok()?;
+    if age > ttl {
+        return None;
+    }
+    fs::read_to_string(path).ok()
+}
+
+/// Writes the cached value so only the current user can read it.
+fn write_private(path: &Path, value: &str) -> Result<()> {
+    use std::io::Write;
+
+    let dir = path.parent().context("cache path has no parent")?;
+    fs::create_dir_all(dir)?;
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        fs::set_permissions(dir, fs::Permissions::from_mode(0o700))?;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    file.write_all(value.as_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[cfg(unix)]
+    fn counting_script(dir: &Path) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+
+        let script = dir.join("fake-pass.sh");
+        fs::write(
+            &script,
+            "#!/bin/sh\necho run >> \"$(dirname \"$0\")/calls.log\"\necho \"token-for-$1\"\n",
+        )
+        .unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        script
+    }
+
+    #[cfg(unix)]
+    fn calls(dir: &Path) -> usize {
+        fs::read_to_string(dir.join("calls.log"))
+            .map(|log| log.lines().count())
+            .unwrap_or(0)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn command_output_is_cached_for_ttl() -> Result<()> {
+        let temp = tempdir()?;
+        let cache = temp.path().join("cache");
+        counting_script(temp.path());
+
+        let variable = CommandVariable {
+            command: "./fake-pass.sh api".to_string(),
+            ttl: Some(60),
+        };
+        assert_eq!(
+            run_command_variable(&variable, temp.path(), Some(&cache))?,
+            "token-for-api"
+        );
+        assert_eq!(
+            run_command_variable(&variable, temp.path(), Some(&cache))?,
+            "token-for-api"
+        );
+        assert_eq!(calls(temp.path()), 1);
+
+        let entries: Vec<_> = fs::read_dir(cache.join("commands"))?.collect();
+        assert_eq!(entries.len(), 1);
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = entries[0]
+                .as_ref()
+                .unwrap()
+                .metadata()?
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let uncached = CommandVariable {
+            command: "./fake-pass.sh other".to_string(),
+            ttl: None,
+        };
+        run_command_variable(&uncached, temp.path(), Some(&cache))?;
+        run_command_variable(&uncached, temp.path(), Some(&cache))?;
+        assert_eq!(calls(temp.path()), 3);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn deferred_values_run_only_referenced_commands_once() -> Result<()> {
+        let temp = tempdir()?;
+        counting_script(temp.path());
+        let variables = HashMap::from([
+            (
+                "API_TOKEN".to_string(),
+                CommandVariable {
+                    command: "./fake-pass.sh api".to_string(),
+                    ttl: None,
+                },
+            ),
+            (
+                "UNUSED".to_string(),
+                CommandVariable {
+                    command: "./fake-pass.sh unused".to_string(),
+                    ttl: None,
+                },
+            ),
+        ]);
+        let mut deferred = DeferredValues::new(&variables, temp.path());
+
+        assert!(deferred.lookup("PLAIN").is_none());
+        assert_eq!(deferred.lookup("API_TOKEN").unwrap()?, "token-for-api");
+        assert_eq!(deferred.lookup("API_TOKEN").unwrap()?, "token-for-api");
+        assert_eq!(calls(temp.path()), 1);
+        assert_eq!(deferred.resolved().len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn failing_command_reports_stderr() {
+        let temp = tempdir().unwrap();
+        let variable = CommandVariable {
+            command: "echo locked >&2; exit 3".to_string(),
+            ttl: None,
+        };
+        let err = run_command_variable(&variable, temp.path(), None).unwrap_err();
+        assert!(err.to_string().contains("locked"), "{err}");
+    }
+}
@@ -0,0 +1,308 @@
+use anyhow::{anyhow, bail, Result};
+use serde_json::Value;
+
+/// Sets the member at `path` (for example `["profiles", "staging"]`) of a JSON or JSONC
+/// document, creating missing objects on the way.
+///
+/// Only the edited member changes: the order, formatting and comments of everything else are
+/// kept as written.
+pub fn set_json_member(contents: &str, path: &[&str], value: &Value) -> Result<String> {
+    let Some((key, parents)) = path.split_last() else {
+        bail!("no member to set");
+    };
+    let bytes = contents.as_bytes();
+    let mut open = skip_trivia(bytes, 0);
+    if bytes.get(open) != Some(&b'{') {
+        bail!("the config is not a JSON object");
+    }
+
+    for (depth, parent) in parents.iter().enumerate() {
+        let object = parse_object(bytes, open)?;
+        match object.members.iter().find(|member| member.key == *parent) {
+            Some(member) if bytes[member.value_start] == b'{' => open = member.value_start,
+            Some(_) => bail!("{} is not a JSON object", path[..=depth].join(".")),
+            None => {
+                // Nest the remaining keys into one new member
+                let nested = path[depth + 1..]
+                    .iter()
+                    .rev()
+                    .fold(value.clone(), |inner, key| {
+                        Value::Object([(key.to_string(), inner)].into_iter().collect())
+                    });
+                return Ok(insert_member(contents, &object, parent, &nested));
+            }
+        }
+    }
+
+    let object = parse_object(bytes, open)?;
+    match object.members.iter().find(|member| member.key == *key) {
+        Some(member) => {
+            let indent = line_indent(contents, member.key_start);
+            Ok(format!(
+                "{}{}{}",
+                &contents[..member.value_start],
+                render(value, indent),
+                &contents[member.value_end..]
+            ))
+        }
+        None => Ok(insert_member(contents, &object, key, value)),
+    }
+}
+
+struct Object {
+    open: usize,
+    close: usize,
+    members: Vec<Member>,
+}
+
+struct Member {
+    key: String,
+    key_start: usize,
+    value_start: usize,
+    value_end: usize,
+}
+
+fn insert_member(contents: &str, object: &Object, key: &str, value: &Value) -> String {
+    let bytes = contents.as_bytes();
+    let key = serde_json::to_string(key).expect("strings serialize");
+    let close_indent = line_indent(contents, object.close);
+    let multiline = close_indent.len() == object.close - line_start(contents, object.close);
+
+    let Some(last) = object.members.last() else {
+        let outer = line_indent(contents, object.open);
+        let indent = format!("{outer}  ");
+        let member = format!("{indent}{key}: {}", render(value, &indent));
+        return format!(
+            "{}{{\n{member}\n{outer}}}{}",
+            &contents[..object.open],
+            &contents[object.close + 1..]
+        );
+    };
+
+    let after_last = skip_trivia(bytes, last.value_end);
+    let trailing_comma = bytes.get(after_last) == Some(&b',');
+    if !multiline {
+        let member = format!(
+            ", {key}: {}",
+            serde_json::to_string(value).expect("values serialize")
+        );
+        return format!(
+            "{}{member}{}",
+            &contents[..last.value_end],
+            &contents[last.value_end..]
+        );
+    }
+
+    let indent = line_indent(contents, last.key_start);
+    let mut member = format!("{indent}{key}: {}", render(value, indent));
+    if trailing_comma {
+        member.push(',');
+    }
+    let insert_at = line_start(contents, object.close);
+    let comma = if trailing_comma { "" } else { "," };
+    format!(
+        "{}{comma}{}{member}\n{}",
+        &contents[..last.value_end],
+        &contents[last.value_end..insert_at],
+        &contents[insert_at..]
+    )
+}
+
+/// Pretty-prints `value` with every line after the first indented like its member.
+fn render(value: &Value, indent: &str) -> String {
+    serde_json::to_string_pretty(value)
+        .expect("values serialize")
+        .replace('\n', &format!("\n{indent}"))
+}
+
+fn line_start(contents: &str, pos: usize) -> usize {
+    contents[..pos].rfind('\n').map_or(0, |index| index + 1)
+}
+
+fn line_indent(contents: &str, pos: usize) -> &str {
+    let start = line_start(contents, pos);
+    let line = &contents[start..pos];
+    &line[..line.len() - line.trim_start().len()]
+}
+
+fn parse_object(bytes: &[u8], open: usize) -> Result<Object> {
+    let mut members = Vec::new();
+    let mut pos = open + 1;
+    loop {
+        pos = skip_trivia(bytes, pos);
+        match bytes.get(pos) {
+            Some(b'}') => {
+                return Ok(Object {
+                    open,
+                    close: pos,
+                    members,
+                })
+            }
+            Some(b',') => pos += 1,
+            Some(b'"') => {
+                let key_start = pos;
+                let key_end = skip_value(bytes, pos)?;
+                let key: String = serde_json::from_slice(&bytes[key_start..key_end])?;
+                pos = skip_trivia(bytes, key_end);
+                if bytes.get(pos) != Some(&b':') {
+                    bail!("expected `:` after {key}");
+                }
+                let value_start = skip_trivia(bytes, pos + 1);
+                let value_end = skip_value(bytes, value_start)?;
+                members.push(Member {
+                    key,
+                    key_start,
+                    value_start,
+                    value_end,
+                });
+                pos = value_end;
+            }
+            _ => bail!("unexpected content at byte {pos}"),
+        }
+    }
+}
+
+/// Returns the end of the value starting at `pos`.
+fn skip_value(bytes: &[u8], pos: usize) -> Result<usize> {
+    match bytes.get(pos) {
+        Some(b'"') => {
+            let mut index = pos + 1;
+            while let Some(&byte) = bytes.get(index) {
+                match byte {
+                    b'\\' => index += 2,
+                    b'"' => return Ok(index + 1),
+                    _ => index += 1,
+                }
+            }
+            Err(anyhow!("unterminated string"))
+        }
+        Some(b'{' | b'[') => {
+            let mut depth = 0;
+            let mut index = pos;
+            while let Some(&byte) = bytes.get(index) {
+                match byte {
+                    b'"' => {
+                        index = skip_value(bytes, index)?;
+                        continue;
+                    }
+                    b'/' => {
+                        let next = skip_trivia(bytes, index);
+                        if next > index {
+                            index = next;
+                            continue;
+                        }
+                    }
+                    b'{' | b'[' => depth += 1,
+                    b'}' | b']' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return Ok(index + 1);
+                        }
+                    }
+                    _ => {}
+                }
+                index += 1;
+            }
+            Err(anyhow!("unterminated object or array"))
+        }
+        Some(_) => {
+            let mut index = pos;
+            while bytes.get(index).is_some_and(|byte| {
+                !matches!(byte, b',' | b'}' | b']' | b'/') && !byte.is_ascii_whitespace()
+            }) {
+                index += 1;
+            }
+            Ok(index)
+        }
+        None => Err(anyhow!("unexpected end of the config")),
+    }
+}
+
+/// Skips whitespace and `//` or `/* */` comments.
+fn skip_trivia(bytes: &[u8], mut pos: usize) -> usize {
+    loop {
+        match (bytes.get(pos), bytes.get(pos + 1)) {
+            (Some(byte), _) if byte.is_ascii_whitespace() => pos += 1,
+            (Some(b'/'), Some(b'/')) => {
+                while bytes.get(pos).is_some_and(|byte| *byte != b'\n') {
+                    pos += 1;
+                }
+            }
+            (Some(b'/'), Some(b'*')) => {
+                pos += 2;
+                while pos < bytes.len() && !bytes[pos..].starts_with(b"*/") {
+                    pos += 1;
+                }
+                pos = (pos + 2).min(bytes.len());
+            }
+            _ => return pos,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn inserts_members_and_keeps_the_rest_as_written() -> Result<()> {
+        let contents = r#"{
+    // shared by every profile
+    "variables": {
+        "ZED": "last", // stays put
+        "API_BASE": "http://localhost",
+    },
+    "profiles": {}
+}
+"#;
+        let edited = set_json_member(contents, &["variables", "TOKEN"], &json!("abc"))?;
+        let edited = set_json_member(
+            &edited,
+            &["profiles", "staging"],
+            &json!({"variables": {"API_BASE": "https://staging.example.com"}}),
+        )?;
+        let edited = set_json_member(&edited, &["defaultProfile"], &json!("staging"))?;
+        assert_eq!(
+            edited,
+            r#"{
+    // shared by every profile
+    "variables": {
+        "ZED": "last", // stays put
+        "API_BASE": "http://localhost",
+        "TOKEN": "abc",
+    },
+    "profiles": {
+      "staging": {
+        "variables": {
+          "API_BASE": "https://staging.example.com"
+        }
+      }
+    },
+    "defaultProfile": "staging"
+}
+"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn replaces_values_and_creates_missing_objects() -> Result<()> {
+        let edited = set_json_member(
+            r#"{"variables": {"A": "1", "B": [1, {"x": "}"}]}}"#,
+            &["variables", "A"],
+            &json!("2"),
+        )?;
+        assert_eq!(edited, r#"{"variables": {"A": "2", "B": [1, {"x": "}"}]}}"#);
+
+        let edited = set_json_member("{}\n", &["profiles", "dev", "variables"], &json!({}))?;
+        assert_eq!(
+            edited,
+            "{\n  \"profiles\": {\n    \"dev\": {\n      \"variables\": {}\n    }\n  }\n}\n"
+        );
+
+        let edited = set_json_member(r#"{"a": 1}"#, &["b"], &json!(true))?;
+        assert_eq!(edited, r#"{"a": 1, "b": true}"#);
+        Ok(())
+    }
+}
@@ -3,11 +3,11 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use anyhow::{bail, Result};
+use anyhow::Result;
 
-use crate::env::{load_env_file_sync, EnvMap};
+use crate::env::{load_env_file_tracked, EnvMap, ProvenanceMap, VariableSource};
 
-use super::{CurlpitConfig, CurlpitProfileConfig, LoadedConfig};
+use super::{apply_overlays, resolve_profile, CommandVariable, LoadedConfig, VariableValue};
 
 #[derive(Debug, Clone)]
 pub struct EnvironmentContext {
@@ -20,6 +20,9 @@ pub struct EnvironmentContext {
     pub response_output_dir: Option<PathBuf>,
     pub default_headers: HashMap<String, String>,
     pub template_variants: Vec<(String, String)>,
+    pub provenance: ProvenanceMap,
+    /// Variables produced by commands, run only when a request references them.
+    pub command_variables: HashMap<String, CommandVariable>,
 }
 
 #[derive(Debug, Clone)]
@@ -51,6 +54,15 @@ impl EnvironmentBuilder {
         }
     }
 
+    /// Returns a builder whose config includes the overlays that apply to `request_path`.
+    pub fn for_request(&self, request_path: &Path) -> Result<Self> {
+        let mut builder = self.clone();
+        if let Some(cfg) = &self.config {
+            builder.config = Some(apply_overlays(cfg, request_path)?);
+        }
+        Ok(builder)
+    }
+
     pub async fn build(&self) -> Result<EnvironmentContext> {
         let mut template_variables: EnvMap = HashMap::new();
         let mut profile_name = None;
@@ -59,22 +71,52 @@ impl EnvironmentBuilder {
         let mut response_output_dir = self.explicit_output_dir.clone();
         let mut default_headers: HashMap<String, String> = HashMap::new();
         let mut template_variants: Vec<(String, String)> = Vec::new();
+        let mut provenance = ProvenanceMap::new();
+        let mut command_variables: HashMap<String, CommandVariable> = HashMap::new();
 
         if let Some(cfg) = &self.config {
             let profile = resolve_profile(&cfg.config, self.requested_profile.as_deref())?;
             profile_name = Some(profile.name.clone());
-            template_variables.extend(cfg.config.variables.clone());
-            template_variables.extend(profile.config.variables.clone());
+            let mut config_variables = cfg.config.variables.clone();
+            config_variables.extend(profile.config.variables.clone());
+            for (key, value) in config_variables {
+                match value {
+                    VariableValue::Literal(value) => {
+                        template_variables.insert(key, value);
+                    }
+                    VariableValue::Command(command) => {
+                        command_variables.insert(key, command);
+                    }
+                }
+            }
+            record_config_variables(&mut provenance, cfg, "variables", &cfg.config.variables);
+            for (name, ancestor) in &profile.lineage {
+                record_config_variables(
+                    &mut provenance,
+                    cfg,
+                    &format!("profiles.{name}.variables"),
+                    &ancestor.variables,
+                );
+            }
 
             // Collect variable values from other profiles for placeholder substitution
-            for other in cfg.config.profiles.values() {
-                for (key, value) in &other.variables {
+            let mut other_names: Vec<&String> = cfg.config.profiles.keys().collect();
+            other_names.sort();
+            for other_name in other_names {
+                // Broken siblings only matter when they are the selected profile
+                let Ok(other) = resolve_profile(&cfg.config, Some(other_name)) else {
+                    continue;
+                };
+                for (key, value) in &other.config.variables {
+                    let Some(value) = value.as_literal() else {
+                        continue;
+                    };
                     if template_variables
                         .get(key)
                         .map(|existing| existing != value)
                         .unwrap_or(true)
                     {
-                        template_variants.push((key.clone(), value.clone()));
+                        template_variants.push((key.clone(), value.to_string()));
                     }
                 }
             }
@@ -88,20 +130,36 @@ impl EnvironmentBuilder {
             if let Some(root_env) = &cfg.config.env {
                 push_unique(&mut env_paths, resolve_relative(&self.config_dir, root_env));
             }
-            if let Some(profile_env) = &profile.config.env {
+            for profile_env in &profile.env_files {
                 push_unique(
                     &mut env_paths,
                     resolve_relative(&self.config_dir, profile_env),
                 );
             }
+            for (owner, overlay_env) in &cfg.overlay_env_files {
+                let applies = owner
+                    .as_ref()
+                    .is_none_or(|owner| profile.lineage.iter().any(|(name, _)| name == owner));
+                if applies {
+                    push_unique(&mut env_paths, overlay_env.clone());
+                }
+            }
             if let Some(explicit) = &self.explicit_env {
                 push_unique(&mut env_paths, explicit.clone());
             }
 
             for env_path in env_paths {
-                let loaded = load_env_file_sync(&env_path, &mut initial_env)?;
+                let loaded =
+                    load_env_file_tracked(&env_path, &mut initial_env, &mut provenance, |line| {
+                        VariableSource::EnvFile {
+                            path: env_path.clone(),
+                            line,
+                        }
+                    })?;
                 env_files.push(loaded);
             }
+            // Env files take precedence over config variables, commands included
+            command_variables.retain(|key, _| !initial_env.contains_key(key));
 
             if response_output_dir.is_none() {
                 if let Some(dir) = &profile.config.response_output_dir {
@@ -118,7 +176,13 @@ impl EnvironmentBuilder {
 
         if self.config.is_none() {
             if let Some(explicit) = &self.explicit_env {
-                let loaded = load_env_file_sync(explicit, &mut initial_env)?;
+                let loaded =
+                    load_env_file_tracked(explicit, &mut initial_env, &mut provenance, |line| {
+                        VariableSource::EnvFile {
+                            path: explicit.clone(),
+                            line,
+                        }
+                    })?;
                 env_files.push(loaded);
             }
         }
@@ -140,56 +204,35 @@ impl EnvironmentBuilder {
             response_output_dir,
             default_headers,
             template_variants,
+            provenance,
+            command_variables,
         })
     }
 }
 
-fn push_unique(envs: &mut Vec<PathBuf>, candidate: PathBuf) {
-    if !envs.iter().any(|existing| existing == &candidate) {
-        envs.push(candidate);
+fn record_config_variables(
+    provenance: &mut ProvenanceMap,
+    cfg: &LoadedConfig,
+    prefix: &str,
+    variables: &HashMap<String, VariableValue>,
+) {
+    let mut keys: Vec<_> = variables.keys().collect();
+    keys.sort();
+    for key in keys {
+        provenance.record(
+            key,
+            VariableSource::Config {
+                path: cfg.origin_of(&format!("{prefix}.{key}")).to_path_buf(),
+                key: format!("{prefix}.{key}"),
+            },
+        );
     }
 }
 
-struct ResolvedProfile<'a> {
-    name: String,
-    config: &'a CurlpitProfileConfig,
-}
-
-fn resolve_profile<'a>(
-    config: &'a CurlpitConfig,
-    requested: Option<&str>,
-) -> Result<ResolvedProfile<'a>> {
-    if config.profiles.is_empty() {
-        bail!("No profiles defined in configuration");
-    }
-
-    if let Some(name) = requested {
-        if let Some(profile) = config.profiles.get(name) {
-            return Ok(ResolvedProfile {
-                name: name.to_string(),
-                config: profile,
-            });
-        }
-        bail!("Unknown profile: {}", name);
-    }
-
-    if let Some(default) = &config.default_profile {
-        if let Some(profile) = config.profiles.get(default) {
-            return Ok(ResolvedProfile {
-                name: default.to_string(),
-                config: profile,
-            });
-        }
-    }
-
-    if let Some((name, profile)) = config.profiles.iter().next() {
-        return Ok(ResolvedProfile {
-            name: name.to_string(),
-            config: profile,
-        });
+fn push_unique(envs: &mut Vec<PathBuf>, candidate: PathBuf) {
+    if !envs.iter().any(|existing| existing == &candidate) {
+        envs.push(candidate);
     }
-
-    bail!("No profile candidates available");
 }
 
 fn resolve_relative(base: &Path, value: &str) -> PathBuf {
@@ -205,6 +248,7 @@ fn resolve_relative(base: &Path, value: &str) -> PathBuf {
 mod tests {
     use super::*;
     use crate::config::load_config;
+    use crate::env::resolve_variable;
     use anyhow::Result;
     use tempfile::tempdir;
 
@@ -304,6 +348,31 @@ mod tests {
             Some(&"root".to_string())
         );
 
+        assert_eq!(
+            environment.provenance.chain("PROFILE"),
+            &[VariableSource::Config {
+                path: config_dir.join("curlpit.json"),
+                key: "profiles.staging.variables.PROFILE".to_string(),
+            }]
+        );
+        assert_eq!(
+            environment.provenance.chain("TOKEN"),
+            &[
+                VariableSource::EnvFile {
+                    path: config_dir.join("root.env"),
+                    line: Some(2),
+                },
+                VariableSource::EnvFile {
+                    path: config_dir.join("staging.env"),
+                    line: Some(1),
+                },
+                VariableSource::EnvFile {
+                    path: config_dir.join("override.env"),
+                    line: Some(1),
+                },
+            ]
+        );
+
         assert_eq!(environment.env_files.len(), 3);
         assert!(environment.env_files[0].ends_with(Path::new("root.env")));
         assert!(environment.env_files[1].ends_with(Path::new("staging.env")));
@@ -361,6 +430,81 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn environment_builder_applies_profile_inheritance() -> Result<()> {
+        let temp = tempdir()?;
+        let config_dir = temp.path().to_path_buf();
+
+        write_file(
+            &config_dir.join("curlpit.json"),
+            r#"{
+  "profiles": {
+    "base": {
+      "env": "base.env",
+      "variables": {"API_BASE": "https://api.example.com", "REGION": "us"},
+      "defaultHeaders": {"X-Client": "curlpit"},
+      "responseOutputDir": "base-responses"
+    },
+    "staging": {
+      "extends": "base",
+      "env": "staging.env",
+      "variables": {"API_BASE": "https://staging.example.com"}
+    },
+    "staging-eu": {
+      "extends": ["staging"],
+      "variables": {"REGION": "eu"}
+    },
+    "other": {"variables": {"REGION": "ap"}}
+  }
+}
+"#,
+        );
+        write_file(&config_dir.join("base.env"), "TOKEN=base\n");
+        write_file(&config_dir.join("staging.env"), "TOKEN=staging\n");
+
+        let loaded = load_config(&config_dir)?.expect("config should load");
+        let builder = EnvironmentBuilder::new(
+            config_dir.clone(),
+            config_dir.clone(),
+            Some(loaded),
+            Some("staging-eu".to_string()),
+            None,
+            None,
+        );
+
+        let environment = builder.build().await?;
+        assert_eq!(
+            environment.template_variables.get("API_BASE"),
+            Some(&"https://staging.example.com".to_string())
+        );
+        assert_eq!(
+            environment.template_variables.get("REGION"),
+            Some(&"eu".to_string())
+        );
+        assert_eq!(
+            environment.initial_env.get("TOKEN"),
+            Some(&"staging".to_string())
+        );
+        assert_eq!(environment.env_files.len(), 2);
+        assert_eq!(
+            environment.default_headers.get("X-Client"),
+            Some(&"curlpit".to_string())
+        );
+        assert_eq!(
+            environment.response_output_dir,
+            Some(config_dir.join("base-responses"))
+        );
+        assert_eq!(environment.provenance.chain("API_BASE").len(), 2);
+        assert!(environment
+            .template_variants
+            .contains(&("REGION".to_string(), "ap".to_string())));
+        assert!(environment
+            .template_variants
+            .contains(&("REGION".to_string(), "us".to_string())));
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn environment_builder_uses_root_env_when_profile_env_missing() -> Result<()> {
         let temp = tempdir()?;
@@ -406,4 +550,46 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn variable_references_follow_profile_precedence() -> Result<()> {
+        let temp = tempdir()?;
+        let config_dir = temp.path().to_path_buf();
+
+        write_file(
+            &config_dir.join("curlpit.json"),
+            r#"{
+  "variables": {"API_BASE": "https://api.example.com", "API_V2": "{API_BASE}/v2"},
+  "profiles": {
+    "default": {},
+    "local": {"env": "local.env", "variables": {"API_BASE": "http://localhost:{PORT}"}}
+  }
+}
+"#,
+        );
+        write_file(&config_dir.join("local.env"), "PORT=8080\n");
+
+        let build = |profile: &str| {
+            EnvironmentBuilder::new(
+                config_dir.clone(),
+                config_dir.clone(),
+                load_config(&config_dir).unwrap(),
+                Some(profile.to_string()),
+                None,
+                None,
+            )
+        };
+
+        let default = build("default").build().await?;
+        assert_eq!(
+            resolve_variable("API_V2", &default.initial_env)?,
+            "https://api.example.com/v2"
+        );
+        let local = build("local").build().await?;
+        assert_eq!(
+            resolve_variable("API_V2", &local.initial_env)?,
+            "http://localhost:8080/v2"
+        );
+        Ok(())
+    }
 }
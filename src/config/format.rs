@@ -0,0 +1,212 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Error};
+
+use super::loader::CurlpitConfig;
+
+/// Config file names checked in each directory, in priority order.
+pub(crate) const CONFIG_FILE_NAMES: &[&str] = &[
+    "curlpit.json",
+    "curlpit.jsonc",
+    "curlpit.yaml",
+    "curlpit.yml",
+    "curlpit.toml",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ConfigFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl ConfigFormat {
+    /// Picks the format from the file extension; anything unrecognised is read as JSONC.
+    pub(crate) fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("yaml" | "yml") => ConfigFormat::Yaml,
+            Some("toml") => ConfigFormat::Toml,
+            _ => ConfigFormat::Json,
+        }
+    }
+}
+
+/// Deserializes `contents` into a config, reporting syntax and type errors as
+/// `path:line:column: message`.
+pub(crate) fn parse_config_str(contents: &str, path: &Path) -> Result<CurlpitConfig, Error> {
+    match ConfigFormat::from_path(path) {
+        ConfigFormat::Json => {
+            let stripped = strip_jsonc(contents);
+            serde_json::from_str(&stripped)
+                .map_err(|err| located(path, err.line(), err.column(), &err.to_string()))
+        }
+        ConfigFormat::Yaml => serde_yaml::from_str(contents).map_err(|err| {
+            let message = err.to_string();
+            match err.location() {
+                Some(location) => located(path, location.line(), location.column(), &message),
+                None => anyhow!("{}: {message}", path.display()),
+            }
+        }),
+        ConfigFormat::Toml => toml::from_str(contents).map_err(|err| match err.span() {
+            Some(span) => {
+                let (line, column) = line_and_column(contents, span.start);
+                located(path, line, column, err.message())
+            }
+            None => anyhow!("{}: {}", path.display(), err.message()),
+        }),
+    }
+}
+
+fn located(path: &Path, line: usize, column: usize, message: &str) -> Error {
+    let suffix = format!(" at line {line} column {column}");
+    let message = message.strip_suffix(&suffix).unwrap_or(message);
+    anyhow!("{}:{line}:{column}: {message}", path.display())
+}
+
+fn line_and_column(contents: &str, offset: usize) -> (usize, usize) {
+    let before = &contents[..offset.min(contents.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rsplit('\n')
+        .next()
+        .map(|last| last.chars().count())
+        .unwrap_or(0)
+        + 1;
+    (line, column)
+}
+
+/// Blanks out `//` and `/* */` comments and trailing commas so the result parses as JSON.
+///
+/// Removed characters become spaces (newlines are kept) so error positions still point at the
+/// original text.
+pub(crate) fn strip_jsonc(input: &str) -> String {
+    let mut output: Vec<char> = Vec::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    let mut in_string = false;
+    let mut pending_comma: Option<usize> = None;
+
+    while let Some(ch) = chars.next() {
+        if in_string {
+            output.push(ch);
+            match ch {
+                '\\' => {
+                    if let Some(next) = chars.next() {
+                        output.push(next);
+                    }
+                }
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match ch {
+            '/' if chars.peek() == Some(&'/') => {
+                output.push(' ');
+                while let Some(&next) = chars.peek() {
+                    if next == '\n' {
+                        break;
+                    }
+                    output.push(' ');
+                    chars.next();
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                output.push(' ');
+                output.push(' ');
+                chars.next();
+                let mut previous = '\0';
+                for next in chars.by_ref() {
+                    output.push(if next == '\n' { '\n' } else { ' ' });
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            ',' => {
+                pending_comma = Some(output.len());
+                output.push(ch);
+            }
+            '}' | ']' => {
+                if let Some(index) = pending_comma.take() {
+                    output[index] = ' ';
+                }
+                output.push(ch);
+            }
+            '"' => {
+                pending_comma = None;
+                in_string = true;
+                output.push(ch);
+            }
+            ch if ch.is_whitespace() => output.push(ch),
+            _ => {
+                pending_comma = None;
+                output.push(ch);
+            }
+        }
+    }
+
+    output.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn strips_comments_and_trailing_commas() {
+        let input = "{\n  // why\n  \"a\": \"b // c\", /* note */\n  \"d\": [1, 2,],\n}";
+        let stripped = strip_jsonc(input);
+        assert_eq!(stripped.lines().count(), input.lines().count());
+        let value: serde_json::Value = serde_json::from_str(&stripped).unwrap();
+        assert_eq!(value["a"], "b // c");
+        assert_eq!(value["d"], serde_json::json!([1, 2]));
+    }
+
+    #[test]
+    fn parses_yaml_and_toml_into_config() {
+        let yaml = "# platform defaults\ndefaultProfile: dev\nprofiles:\n  dev:\n    variables:\n      API_BASE: http://localhost\n";
+        let config = parse_config_str(yaml, &PathBuf::from("curlpit.yaml")).unwrap();
+        assert_eq!(config.default_profile.as_deref(), Some("dev"));
+        assert_eq!(
+            config.profiles["dev"].variables["API_BASE"],
+            "http://localhost"
+        );
+
+        let toml = "# headers\n[defaultHeaders]\nAccept = \"application/json\"\n\n[profiles.dev.variables]\nAPI_BASE = \"http://localhost\"\n";
+        let config = parse_config_str(toml, &PathBuf::from("curlpit.toml")).unwrap();
+        assert_eq!(config.default_headers["Accept"], "application/json");
+        assert_eq!(
+            config.profiles["dev"].variables["API_BASE"],
+            "http://localhost"
+        );
+    }
+
+    #[test]
+    fn errors_report_line_and_column() {
+        let err = parse_config_str(
+            "{\n  // comment\n  \"variables\": 3\n}",
+            &PathBuf::from("curlpit.json"),
+        )
+        .unwrap_err();
+        assert!(err.to_string().starts_with("curlpit.json:3:"), "{err}");
+
+        let err = parse_config_str(
+            "profiles:\n  dev:\n    variables: [1\n",
+            &PathBuf::from("curlpit.yml"),
+        )
+        .unwrap_err();
+        assert!(err.to_string().starts_with("curlpit.yml:"), "{err}");
+
+        let err =
+            parse_config_str("variables = \"oops\"\n", &PathBuf::from("curlpit.toml")).unwrap_err();
+        assert!(err.to_string().starts_with("curlpit.toml:1:"), "{err}");
+    }
+}
@@ -0,0 +1,213 @@
+use std::collections::{BTreeSet, HashMap};
+
+use super::EnvironmentContext;
+
+/// A variable or header value as shown when inspecting an environment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntryValue {
+    Resolved(String),
+    /// A command variable; inspecting an environment never runs its command.
+    Unresolved {
+        command: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Difference {
+    OnlyLeft(EntryValue),
+    OnlyRight(EntryValue),
+    Changed { left: EntryValue, right: EntryValue },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryDiff {
+    pub key: String,
+    pub difference: Difference,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnvironmentDiff {
+    pub variables: Vec<EntryDiff>,
+    pub default_headers: Vec<EntryDiff>,
+}
+
+impl EnvironmentDiff {
+    pub fn is_empty(&self) -> bool {
+        self.variables.is_empty() && self.default_headers.is_empty()
+    }
+}
+
+/// Compares the resolved variables and default headers of two environments.
+pub fn diff_environments(left: &EnvironmentContext, right: &EnvironmentContext) -> EnvironmentDiff {
+    EnvironmentDiff {
+        variables: diff_maps(&inspect_variables(left), &inspect_variables(right)),
+        default_headers: diff_maps(
+            &resolved_values(&left.default_headers),
+            &resolved_values(&right.default_headers),
+        ),
+    }
+}
+
+/// Lists every variable of `environment`, command variables included but not run.
+pub fn inspect_variables(environment: &EnvironmentContext) -> HashMap<String, EntryValue> {
+    let mut values = resolved_values(&environment.initial_env);
+    for (key, variable) in &environment.command_variables {
+        values.insert(
+            key.clone(),
+            EntryValue::Unresolved {
+                command: variable.command.clone(),
+            },
+        );
+    }
+    values
+}
+
+fn resolved_values(values: &HashMap<String, String>) -> HashMap<String, EntryValue> {
+    values
+        .iter()
+        .map(|(key, value)| (key.clone(), EntryValue::Resolved(value.clone())))
+        .collect()
+}
+
+fn diff_maps(
+    left: &HashMap<String, EntryValue>,
+    right: &HashMap<String, EntryValue>,
+) -> Vec<EntryDiff> {
+    let keys: BTreeSet<&String> = left.keys().chain(right.keys()).collect();
+    keys.into_iter()
+        .filter_map(|key| {
+            let difference = match (left.get(key), right.get(key)) {
+                (Some(l), None) => Difference::OnlyLeft(l.clone()),
+                (None, Some(r)) => Difference::OnlyRight(r.clone()),
+                (Some(l), Some(r)) if l != r => Difference::Changed {
+                    left: l.clone(),
+                    right: r.clone(),
+                },
+                _ => return None,
+            };
+            Some(EntryDiff {
+                key: key.clone(),
+                difference,
+            })
+        })
+        .collect()
+}
+
+/// Masks a value for display, keeping only its length visible.
+pub fn redact(value: &str) -> String {
+    if value.is_empty() {
+        String::new()
+    } else {
+        format!("****** ({} chars)", value.chars().count())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{load_config, EnvironmentBuilder};
+    use anyhow::Result;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn diff_environments_reports_missing_and_changed_entries() -> Result<()> {
+        let temp = tempdir()?;
+        let dir = temp.path();
+        std::fs::write(
+            dir.join("curlpit.json"),
+            r#"{
+  "variables": {"SHARED": "same"},
+  "profiles": {
+    "dev": {
+      "variables": {"API_BASE": "http://localhost", "DEV_ONLY": "1"},
+      "defaultHeaders": {"X-Env": "dev"}
+    },
+    "prod": {
+      "env": "prod.env",
+      "variables": {"API_BASE": "https://api.example.com"},
+      "defaultHeaders": {"X-Env": "prod", "X-Prod": "yes"}
+    }
+  }
+}
+"#,
+        )?;
+        std::fs::write(dir.join("prod.env"), "TOKEN=secret\n")?;
+
+        let loaded = load_config(dir)?.expect("config should load");
+        let build = |profile: &str| {
+            EnvironmentBuilder::new(
+                dir.to_path_buf(),
+                dir.to_path_buf(),
+                Some(loaded.clone()),
+                Some(profile.to_string()),
+                None,
+                None,
+            )
+        };
+        let dev = build("dev").build().await?;
+        let prod = build("prod").build().await?;
+
+        let diff = diff_environments(&dev, &prod);
+        let keys: Vec<_> = diff.variables.iter().map(|d| d.key.as_str()).collect();
+        assert_eq!(keys, vec!["API_BASE", "DEV_ONLY", "TOKEN"]);
+        assert_eq!(
+            diff.variables[2].difference,
+            Difference::OnlyRight(EntryValue::Resolved("secret".to_string()))
+        );
+        assert_eq!(diff.default_headers.len(), 2);
+        assert!(diff_environments(&dev, &dev).is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn diff_environments_lists_command_variables_without_running_them() -> Result<()> {
+        let temp = tempdir()?;
+        let dir = temp.path();
+        std::fs::write(
+            dir.join("curlpit.json"),
+            r#"{
+  "profiles": {
+    "dev": {"variables": {"API_TOKEN": "dev-token"}},
+    "prod": {"variables": {"API_TOKEN": {"command": "touch ran && echo prod-token"}}}
+  }
+}
+"#,
+        )?;
+
+        let loaded = load_config(dir)?.expect("config should load");
+        let build = |profile: &str| {
+            EnvironmentBuilder::new(
+                dir.to_path_buf(),
+                dir.to_path_buf(),
+                Some(loaded.clone()),
+                Some(profile.to_string()),
+                None,
+                None,
+            )
+        };
+        let dev = build("dev").build().await?;
+        let prod = build("prod").build().await?;
+
+        let diff = diff_environments(&dev, &prod);
+        assert_eq!(
+            diff.variables,
+            vec![EntryDiff {
+                key: "API_TOKEN".to_string(),
+                difference: Difference::Changed {
+                    left: EntryValue::Resolved("dev-token".to_string()),
+                    right: EntryValue::Unresolved {
+                        command: "touch ran && echo prod-token".to_string()
+                    },
+                },
+            }]
+        );
+        assert!(!dir.join("ran").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn redact_hides_values() {
+        assert_eq!(redact("secret"), "****** (6 chars)");
+        assert_eq!(redact(""), "");
+    }
+}
@@ -1,64 +1,145 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     fs,
     path::{Path, PathBuf},
 };
 
 use anyhow::{Context, Result};
+use schemars::JsonSchema;
 use serde::Deserialize;
 use serde_json::Value;
 
-#[derive(Debug, Clone, Deserialize, Default)]
+use super::command::VariableValue;
+use super::format::{parse_config_str, CONFIG_FILE_NAMES};
+
+#[derive(Debug, Clone, Deserialize, Default, JsonSchema)]
 #[serde(default)]
 pub struct CurlpitProfileConfig {
+    /// Profile or list of profiles to inherit variables, headers, env files and output
+    /// directories from
+    pub extends: Option<ProfileExtends>,
+    /// Relative path to an env file for this profile
     pub env: Option<String>,
-    pub variables: HashMap<String, String>,
+    /// Template variables available as `{NAME}` placeholders; a value may also be
+    /// `{"command": "...", "ttl": seconds}` to use a command's output
+    pub variables: HashMap<String, VariableValue>,
+    /// Directory where responses for this profile are stored
     #[serde(rename = "responseOutputDir")]
     pub response_output_dir: Option<String>,
+    /// Headers added to every request run with this profile
     #[serde(rename = "defaultHeaders")]
     pub default_headers: HashMap<String, String>,
     #[serde(flatten)]
     pub extras: HashMap<String, Value>,
 }
 
-#[derive(Debug, Clone, Deserialize, Default)]
+/// Parent profile(s) a profile inherits from, merged in the order listed.
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ProfileExtends {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl ProfileExtends {
+    pub fn parents(&self) -> &[String] {
+        match self {
+            ProfileExtends::One(parent) => std::slice::from_ref(parent),
+            ProfileExtends::Many(parents) => parents,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Default, JsonSchema)]
 #[serde(default)]
 pub struct CurlpitConfig {
+    /// Optional reference to the curlpit configuration schema
+    #[serde(rename = "$schema")]
+    pub schema: Option<String>,
+    /// Named sets of variables, env files and headers selected with `--profile`
     pub profiles: HashMap<String, CurlpitProfileConfig>,
-    pub variables: HashMap<String, String>,
+    /// Template variables shared by every profile; a value may also be
+    /// `{"command": "...", "ttl": seconds}` to use a command's output
+    pub variables: HashMap<String, VariableValue>,
+    /// Profile name to use when no --profile flag is provided
     #[serde(rename = "defaultProfile")]
     pub default_profile: Option<String>,
+    /// Directory where response bodies should be stored by default
     #[serde(rename = "responseOutputDir")]
     pub response_output_dir: Option<String>,
+    /// Relative path to an env file applied to all profiles
     pub env: Option<String>,
+    /// Headers added to every request
     #[serde(rename = "defaultHeaders")]
     pub default_headers: HashMap<String, String>,
+    /// Header rules applied when importing curl commands
     #[serde(rename = "import")]
     pub import: Option<ImportConfig>,
+    /// Check GitHub for a newer release when starting the interactive mode
     #[serde(rename = "checkForUpdates")]
     pub check_for_updates: Option<bool>,
+    /// Custom export templates by name, as paths to template files relative to this config
+    #[serde(rename = "exportTemplates")]
+    pub export_templates: HashMap<String, String>,
     #[serde(flatten)]
     pub extras: HashMap<String, Value>,
 }
 
-#[derive(Debug, Clone, Deserialize, Default)]
+#[derive(Debug, Clone, Deserialize, Default, JsonSchema)]
 #[serde(default)]
+// Editors flag typos such as `excludeHeader`; loading still ignores unknown keys
+#[schemars(deny_unknown_fields)]
 pub struct ImportConfig {
+    /// Only keep these headers (case-insensitive) from imported commands
     #[serde(rename = "includeHeaders")]
     pub include_headers: Option<Vec<String>>,
+    /// Drop these headers (case-insensitive) from imported commands
     #[serde(rename = "excludeHeaders")]
     pub exclude_headers: Option<Vec<String>>,
+    /// Headers added to every imported request
     #[serde(rename = "appendHeaders")]
     pub append_headers: HashMap<String, String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct LoadedConfig {
     pub config: CurlpitConfig,
     pub path: PathBuf,
     pub dir: PathBuf,
+    /// Overlay files merged into `config`, outermost first.
+    pub overlays: Vec<PathBuf>,
+    /// Overlay file that last set a dotted config key such as `profiles.dev.variables.TOKEN`.
+    pub key_origins: HashMap<String, PathBuf>,
+    /// Env files contributed by overlays, outermost first, with the profile each belongs to
+    /// (`None` for a root-level `env`). They load after the base config's env files.
+    pub overlay_env_files: Vec<(Option<String>, PathBuf)>,
 }
 
+impl LoadedConfig {
+    /// Returns the file that supplied a dotted config key.
+    pub fn origin_of(&self, key: &str) -> &Path {
+        self.key_origins
+            .get(key)
+            .map(PathBuf::as_path)
+            .unwrap_or(&self.path)
+    }
+
+    /// Custom export templates with their paths resolved against the config directory.
+    pub fn export_templates(&self) -> BTreeMap<String, PathBuf> {
+        self.config
+            .export_templates
+            .iter()
+            .map(|(name, path)| (name.clone(), self.dir.join(path)))
+            .collect()
+    }
+}
+
+/// Loads the configuration for `target`.
+///
+/// A file target is read directly. A directory target is searched for a config file (any of
+/// [`CONFIG_FILE_NAMES`], directly or under `.curlpit/`) in it and every parent directory up
+/// to the repository root (a directory containing `.git`). The outermost config found is the project config; nested ones
+/// are overlays, see [`super::apply_overlays`]. Outside a repository the nearest config is used.
 pub fn load_config(target: &Path) -> Result<Option<LoadedConfig>> {
     let resolved = if target.is_absolute() {
         target.to_path_buf()
@@ -66,40 +147,64 @@ pub fn load_config(target: &Path) -> Result<Option<LoadedConfig>> {
         std::env::current_dir()?.join(target)
     };
 
-    let mut candidates: Vec<(PathBuf, PathBuf)> = Vec::new();
-
-    if resolved.is_dir() {
-        let direct = resolved.join("curlpit.json");
-        candidates.push((direct, resolved.clone()));
-        let nested_dir = resolved.join(".curlpit");
-        candidates.push((nested_dir.join("curlpit.json"), nested_dir));
-    } else {
+    if !resolved.is_dir() {
+        if !resolved.exists() {
+            return Ok(None);
+        }
         let parent = resolved
             .parent()
             .map(|p| p.to_path_buf())
             .unwrap_or_else(|| std::env::current_dir().unwrap());
-        candidates.push((resolved.clone(), parent));
+        return read_config(&resolved, parent).map(Some);
     }
 
-    for (file_path, dir) in candidates {
-        if !file_path.exists() {
-            continue;
+    let in_repository = resolved.ancestors().any(|dir| dir.join(".git").exists());
+    let mut outermost = None;
+    for dir in resolved.ancestors() {
+        let found = match find_config_file(dir) {
+            Some(file_path) => Some((file_path, dir.to_path_buf())),
+            None => {
+                let nested_dir = dir.join(".curlpit");
+                find_config_file(&nested_dir).map(|file_path| (file_path, nested_dir))
+            }
+        };
+        if found.is_some() {
+            outermost = found;
+            if !in_repository {
+                break;
+            }
         }
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
 
-        let contents = fs::read_to_string(&file_path)
-            .with_context(|| format!("reading config {}", file_path.display()))?;
+    outermost
+        .map(|(file_path, dir)| read_config(&file_path, dir))
+        .transpose()
+}
 
-        let config: CurlpitConfig = serde_json::from_str(&contents)
-            .with_context(|| format!("parsing config {}", file_path.display()))?;
+pub(crate) fn find_config_file(dir: &Path) -> Option<PathBuf> {
+    CONFIG_FILE_NAMES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|candidate| candidate.is_file())
+}
 
-        return Ok(Some(LoadedConfig {
-            config,
-            path: file_path,
-            dir,
-        }));
-    }
+pub(crate) fn parse_config_file(file_path: &Path) -> Result<CurlpitConfig> {
+    let contents = fs::read_to_string(file_path)
+        .with_context(|| format!("reading config {}", file_path.display()))?;
+
+    parse_config_str(&contents, file_path)
+}
 
-    Ok(None)
+fn read_config(file_path: &Path, dir: PathBuf) -> Result<LoadedConfig> {
+    Ok(LoadedConfig {
+        config: parse_config_file(file_path)?,
+        path: file_path.to_path_buf(),
+        dir,
+        ..LoadedConfig::default()
+    })
 }
 
 #[cfg(test)]
@@ -143,4 +248,82 @@ mod tests {
         assert!(result.config.profiles.contains_key("local"));
         Ok(())
     }
+
+    #[test]
+    fn walks_up_to_find_config_in_parent_directory() -> Result<()> {
+        let temp = tempdir()?;
+        std::fs::create_dir_all(temp.path().join(".git"))?;
+        let config_path = temp.path().join("curlpit.json");
+        std::fs::write(&config_path, r#"{"profiles":{"root":{}}}"#)?;
+        let nested = temp.path().join("requests/users");
+        std::fs::create_dir_all(&nested)?;
+
+        let result = load_config(&nested)?.expect("config should be discovered");
+        assert_eq!(result.path, config_path);
+        assert_eq!(result.dir, temp.path());
+        Ok(())
+    }
+
+    #[test]
+    fn stops_walking_at_repository_root() -> Result<()> {
+        let temp = tempdir()?;
+        std::fs::write(
+            temp.path().join("curlpit.json"),
+            r#"{"profiles":{"outside":{}}}"#,
+        )?;
+        let repo = temp.path().join("repo");
+        std::fs::create_dir_all(repo.join(".git"))?;
+        std::fs::create_dir_all(repo.join("src"))?;
+
+        assert!(load_config(&repo.join("src"))?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn prefers_outermost_config_within_repository() -> Result<()> {
+        let temp = tempdir()?;
+        std::fs::create_dir_all(temp.path().join(".git"))?;
+        let root_config = temp.path().join("curlpit.json");
+        std::fs::write(&root_config, r#"{"profiles":{"root":{}}}"#)?;
+        let nested = temp.path().join("requests");
+        std::fs::create_dir_all(&nested)?;
+        std::fs::write(nested.join("curlpit.json"), r#"{"variables":{"A":"1"}}"#)?;
+
+        let result = load_config(&nested)?.expect("config should be discovered");
+        assert_eq!(result.path, root_config);
+        Ok(())
+    }
+
+    #[test]
+    fn prefers_nearest_config_outside_repository() -> Result<()> {
+        let temp = tempdir()?;
+        std::fs::write(
+            temp.path().join("curlpit.json"),
+            r#"{"profiles":{"outside":{}}}"#,
+        )?;
+        let project = temp.path().join("project");
+        std::fs::create_dir_all(project.join("requests"))?;
+        let project_config = project.join("curlpit.json");
+        std::fs::write(&project_config, r#"{"profiles":{"project":{}}}"#)?;
+
+        let result = load_config(&project.join("requests"))?.expect("config should be discovered");
+        assert_eq!(result.path, project_config);
+        assert_eq!(result.dir, project);
+        Ok(())
+    }
+
+    #[test]
+    fn loads_yaml_config_from_directory() -> Result<()> {
+        let temp = tempdir()?;
+        let config_path = temp.path().join("curlpit.yaml");
+        std::fs::write(
+            &config_path,
+            "# shared across teams\nprofiles:\n  dev:\n    variables:\n      API_BASE: http://localhost\n",
+        )?;
+
+        let result = load_config(temp.path())?.expect("config should load");
+        assert_eq!(result.path, config_path);
+        assert!(result.config.profiles.contains_key("dev"));
+        Ok(())
+    }
 }
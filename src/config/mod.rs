@@ -1,5 +1,26 @@
+mod check;
+mod command;
+mod edit;
 mod environment;
+mod format;
+mod inspect;
 mod loader;
+mod overlay;
+mod profile;
+mod schema;
 
+pub use check::{check_config, ConfigIssue};
+pub use command::{
+    default_cache_dir, run_command_variable, CommandVariable, DeferredValues, VariableValue,
+    CACHE_DIR_ENV,
+};
+pub use edit::set_json_member;
 pub use environment::{EnvironmentBuilder, EnvironmentContext};
-pub use loader::{load_config, CurlpitConfig, CurlpitProfileConfig, LoadedConfig};
+pub use inspect::{
+    diff_environments, inspect_variables, redact, Difference, EntryDiff, EntryValue,
+    EnvironmentDiff,
+};
+pub use loader::{load_config, CurlpitConfig, CurlpitProfileConfig, LoadedConfig, ProfileExtends};
+pub use overlay::apply_overlays;
+pub use profile::{resolve_profile, ResolvedProfile};
+pub use schema::{config_schema, with_profile_names};
@@ -0,0 +1,295 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use super::loader::{find_config_file, parse_config_file, CurlpitConfig, LoadedConfig};
+
+/// Merges the `curlpit.json` files between the project root and `request_path` into `base`.
+/// `request_path` is a request file or a folder of them; a folder's own config applies too.
+///
+/// Overlays are applied outermost first, so the file closest to the request wins. Variables,
+/// headers and profiles are merged; env files are added after the ones already loaded, and env
+/// and output paths are resolved against the overlay's own directory.
+pub fn apply_overlays(base: &LoadedConfig, request_path: &Path) -> Result<LoadedConfig> {
+    let mut merged = base.clone();
+    let root = project_root(base);
+
+    let mut overlay_files = Vec::new();
+    let start = if request_path.is_dir() {
+        Some(request_path)
+    } else {
+        request_path.parent()
+    };
+    if let Some(start) = start {
+        for dir in start.ancestors() {
+            if !dir.starts_with(&root) || dir == root {
+                break;
+            }
+            if dir == base.dir {
+                continue;
+            }
+            if let Some(file_path) = find_config_file(dir) {
+                if file_path != base.path {
+                    overlay_files.push((file_path, dir.to_path_buf()));
+                }
+            }
+        }
+    }
+
+    for (file_path, dir) in overlay_files.into_iter().rev() {
+        let overlay = parse_config_file(&file_path)?;
+        merge_overlay(&mut merged, overlay, &file_path, &dir);
+    }
+
+    Ok(merged)
+}
+
+fn project_root(config: &LoadedConfig) -> PathBuf {
+    if config
+        .dir
+        .file_name()
+        .is_some_and(|name| name == ".curlpit")
+    {
+        if let Some(parent) = config.dir.parent() {
+            return parent.to_path_buf();
+        }
+    }
+    config.dir.clone()
+}
+
+fn merge_overlay(target: &mut LoadedConfig, overlay: CurlpitConfig, path: &Path, dir: &Path) {
+    for (key, value) in overlay.variables {
+        target
+            .key_origins
+            .insert(format!("variables.{key}"), path.to_path_buf());
+        target.config.variables.insert(key, value);
+    }
+    target
+        .config
+        .default_headers
+        .extend(overlay.default_headers);
+
+    if let Some(env) = overlay.env {
+        target.overlay_env_files.push((None, dir.join(env)));
+    }
+    if let Some(output_dir) = overlay.response_output_dir {
+        target.config.response_output_dir = Some(absolute(dir, &output_dir));
+    }
+    for (name, template) in overlay.export_templates {
+        target
+            .config
+            .export_templates
+            .insert(name, absolute(dir, &template));
+    }
+    if overlay.default_profile.is_some() {
+        target.config.default_profile = overlay.default_profile;
+    }
+
+    for (name, profile) in overlay.profiles {
+        let existing = target.config.profiles.entry(name.clone()).or_default();
+        for (key, value) in profile.variables {
+            target.key_origins.insert(
+                format!("profiles.{name}.variables.{key}"),
+                path.to_path_buf(),
+            );
+            existing.variables.insert(key, value);
+        }
+        existing.default_headers.extend(profile.default_headers);
+        existing.extras.extend(profile.extras);
+        if let Some(env) = profile.env {
+            target
+                .overlay_env_files
+                .push((Some(name.clone()), dir.join(env)));
+        }
+        if let Some(output_dir) = profile.response_output_dir {
+            existing.response_output_dir = Some(absolute(dir, &output_dir));
+        }
+        if profile.extends.is_some() {
+            existing.extends = profile.extends;
+        }
+    }
+
+    target.overlays.push(path.to_path_buf());
+}
+
+fn absolute(dir: &Path, value: &str) -> String {
+    dir.join(value).to_string_lossy().into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{load_config, EnvironmentBuilder};
+    use crate::env::VariableSource;
+    use tempfile::tempdir;
+
+    fn write_file(path: &Path, contents: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn overlays_apply_only_below_their_directory() -> Result<()> {
+        let temp = tempdir()?;
+        let root = temp.path();
+        write_file(
+            &root.join("curlpit.json"),
+            r#"{
+  "variables": {"API_BASE": "https://api.example.com", "TEAM": "core"},
+  "defaultHeaders": {"X-Root": "1"},
+  "profiles": {"dev": {"variables": {"TOKEN": "dev"}}}
+}"#,
+        );
+        write_file(
+            &root.join("requests/billing/curlpit.json"),
+            r#"{
+  "variables": {"TEAM": "billing"},
+  "defaultHeaders": {"X-Team": "billing"},
+  "profiles": {
+    "dev": {"variables": {"ACCOUNT": "acct_1"}},
+    "sandbox": {"env": "sandbox.env"}
+  }
+}"#,
+        );
+        let base = load_config(root)?.expect("config");
+
+        let billing = apply_overlays(&base, &root.join("requests/billing/invoices/list.curl"))?;
+        assert_eq!(billing.config.variables["TEAM"], "billing");
+        assert_eq!(
+            billing.config.variables["API_BASE"],
+            "https://api.example.com"
+        );
+        assert_eq!(billing.config.default_headers.len(), 2);
+        let dev = &billing.config.profiles["dev"];
+        assert_eq!(dev.variables["TOKEN"], "dev");
+        assert_eq!(dev.variables["ACCOUNT"], "acct_1");
+        assert!(billing.config.profiles.contains_key("sandbox"));
+        assert_eq!(
+            billing.overlay_env_files,
+            vec![(
+                Some("sandbox".to_string()),
+                root.join("requests/billing/sandbox.env")
+            )]
+        );
+        assert_eq!(
+            billing.overlays,
+            vec![root.join("requests/billing/curlpit.json")]
+        );
+        assert_eq!(
+            billing.origin_of("variables.TEAM"),
+            root.join("requests/billing/curlpit.json")
+        );
+        assert_eq!(billing.origin_of("variables.API_BASE"), base.path);
+
+        let other = apply_overlays(&base, &root.join("requests/users/get.curl"))?;
+        assert_eq!(other.config.variables["TEAM"], "core");
+        assert!(other.overlays.is_empty());
+        assert!(!other.config.profiles.contains_key("sandbox"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn builder_for_request_loads_overlay_env_and_provenance() -> Result<()> {
+        let temp = tempdir()?;
+        let root = temp.path();
+        write_file(
+            &root.join("curlpit.json"),
+            r#"{"profiles": {"dev": {"variables": {"TOKEN": "root"}}}}"#,
+        );
+        write_file(
+            &root.join("nested/curlpit.json"),
+            r#"{"env": "nested.env", "profiles": {"dev": {"variables": {"TOKEN": "nested"}}}}"#,
+        );
+        write_file(&root.join("nested/nested.env"), "SECRET=shh\n");
+        let base = load_config(root)?;
+
+        let builder = EnvironmentBuilder::new(
+            root.to_path_buf(),
+            root.to_path_buf(),
+            base,
+            None,
+            None,
+            None,
+        )
+        .for_request(&root.join("nested/call.curl"))?;
+        let environment = builder.build().await?;
+
+        assert_eq!(environment.initial_env["TOKEN"], "nested");
+        assert_eq!(environment.initial_env["SECRET"], "shh");
+        assert_eq!(
+            environment.provenance.origin("TOKEN"),
+            Some(&VariableSource::Config {
+                path: root.join("nested/curlpit.json"),
+                key: "profiles.dev.variables.TOKEN".to_string(),
+            })
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn overlay_profile_env_adds_to_the_parent_env() -> Result<()> {
+        let temp = tempdir()?;
+        let root = temp.path();
+        write_file(
+            &root.join("curlpit.json"),
+            r#"{"profiles": {"dev": {"env": "dev.env"}, "prod": {}}}"#,
+        );
+        write_file(&root.join("dev.env"), "TOKEN=root\nSECRET=shh\n");
+        write_file(
+            &root.join("nested/curlpit.json"),
+            r#"{"profiles": {"dev": {"env": "dev.env"}, "prod": {"env": "prod.env"}}}"#,
+        );
+        write_file(&root.join("nested/dev.env"), "TOKEN=nested\n");
+        write_file(&root.join("nested/prod.env"), "TOKEN=prod\n");
+        let base = load_config(root)?;
+
+        let environment = EnvironmentBuilder::new(
+            root.to_path_buf(),
+            root.to_path_buf(),
+            base,
+            Some("dev".to_string()),
+            None,
+            None,
+        )
+        .for_request(&root.join("nested/call.curl"))?
+        .build()
+        .await?;
+
+        assert_eq!(environment.initial_env["TOKEN"], "nested");
+        assert_eq!(environment.initial_env["SECRET"], "shh");
+        assert_eq!(
+            environment.provenance.chain("TOKEN"),
+            [
+                VariableSource::EnvFile {
+                    path: root.join("dev.env"),
+                    line: Some(1),
+                },
+                VariableSource::EnvFile {
+                    path: root.join("nested/dev.env"),
+                    line: Some(1),
+                },
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn a_folder_gets_its_own_overlay() -> Result<()> {
+        let temp = tempdir()?;
+        let root = temp.path();
+        write_file(
+            &root.join("curlpit.json"),
+            r#"{"variables": {"TEAM": "core"}, "profiles": {"dev": {}}}"#,
+        );
+        write_file(
+            &root.join("billing/curlpit.json"),
+            r#"{"variables": {"TEAM": "billing"}}"#,
+        );
+        let base = load_config(root)?.expect("config");
+
+        let folder = apply_overlays(&base, &root.join("billing"))?;
+        assert_eq!(folder.config.variables["TEAM"], "billing");
+        assert_eq!(folder.overlays, vec![root.join("billing/curlpit.json")]);
+        Ok(())
+    }
+}
@@ -0,0 +1,172 @@
+use anyhow::{anyhow, bail, Result};
+
+use super::{CurlpitConfig, CurlpitProfileConfig};
+
+pub struct ResolvedProfile<'a> {
+    pub name: String,
+    /// The profile's configuration with every ancestor merged in.
+    pub config: CurlpitProfileConfig,
+    /// Env files contributed by the profile and its ancestors, in load order.
+    pub env_files: Vec<String>,
+    /// Ancestors followed by the profile itself, in merge order.
+    pub lineage: Vec<(String, &'a CurlpitProfileConfig)>,
+}
+
+/// Picks the requested profile, falling back to `defaultProfile` and then the first profile.
+pub fn resolve_profile<'a>(
+    config: &'a CurlpitConfig,
+    requested: Option<&str>,
+) -> Result<ResolvedProfile<'a>> {
+    if config.profiles.is_empty() {
+        bail!("No profiles defined in configuration");
+    }
+
+    if let Some(name) = requested {
+        if config.profiles.contains_key(name) {
+            return build_profile(config, name);
+        }
+        bail!("Unknown profile: {}", name);
+    }
+
+    if let Some(default) = &config.default_profile {
+        if config.profiles.contains_key(default) {
+            return build_profile(config, default);
+        }
+    }
+
+    if let Some(name) = config.profiles.keys().next() {
+        return build_profile(config, name);
+    }
+
+    bail!("No profile candidates available");
+}
+
+fn build_profile<'a>(config: &'a CurlpitConfig, name: &str) -> Result<ResolvedProfile<'a>> {
+    let mut lineage = Vec::new();
+    collect_lineage(config, name, &mut Vec::new(), &mut lineage)?;
+
+    let mut merged = CurlpitProfileConfig::default();
+    let mut env_files: Vec<String> = Vec::new();
+    for (_, profile) in &lineage {
+        merged.variables.extend(profile.variables.clone());
+        merged
+            .default_headers
+            .extend(profile.default_headers.clone());
+        merged.extras.extend(profile.extras.clone());
+        if let Some(env) = &profile.env {
+            if !env_files.contains(env) {
+                env_files.push(env.clone());
+            }
+            merged.env = Some(env.clone());
+        }
+        if profile.response_output_dir.is_some() {
+            merged.response_output_dir = profile.response_output_dir.clone();
+        }
+    }
+    merged.extends = config.profiles[name].extends.clone();
+
+    Ok(ResolvedProfile {
+        name: name.to_string(),
+        config: merged,
+        env_files,
+        lineage,
+    })
+}
+
+fn collect_lineage<'a>(
+    config: &'a CurlpitConfig,
+    name: &str,
+    stack: &mut Vec<String>,
+    lineage: &mut Vec<(String, &'a CurlpitProfileConfig)>,
+) -> Result<()> {
+    if stack.iter().any(|entry| entry == name) {
+        stack.push(name.to_string());
+        bail!("Profile inheritance cycle: {}", stack.join(" -> "));
+    }
+
+    let profile = config
+        .profiles
+        .get(name)
+        .ok_or_else(|| anyhow!("Unknown profile: {}", name))?;
+
+    stack.push(name.to_string());
+    for parent in profile.extends.iter().flat_map(|extends| extends.parents()) {
+        if !config.profiles.contains_key(parent) {
+            bail!(
+                "Profile {} extends unknown profile {} (via {})",
+                name,
+                parent,
+                stack.join(" -> ")
+            );
+        }
+        collect_lineage(config, parent, stack, lineage)?;
+    }
+    stack.pop();
+
+    if !lineage.iter().any(|(existing, _)| existing == name) {
+        lineage.push((name.to_string(), profile));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> CurlpitConfig {
+        serde_json::from_str(json).expect("valid config")
+    }
+
+    #[test]
+    fn resolve_profile_merges_parents_in_order() -> Result<()> {
+        let config = parse(
+            r#"{"profiles": {
+  "base": {"env": "base.env", "variables": {"A": "base", "B": "base"}},
+  "auth": {"env": "auth.env", "variables": {"B": "auth"}, "responseOutputDir": "auth-out"},
+  "dev": {"extends": ["base", "auth"], "variables": {"C": "dev"}}
+}}"#,
+        );
+
+        let resolved = resolve_profile(&config, Some("dev"))?;
+        let names: Vec<_> = resolved.lineage.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, vec!["base", "auth", "dev"]);
+        assert_eq!(resolved.config.variables["A"], "base");
+        assert_eq!(resolved.config.variables["B"], "auth");
+        assert_eq!(resolved.config.variables["C"], "dev");
+        assert_eq!(resolved.env_files, vec!["base.env", "auth.env"]);
+        assert_eq!(
+            resolved.config.response_output_dir.as_deref(),
+            Some("auth-out")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn resolve_profile_reports_cycles_with_chain() {
+        let config = parse(
+            r#"{"profiles": {
+  "a": {"extends": "b"},
+  "b": {"extends": "c"},
+  "c": {"extends": "a"}
+}}"#,
+        );
+
+        let err = resolve_profile(&config, Some("a"))
+            .err()
+            .expect("cycle should be rejected");
+        assert_eq!(
+            err.to_string(),
+            "Profile inheritance cycle: a -> b -> c -> a"
+        );
+    }
+
+    #[test]
+    fn resolve_profile_rejects_unknown_parents() {
+        let config = parse(r#"{"profiles": {"dev": {"extends": "missing"}}}"#);
+
+        let err = resolve_profile(&config, Some("dev"))
+            .err()
+            .expect("unknown parent should be rejected");
+        assert!(err.to_string().contains("extends unknown profile missing"));
+    }
+}
@@ -0,0 +1,76 @@
+use schemars::gen::SchemaSettings;
+use serde_json::{json, Value};
+
+use super::loader::CurlpitConfig;
+
+const TITLE: &str = "Curlpit Configuration";
+
+/// JSON Schema (draft-07) for curlpit config files, generated from [`CurlpitConfig`].
+pub fn config_schema() -> Value {
+    let generator = SchemaSettings::draft07().into_generator();
+    let mut root = generator.into_root_schema_for::<CurlpitConfig>();
+    root.schema.metadata().title = Some(TITLE.to_string());
+    serde_json::to_value(root).expect("config schema serializes")
+}
+
+/// Restricts `defaultProfile` and `extends` to the given profile names so editors can offer them.
+pub fn with_profile_names(mut schema: Value, names: &[String]) -> Value {
+    if names.is_empty() {
+        return schema;
+    }
+    let names = json!(names);
+
+    if let Some(default_profile) = schema.pointer_mut("/properties/defaultProfile") {
+        default_profile["enum"] = names.clone();
+    }
+    if let Some(Value::Array(variants)) = schema.pointer_mut("/definitions/ProfileExtends/anyOf") {
+        for variant in variants {
+            if variant.get("type") == Some(&json!("array")) {
+                variant["items"]["enum"] = names.clone();
+            } else {
+                variant["enum"] = names.clone();
+            }
+        }
+    }
+    schema
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checked_in_schema_matches_generated() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/schema.json");
+        let checked_in = std::fs::read_to_string(path).unwrap();
+        let generated = serde_json::to_string_pretty(&config_schema()).unwrap() + "\n";
+        assert!(
+            checked_in == generated,
+            "schema.json is out of date; regenerate it with `curlpit schema --out schema.json`"
+        );
+    }
+
+    #[test]
+    fn import_rules_reject_unknown_keys() {
+        let schema = config_schema();
+        assert_eq!(
+            schema["definitions"]["ImportConfig"]["additionalProperties"],
+            json!(false)
+        );
+    }
+
+    #[test]
+    fn profile_names_become_enums() {
+        let schema = with_profile_names(config_schema(), &["dev".to_string(), "prod".to_string()]);
+        assert_eq!(
+            schema["properties"]["defaultProfile"]["enum"],
+            json!(["dev", "prod"])
+        );
+        let variants = schema["definitions"]["ProfileExtends"]["anyOf"]
+            .as_array()
+            .unwrap();
+        assert!(variants
+            .iter()
+            .all(|variant| variant["enum"].is_array() || variant["items"]["enum"].is_array()));
+    }
+}
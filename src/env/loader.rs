@@ -1,23 +1,48 @@
 use std::{
+    cell::Cell,
+    collections::HashMap,
     fs,
-    io::Cursor,
+    io::{self, Read},
     path::{Path, PathBuf},
+    rc::Rc,
 };
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 
-use crate::env::EnvMap;
+use crate::env::{secrets, EnvMap, ProvenanceMap, VariableSource};
 
+/// Loads an env file into `env`, decrypting it first when it is age-encrypted.
 pub fn load_env_file_sync(path: &Path, env: &mut EnvMap) -> Result<PathBuf> {
-    let content =
-        fs::read_to_string(path).with_context(|| format!("reading env file {}", path.display()))?;
-    let iter = dotenvy::from_read_iter(Cursor::new(content));
-
-    for item in iter {
-        let (key, value) = item.with_context(|| format!("parsing env file {}", path.display()))?;
+    let content = read_env_contents(path)?;
+    for (key, value, _) in parse_env(&content, path)? {
         env.insert(key, value);
     }
+    Ok(path.to_path_buf())
+}
 
+/// Loads an env file and records the source of every key it defines.
+///
+/// `source` builds the provenance entry from the line the key was assigned on.
+pub fn load_env_file_tracked(
+    path: &Path,
+    env: &mut EnvMap,
+    provenance: &mut ProvenanceMap,
+    source: impl Fn(Option<usize>) -> VariableSource,
+) -> Result<PathBuf> {
+    // Decrypting is deliberately slow, so values and line numbers share one plaintext
+    let content = read_env_contents(path)?;
+    let mut layer: HashMap<String, (String, usize)> = HashMap::new();
+    for (key, value, line) in parse_env(&content, path)? {
+        layer.insert(key, (value, line));
+    }
+
+    let mut keys: Vec<_> = layer.keys().cloned().collect();
+    keys.sort();
+    for key in keys {
+        let (value, line) = layer.remove(&key).unwrap_or_default();
+        provenance.record(&key, source(Some(line)));
+        env.insert(key, value);
+    }
     Ok(path.to_path_buf())
 }
 
@@ -25,12 +50,87 @@ pub fn load_env_directive(
     path: &Path,
     env: &mut EnvMap,
     env_files: &mut Vec<PathBuf>,
+    provenance: &mut ProvenanceMap,
 ) -> Result<()> {
-    let loaded = load_env_file_sync(path, env)?;
+    let loaded =
+        load_env_file_tracked(path, env, provenance, |line| VariableSource::EnvDirective {
+            path: path.to_path_buf(),
+            line,
+        })?;
     env_files.push(loaded);
     Ok(())
 }
 
+/// Returns the plaintext of an env file; encrypted files need [`secrets::PASSPHRASE_ENV`].
+pub fn read_env_contents(path: &Path) -> Result<String> {
+    let bytes = fs::read(path).with_context(|| format!("reading env file {}", path.display()))?;
+    if !secrets::is_encrypted(&bytes) {
+        return String::from_utf8(bytes)
+            .with_context(|| format!("reading env file {}", path.display()));
+    }
+
+    let passphrase = secrets::passphrase_from_env().ok_or_else(|| {
+        anyhow!(
+            "env file {} is encrypted; set {} to decrypt it",
+            path.display(),
+            secrets::PASSPHRASE_ENV
+        )
+    })?;
+    secrets::decrypt_env(&bytes, &passphrase)
+        .with_context(|| format!("decrypting env file {}", path.display()))
+}
+
+/// Parses env file contents with dotenvy, pairing each assignment with its (1-based) line.
+fn parse_env(content: &str, path: &Path) -> Result<Vec<(String, String, usize)>> {
+    let consumed = Rc::new(Cell::new(0));
+    let reader = LineReader {
+        rest: content.as_bytes(),
+        consumed: Rc::clone(&consumed),
+    };
+    let lines: Vec<&str> = content.lines().collect();
+
+    let mut entries = Vec::new();
+    let mut next_line = 0;
+    for item in dotenvy::from_read_iter(reader) {
+        let (key, value) = item.with_context(|| format!("parsing env file {}", path.display()))?;
+        // dotenvy skips blank and comment lines before the assignment it returns
+        let end = consumed.get();
+        let start = (next_line..end)
+            .find(|&index| {
+                let line = lines.get(index).map_or("", |line| line.trim());
+                !line.is_empty() && !line.starts_with('#')
+            })
+            .unwrap_or(next_line);
+        entries.push((key, value, start + 1));
+        next_line = end;
+    }
+    Ok(entries)
+}
+
+/// Hands out at most one line per `read`, so the lines consumed so far always end at the
+/// assignment dotenvy last returned.
+struct LineReader<'a> {
+    rest: &'a [u8],
+    consumed: Rc<Cell<usize>>,
+}
+
+impl Read for LineReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let line_len = self
+            .rest
+            .iter()
+            .position(|&byte| byte == b'\n')
+            .map_or(self.rest.len(), |index| index + 1);
+        let len = line_len.min(buf.len());
+        buf[..len].copy_from_slice(&self.rest[..len]);
+        self.rest = &self.rest[len..];
+        if len > 0 && len == line_len {
+            self.consumed.set(self.consumed.get() + 1);
+        }
+        Ok(len)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -51,6 +151,74 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn load_env_file_tracked_records_lines() -> Result<()> {
+        let temp = tempdir()?;
+        let env_path = temp.path().join("vars.env");
+        fs::write(
+            &env_path,
+            "# comment\nFOO=first\n\nexport BAR=bar\nFOO=from-file\n",
+        )?;
+
+        let mut env_map = EnvMap::from([("FOO".to_string(), "old".to_string())]);
+        let mut provenance = ProvenanceMap::new();
+        load_env_file_tracked(&env_path, &mut env_map, &mut provenance, |line| {
+            VariableSource::EnvFile {
+                path: env_path.clone(),
+                line,
+            }
+        })?;
+
+        assert_eq!(env_map.get("FOO"), Some(&"from-file".to_string()));
+        assert_eq!(
+            provenance.origin("FOO"),
+            Some(&VariableSource::EnvFile {
+                path: env_path.clone(),
+                line: Some(5)
+            })
+        );
+        assert_eq!(
+            provenance.origin("BAR"),
+            Some(&VariableSource::EnvFile {
+                path: env_path,
+                line: Some(4)
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn load_env_file_tracked_follows_dotenvy_for_multiline_values() -> Result<()> {
+        let temp = tempdir()?;
+        let env_path = temp.path().join("vars.env");
+        fs::write(
+            &env_path,
+            "KEY=\"-----BEGIN\nBAR=not-a-key\n-----END\"\n\n# BAR=commented\nexport BAR=bar\n",
+        )?;
+
+        let mut env_map = EnvMap::new();
+        let mut provenance = ProvenanceMap::new();
+        load_env_file_tracked(&env_path, &mut env_map, &mut provenance, |line| {
+            VariableSource::EnvFile {
+                path: env_path.clone(),
+                line,
+            }
+        })?;
+
+        assert_eq!(
+            env_map.get("KEY"),
+            Some(&"-----BEGIN\nBAR=not-a-key\n-----END".to_string())
+        );
+        assert_eq!(env_map.get("BAR"), Some(&"bar".to_string()));
+        let line = |key| match provenance.origin(key) {
+            Some(VariableSource::EnvFile { line, .. }) => *line,
+            _ => None,
+        };
+        assert_eq!(line("KEY"), Some(1));
+        assert_eq!(line("BAR"), Some(6));
+        Ok(())
+    }
+
     #[test]
     fn load_env_file_sync_propagates_io_errors() {
         let mut env_map = EnvMap::new();
@@ -58,4 +226,40 @@ mod tests {
         let err = load_env_file_sync(&path, &mut env_map).unwrap_err();
         assert!(err.to_string().contains("reading env file"));
     }
+
+    #[test]
+    fn load_env_file_sync_decrypts_encrypted_files() -> Result<()> {
+        let temp = tempdir()?;
+        let env_path = temp.path().join("staging.env.age");
+        let encrypted =
+            secrets::encrypt_env_with_work_factor("TOKEN=shared\n", "correct horse", 2)?;
+        fs::write(&env_path, encrypted)?;
+
+        let mut env_map = EnvMap::new();
+        std::env::remove_var(secrets::PASSPHRASE_ENV);
+        let err = load_env_file_sync(&env_path, &mut env_map).unwrap_err();
+        assert!(err.to_string().contains(secrets::PASSPHRASE_ENV));
+
+        std::env::set_var(secrets::PASSPHRASE_ENV, "correct horse");
+        let mut provenance = ProvenanceMap::new();
+        let result = load_env_file_sync(&env_path, &mut env_map).and_then(|_| {
+            load_env_file_tracked(&env_path, &mut env_map, &mut provenance, |line| {
+                VariableSource::EnvFile {
+                    path: env_path.clone(),
+                    line,
+                }
+            })
+        });
+        std::env::remove_var(secrets::PASSPHRASE_ENV);
+        result?;
+        assert_eq!(env_map.get("TOKEN"), Some(&"shared".to_string()));
+        assert_eq!(
+            provenance.origin("TOKEN"),
+            Some(&VariableSource::EnvFile {
+                path: env_path.clone(),
+                line: Some(1)
+            })
+        );
+        Ok(())
+    }
 }
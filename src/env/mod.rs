@@ -5,7 +5,16 @@ pub type EnvMap = HashMap<String, String>;
 #[cfg(feature = "cli")]
 mod loader;
 mod placeholders;
+#[cfg(feature = "cli")]
+pub mod secrets;
+mod source;
 
 #[cfg(feature = "cli")]
-pub use loader::{load_env_directive, load_env_file_sync};
-pub use placeholders::expand_placeholders;
+pub use loader::{
+    load_env_directive, load_env_file_sync, load_env_file_tracked, read_env_contents,
+};
+pub use placeholders::{
+    expand_placeholders, expand_placeholders_deferred, expand_placeholders_with_usage,
+    resolve_variable, Deferred,
+};
+pub use source::{describe_chain, ProvenanceMap, VariableSource};
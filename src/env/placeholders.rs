@@ -3,6 +3,85 @@ use anyhow::{anyhow, bail, Result};
 use crate::env::EnvMap;
 
 pub fn expand_placeholders(input: &str, env: &EnvMap) -> Result<String> {
+    expand_placeholders_with_usage(input, env, &mut Vec::new())
+}
+
+/// Expands placeholders like `expand_placeholders`, appending each key it resolved to `used`.
+///
+/// Keys already present in `used` are not repeated, so one list can be shared across calls.
+/// Keys reached through another variable's value are reported too.
+pub fn expand_placeholders_with_usage(
+    input: &str,
+    env: &EnvMap,
+    used: &mut Vec<String>,
+) -> Result<String> {
+    expand_placeholders_deferred(input, env, used, &mut |_| None)
+}
+
+/// Expands placeholders like `expand_placeholders_with_usage`, asking `deferred` for keys
+/// missing from `env` before falling back to the process environment.
+///
+/// `deferred` returns `None` for keys it does not know, which lets values that are expensive to
+/// produce be computed only when a placeholder actually references them.
+pub fn expand_placeholders_deferred(
+    input: &str,
+    env: &EnvMap,
+    used: &mut Vec<String>,
+    deferred: &mut Deferred<'_>,
+) -> Result<String> {
+    let mut stack = Vec::new();
+    expand(input, env, used, &mut stack, deferred)
+}
+
+/// Lookup for values computed on first use; see [`expand_placeholders_deferred`].
+pub type Deferred<'a> = dyn FnMut(&str) -> Option<Result<String>> + 'a;
+
+/// Returns the value of `key` with any placeholders inside it resolved.
+///
+/// Values from `env` may reference other variables (`"API_V2": "{API_BASE}/v2"`); these are
+/// resolved against the final map, so whichever source won for `API_BASE` is used. Values from
+/// the process environment are taken literally.
+pub fn resolve_variable(key: &str, env: &EnvMap) -> Result<String> {
+    resolve(key, env, &mut Vec::new(), &mut Vec::new(), &mut |_| None)
+}
+
+fn resolve(
+    key: &str,
+    env: &EnvMap,
+    used: &mut Vec<String>,
+    stack: &mut Vec<String>,
+    deferred: &mut Deferred<'_>,
+) -> Result<String> {
+    if let Some(start) = stack.iter().position(|entry| entry == key) {
+        let mut cycle = stack[start..].to_vec();
+        cycle.push(key.to_string());
+        bail!("Variable reference cycle: {}", cycle.join(" -> "));
+    }
+
+    if let Some(raw) = env.get(key) {
+        stack.push(key.to_string());
+        let value = expand(raw, env, used, stack, deferred)?;
+        stack.pop();
+        return Ok(value);
+    }
+
+    if let Some(value) = deferred(key) {
+        return value;
+    }
+
+    std::env::var(key).map_err(|_| match stack.last() {
+        Some(parent) => anyhow!("Missing template variable: {key} (referenced by {parent})"),
+        None => anyhow!("Missing template variable: {key}"),
+    })
+}
+
+fn expand(
+    input: &str,
+    env: &EnvMap,
+    used: &mut Vec<String>,
+    stack: &mut Vec<String>,
+    deferred: &mut Deferred<'_>,
+) -> Result<String> {
     let mut output = String::with_capacity(input.len());
     let mut chars = input.chars().peekable();
 
@@ -53,11 +132,10 @@ pub fn expand_placeholders(input: &str, env: &EnvMap) -> Result<String> {
                     bail!("Invalid template variable: {key}");
                 }
 
-                let value = env
-                    .get(&key)
-                    .cloned()
-                    .or_else(|| std::env::var(&key).ok())
-                    .ok_or_else(|| anyhow!("Missing template variable: {key}"))?;
+                if !used.contains(&key) {
+                    used.push(key.clone());
+                }
+                let value = resolve(&key, env, used, stack, deferred)?;
                 output.push_str(&value);
             }
             _ => output.push(ch),
@@ -132,10 +210,93 @@ mod tests {
         assert!(err.to_string().contains("Invalid template variable"));
     }
 
+    #[test]
+    fn expand_placeholders_with_usage_reports_keys_once() {
+        let env = EnvMap::from([
+            ("API_BASE".to_string(), "https://example.com".to_string()),
+            ("ID".to_string(), "42".to_string()),
+        ]);
+        let mut used = vec!["ID".to_string()];
+        let rendered = expand_placeholders_with_usage(
+            r"{API_BASE}/\{literal\}/{ID}?again={API_BASE}&json={1}",
+            &env,
+            &mut used,
+        )
+        .unwrap();
+
+        assert_eq!(
+            rendered,
+            "https://example.com/{literal}/42?again=https://example.com&json={1}"
+        );
+        assert_eq!(used, vec!["ID".to_string(), "API_BASE".to_string()]);
+    }
+
     #[test]
     fn expand_placeholders_reports_missing_values() {
         let env = EnvMap::new();
         let err = expand_placeholders("{MISSING}", &env).unwrap_err();
         assert!(err.to_string().contains("Missing template variable"));
     }
+
+    #[test]
+    fn expand_placeholders_resolves_nested_references() {
+        let env = EnvMap::from([
+            ("HOST".to_string(), "example.com".to_string()),
+            ("API_BASE".to_string(), "https://{HOST}".to_string()),
+            ("API_V2".to_string(), "{API_BASE}/v2".to_string()),
+        ]);
+        let mut used = Vec::new();
+        let rendered = expand_placeholders_with_usage("{API_V2}/users", &env, &mut used).unwrap();
+
+        assert_eq!(rendered, "https://example.com/v2/users");
+        assert_eq!(used, vec!["API_V2", "API_BASE", "HOST"]);
+        assert_eq!(
+            resolve_variable("API_V2", &env).unwrap(),
+            "https://example.com/v2"
+        );
+    }
+
+    #[test]
+    fn expand_placeholders_detects_reference_cycles() {
+        let env = EnvMap::from([
+            ("A".to_string(), "{B}".to_string()),
+            ("B".to_string(), "x{C}".to_string()),
+            ("C".to_string(), "{A}".to_string()),
+        ]);
+        let err = expand_placeholders("{A}", &env).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Variable reference cycle: A -> B -> C -> A"
+        );
+    }
+
+    #[test]
+    fn expand_placeholders_names_the_referencing_variable() {
+        let env = EnvMap::from([("API_V2".to_string(), "{NOPE_BASE}/v2".to_string())]);
+        let err = expand_placeholders("{API_V2}", &env).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Missing template variable: NOPE_BASE (referenced by API_V2)"
+        );
+    }
+
+    #[test]
+    fn expand_placeholders_deferred_only_asks_for_missing_keys() {
+        let env = EnvMap::from([("API_BASE".to_string(), "https://example.com".to_string())]);
+        let mut asked = Vec::new();
+        let mut deferred = |key: &str| {
+            asked.push(key.to_string());
+            (key == "TOKEN").then(|| Ok("from-command".to_string()))
+        };
+        let rendered = expand_placeholders_deferred(
+            "{API_BASE}?token={TOKEN}",
+            &env,
+            &mut Vec::new(),
+            &mut deferred,
+        )
+        .unwrap();
+
+        assert_eq!(rendered, "https://example.com?token=from-command");
+        assert_eq!(asked, vec!["TOKEN"]);
+    }
 }
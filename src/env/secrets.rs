@@ -0,0 +1,152 @@
+use std::io::Cursor;
+
+use age::{scrypt, secrecy::SecretString};
+use anyhow::{anyhow, Context, Result};
+
+/// Environment variable holding the passphrase for encrypted env files.
+pub const PASSPHRASE_ENV: &str = "CURLPIT_SECRETS_PASSPHRASE";
+
+const ARMOR_HEADER: &str = "-----BEGIN AGE ENCRYPTED FILE-----";
+const BINARY_HEADER: &str = "age-encryption.org/";
+
+/// Whether `contents` is an age-encrypted file (armored or binary).
+pub fn is_encrypted(contents: &[u8]) -> bool {
+    let trimmed = contents.trim_ascii_start();
+    trimmed.starts_with(ARMOR_HEADER.as_bytes()) || trimmed.starts_with(BINARY_HEADER.as_bytes())
+}
+
+/// Reads the secrets passphrase from [`PASSPHRASE_ENV`].
+pub fn passphrase_from_env() -> Option<String> {
+    std::env::var(PASSPHRASE_ENV)
+        .ok()
+        .filter(|value| !value.is_empty())
+}
+
+/// Decrypts a passphrase-encrypted env file.
+pub fn decrypt_env(ciphertext: &[u8], passphrase: &str) -> Result<String> {
+    let identity = scrypt::Identity::new(SecretString::from(passphrase.to_string()));
+    let plaintext = age::decrypt(&identity, ciphertext).map_err(|err| match err {
+        age::DecryptError::DecryptionFailed
+        | age::DecryptError::KeyDecryptionFailed
+        | age::DecryptError::NoMatchingKeys => anyhow!("wrong passphrase"),
+        other => anyhow!(other),
+    })?;
+    String::from_utf8(plaintext).context("decrypted env file is not valid UTF-8")
+}
+
+/// Encrypts env file contents with a passphrase, producing ASCII-armored text.
+pub fn encrypt_env(plaintext: &str, passphrase: &str) -> Result<String> {
+    let recipient = scrypt::Recipient::new(SecretString::from(passphrase.to_string()));
+    Ok(age::encrypt_and_armor(&recipient, plaintext.as_bytes())?)
+}
+
+/// Like [`encrypt_env`] with an explicit scrypt work factor (`N = 2^log_n`).
+///
+/// Meant for tests; the default work factor targets about a second per operation.
+pub fn encrypt_env_with_work_factor(
+    plaintext: &str,
+    passphrase: &str,
+    log_n: u8,
+) -> Result<String> {
+    let mut recipient = scrypt::Recipient::new(SecretString::from(passphrase.to_string()));
+    recipient.set_work_factor(log_n);
+    Ok(age::encrypt_and_armor(&recipient, plaintext.as_bytes())?)
+}
+
+/// Looks up `key` in env file contents.
+pub fn get_assignment(content: &str, key: &str) -> Result<Option<String>> {
+    for item in dotenvy::from_read_iter(Cursor::new(content)) {
+        let (name, value) = item?;
+        if name == key {
+            return Ok(Some(value));
+        }
+    }
+    Ok(None)
+}
+
+/// Replaces the assignment of `key` in env file contents, or appends one.
+pub fn set_assignment(content: &str, key: &str, value: &str) -> String {
+    let assignment = format!("{key}={}", quote_value(value));
+    let mut replaced = false;
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        let trimmed = trimmed.strip_prefix("export ").unwrap_or(trimmed);
+        let assigns_key = trimmed
+            .split_once('=')
+            .is_some_and(|(name, _)| name.trim() == key);
+        if assigns_key {
+            if !replaced {
+                lines.push(assignment.clone());
+                replaced = true;
+            }
+        } else {
+            lines.push(line.to_string());
+        }
+    }
+    if !replaced {
+        lines.push(assignment);
+    }
+    lines.join("\n") + "\n"
+}
+
+fn quote_value(value: &str) -> String {
+    let plain = value
+        .chars()
+        .all(|ch| ch.is_ascii_alphanumeric() || "-_./:@+,".contains(ch));
+    if plain && !value.is_empty() {
+        return value.to_string();
+    }
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('$', "\\$")
+        .replace('\n', "\\n");
+    format!("\"{escaped}\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_encrypted_env() -> Result<()> {
+        let encrypted = encrypt_env_with_work_factor("TOKEN=abc\n", "hunter2", 2)?;
+        assert!(is_encrypted(encrypted.as_bytes()));
+        assert!(!is_encrypted(b"TOKEN=abc\n"));
+
+        assert_eq!(decrypt_env(encrypted.as_bytes(), "hunter2")?, "TOKEN=abc\n");
+        let err = decrypt_env(encrypted.as_bytes(), "wrong").unwrap_err();
+        assert_eq!(err.to_string(), "wrong passphrase");
+        Ok(())
+    }
+
+    #[test]
+    fn set_assignment_replaces_or_appends() -> Result<()> {
+        let content = "# staging\nexport TOKEN=old\nOTHER=1\nTOKEN=dup\n";
+        let updated = set_assignment(content, "TOKEN", "new value \"quoted\"");
+        assert_eq!(
+            updated,
+            "# staging\nTOKEN=\"new value \\\"quoted\\\"\"\nOTHER=1\n"
+        );
+        assert_eq!(
+            get_assignment(&updated, "TOKEN")?.as_deref(),
+            Some("new value \"quoted\"")
+        );
+
+        let appended = set_assignment("OTHER=1", "API_KEY", "k-123");
+        assert_eq!(appended, "OTHER=1\nAPI_KEY=k-123\n");
+        assert_eq!(get_assignment(&appended, "MISSING")?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn set_assignment_round_trips_special_characters() -> Result<()> {
+        for value in ["pa$HOME_Xword", "a b$", "it's", r"back\slash", "${X}'\"\n"] {
+            let updated = set_assignment("OTHER=1\n", "TOKEN", value);
+            assert_eq!(get_assignment(&updated, "TOKEN")?.as_deref(), Some(value));
+            assert_eq!(get_assignment(&updated, "OTHER")?.as_deref(), Some("1"));
+        }
+        Ok(())
+    }
+}
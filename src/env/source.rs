@@ -0,0 +1,128 @@
+use std::{collections::HashMap, fmt, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Where a resolved variable value came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum VariableSource {
+    /// A `variables` entry in curlpit.json; `key` is the dotted path to it.
+    Config { path: PathBuf, key: String },
+    /// An env file referenced by the configuration or `--env`.
+    EnvFile { path: PathBuf, line: Option<usize> },
+    /// An env file loaded through an `@env` directive in the request.
+    EnvDirective { path: PathBuf, line: Option<usize> },
+    /// The process environment.
+    ProcessEnv,
+}
+
+impl fmt::Display for VariableSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VariableSource::Config { path, key } => write!(f, "{} at {key}", path.display()),
+            VariableSource::EnvFile { path, line } => {
+                write!(f, "env file {}", path.display())?;
+                write_line(f, *line)
+            }
+            VariableSource::EnvDirective { path, line } => {
+                write!(f, "@env {}", path.display())?;
+                write_line(f, *line)
+            }
+            VariableSource::ProcessEnv => write!(f, "process env"),
+        }
+    }
+}
+
+fn write_line(f: &mut fmt::Formatter<'_>, line: Option<usize>) -> fmt::Result {
+    match line {
+        Some(line) => write!(f, ":{line}"),
+        None => Ok(()),
+    }
+}
+
+/// Records every source that assigned a variable, in the order they were applied.
+///
+/// The last entry of a chain is the source whose value won; earlier entries were overridden.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ProvenanceMap {
+    chains: HashMap<String, Vec<VariableSource>>,
+}
+
+impl ProvenanceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, key: &str, source: VariableSource) {
+        self.chains.entry(key.to_string()).or_default().push(source);
+    }
+
+    pub fn chain(&self, key: &str) -> &[VariableSource] {
+        self.chains.get(key).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    pub fn origin(&self, key: &str) -> Option<&VariableSource> {
+        self.chain(key).last()
+    }
+}
+
+/// Formats a chain with the winning source first, followed by the sources it overrode.
+pub fn describe_chain(chain: &[VariableSource]) -> String {
+    let mut parts = chain.iter().rev().map(|source| source.to_string());
+    let Some(winner) = parts.next() else {
+        return String::from("unknown");
+    };
+    let overridden: Vec<String> = parts.collect();
+    if overridden.is_empty() {
+        winner
+    } else {
+        format!("{winner}, overrides {}", overridden.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn provenance_map_keeps_override_order() {
+        let mut provenance = ProvenanceMap::new();
+        provenance.record(
+            "TOKEN",
+            VariableSource::Config {
+                path: PathBuf::from("curlpit.json"),
+                key: "variables.TOKEN".to_string(),
+            },
+        );
+        provenance.record(
+            "TOKEN",
+            VariableSource::EnvFile {
+                path: PathBuf::from(".env"),
+                line: Some(3),
+            },
+        );
+
+        assert_eq!(provenance.chain("TOKEN").len(), 2);
+        assert_eq!(
+            describe_chain(provenance.chain("TOKEN")),
+            "env file .env:3, overrides curlpit.json at variables.TOKEN"
+        );
+        assert!(provenance.origin("MISSING").is_none());
+    }
+
+    #[test]
+    fn provenance_map_round_trips_through_json() -> serde_json::Result<()> {
+        let json = r#"{"TOKEN":[{"kind":"envFile","path":"variables.env","line":2}]}"#;
+        let provenance: ProvenanceMap = serde_json::from_str(json)?;
+        assert_eq!(
+            provenance.origin("TOKEN"),
+            Some(&VariableSource::EnvFile {
+                path: PathBuf::from("variables.env"),
+                line: Some(2),
+            })
+        );
+        assert_eq!(serde_json::to_string(&provenance)?, json);
+        Ok(())
+    }
+}
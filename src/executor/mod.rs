@@ -3,6 +3,8 @@ mod printer;
 mod runner;
 mod writer;
 
-pub use models::{ExecutionOptions, ExecutionResult, RequestSummary, ResponseSummary};
-pub use printer::print_execution_result;
-pub use runner::execute_request_file;
+pub use models::{
+    DryRunResult, ExecutionOptions, ExecutionResult, RequestSummary, ResponseSummary,
+};
+pub use printer::{print_dry_run_result, print_execution_result};
+pub use runner::{dry_run_request_file, execute_request_file};
@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 
 use crate::config::EnvironmentContext;
+use crate::parser::{RequestDefinition, ResolvedPlaceholder};
 
 pub struct ExecutionOptions<'a> {
     pub preview_bytes: Option<usize>,
@@ -28,3 +29,9 @@ pub struct ResponseSummary {
     pub body_bytes: usize,
     pub preview: Option<String>,
 }
+
+pub struct DryRunResult {
+    pub request: RequestDefinition,
+    pub placeholders: Vec<ResolvedPlaceholder>,
+    pub env_files: Vec<PathBuf>,
+}
@@ -1,7 +1,9 @@
 use colored::{Color, Colorize};
 use url::Url;
 
-use super::models::ExecutionResult;
+use crate::env::describe_chain;
+
+use super::models::{DryRunResult, ExecutionResult};
 
 pub fn print_execution_result(result: &ExecutionResult) {
     let status_color = if result.response.status >= 400 {
@@ -32,19 +34,7 @@ pub fn print_execution_result(result: &ExecutionResult) {
         );
     }
 
-    if !result.env_files.is_empty() {
-        let files = result
-            .env_files
-            .iter()
-            .map(|p| {
-                p.file_name()
-                    .map(|n| n.to_string_lossy().to_string())
-                    .unwrap_or_else(|| p.display().to_string())
-            })
-            .collect::<Vec<_>>()
-            .join(", ");
-        println!("{} {}", "Env:".bold(), files.dimmed());
-    }
+    print_env_files(&result.env_files);
 
     println!("{}", "Response headers".bold());
     for (name, value) in &result.response.headers {
@@ -64,6 +54,66 @@ pub fn print_execution_result(result: &ExecutionResult) {
     }
 }
 
+pub fn print_dry_run_result(result: &DryRunResult) {
+    println!("{}", "Dry run: request not sent".yellow().bold());
+    println!(
+        "{} {}",
+        result.request.method.bold(),
+        result.request.url.cyan()
+    );
+
+    println!("{}", "Request headers".bold());
+    for (name, value) in &result.request.headers {
+        println!("  {}: {}", name.cyan(), value.dimmed());
+    }
+
+    if let Some(path) = &result.request.body_file {
+        println!(
+            "{} {} {}",
+            "Body file:".bold(),
+            path.display().to_string().cyan(),
+            format!("({} bytes)", result.request.body_bytes.unwrap_or(0)).dimmed()
+        );
+    } else if let Some(text) = &result.request.body_text {
+        println!(
+            "{} {}",
+            "Body:".bold(),
+            format!("({} bytes)", text.len()).dimmed()
+        );
+        println!("{}", text);
+    }
+
+    if !result.placeholders.is_empty() {
+        println!("{}", "Placeholders".bold());
+        for placeholder in &result.placeholders {
+            println!(
+                "  {} = {} {}",
+                placeholder.key.cyan(),
+                placeholder.value,
+                format!("({})", describe_chain(&placeholder.chain)).dimmed()
+            );
+        }
+    }
+
+    print_env_files(&result.env_files);
+}
+
+fn print_env_files(env_files: &[std::path::PathBuf]) {
+    if env_files.is_empty() {
+        return;
+    }
+    let files = env_files
+        .iter()
+        .map(|p| {
+            p.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| p.display().to_string())
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    println!("{} {}", "Env:".bold(), files.dimmed());
+}
+
 fn format_body_link(path: &std::path::Path) -> String {
     let display = path.to_string_lossy();
     match Url::from_file_path(path) {
@@ -75,7 +125,9 @@ fn format_body_link(path: &std::path::Path) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::env::VariableSource;
     use crate::executor::models::{ExecutionResult, RequestSummary, ResponseSummary};
+    use crate::parser::{RequestBody, RequestDefinition, ResolvedPlaceholder};
     use tempfile::tempdir;
 
     #[test]
@@ -148,4 +200,27 @@ mod tests {
 
         print_execution_result(&result);
     }
+
+    #[test]
+    fn print_dry_run_result_handles_bodies_and_placeholders() {
+        let result = DryRunResult {
+            request: RequestDefinition {
+                method: "POST".to_string(),
+                url: "https://example.com/items".to_string(),
+                headers: vec![("accept".to_string(), "application/json".to_string())],
+                body: Some(RequestBody::Text("{}".to_string())),
+                body_bytes: Some(2),
+                body_text: Some("{}".to_string()),
+                body_file: None,
+            },
+            placeholders: vec![ResolvedPlaceholder {
+                key: "API_BASE".to_string(),
+                value: "https://example.com".to_string(),
+                chain: vec![VariableSource::ProcessEnv],
+            }],
+            env_files: Vec::new(),
+        };
+
+        print_dry_run_result(&result);
+    }
 }
@@ -3,10 +3,11 @@ use std::time::Instant;
 use anyhow::{Context, Result};
 use reqwest::{header::HeaderMap, Client, Method};
 
+use crate::config::EnvironmentContext;
 use crate::parser::{parse_request_file, ParsedRequest, RequestBody};
 
 use super::{
-    models::{ExecutionOptions, ExecutionResult, RequestSummary, ResponseSummary},
+    models::{DryRunResult, ExecutionOptions, ExecutionResult, RequestSummary, ResponseSummary},
     writer::{create_preview, write_response_body},
 };
 
@@ -18,6 +19,19 @@ pub async fn execute_request_file(
     execute_parsed_request(parsed, path, options).await
 }
 
+/// Resolves a request exactly as `execute_request_file` would, without sending it.
+pub async fn dry_run_request_file(
+    path: &std::path::Path,
+    environment: &EnvironmentContext,
+) -> Result<DryRunResult> {
+    let parsed = parse_request_file(path, environment).await?;
+    Ok(DryRunResult {
+        request: parsed.request,
+        placeholders: parsed.placeholders,
+        env_files: parsed.env_files,
+    })
+}
+
 async fn execute_parsed_request(
     parsed: ParsedRequest,
     request_file: &std::path::Path,
@@ -123,6 +137,49 @@ mod tests {
             .any(|(name, value)| name == "content-type" && value == "application/json"));
     }
 
+    #[tokio::test]
+    async fn dry_run_request_file_resolves_without_sending() -> Result<()> {
+        let server = MockServer::start_async().await;
+        let mock = server
+            .mock_async(|when, then| {
+                when.any_request();
+                then.status(200);
+            })
+            .await;
+
+        let temp = tempdir()?;
+        let env_path = temp.path().join("local.env");
+        std::fs::write(&env_path, format!("API_BASE={}\n", server.url("")))?;
+        let request_path = temp.path().join("sample.curl");
+        std::fs::write(&request_path, "POST {API_BASE}/items\n\n{\"ok\":true}\n")?;
+
+        let builder = EnvironmentBuilder::new(
+            temp.path().to_path_buf(),
+            temp.path().to_path_buf(),
+            None,
+            None,
+            Some(env_path.clone()),
+            None,
+        );
+        let environment = builder.build().await?;
+
+        let result = dry_run_request_file(&request_path, &environment).await?;
+
+        assert_eq!(result.request.method, "POST");
+        assert_eq!(result.request.url, server.url("/items"));
+        assert_eq!(result.request.body_text.as_deref(), Some("{\"ok\":true}"));
+        assert_eq!(result.placeholders.len(), 1);
+        assert_eq!(
+            result.placeholders[0].chain,
+            vec![crate::env::VariableSource::EnvFile {
+                path: env_path,
+                line: Some(1)
+            }]
+        );
+        mock.assert_hits_async(0).await;
+        Ok(())
+    }
+
     #[tokio::test]
     async fn execute_request_file_writes_preview_and_body() -> Result<()> {
         let server = MockServer::start_async().await;
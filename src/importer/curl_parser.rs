@@ -3,11 +3,8 @@ use std::str::FromStr;
 use anyhow::{anyhow, Result};
 use curl_parser::ParsedRequest;
 
-use super::headers::apply_header_rules;
 use super::model::{ImportOptions, ImportResult};
-use super::substitutions::{
-    apply_substitutions, build_substitutions, format_curl_contents, suggest_file_name,
-};
+use super::substitutions::{finish_import, ImportedRequest};
 
 pub(crate) fn import_via_curl_parser(options: &ImportOptions<'_>) -> Result<ImportResult> {
     let parsed = ParsedRequest::from_str(options.command.trim())
@@ -38,42 +35,18 @@ pub(crate) fn import_via_curl_parser(options: &ImportOptions<'_>) -> Result<Impo
         Some(parsed.body.join("\n"))
     };
 
-    let substitutions = build_substitutions(
-        options.template_variables,
-        options.env_variables,
-        options.template_variants,
-    );
-
-    let substituted_url = apply_substitutions(&url, &substitutions);
-    let substituted_headers: Vec<(String, String)> = headers
-        .iter()
-        .map(|(name, value)| (name.clone(), apply_substitutions(value, &substitutions)))
-        .collect();
-    let final_headers = apply_header_rules(
-        substituted_headers,
-        options.include_headers,
-        options.exclude_headers,
-        options.append_headers,
-    );
-    let substituted_body = body_text
-        .as_ref()
-        .map(|body| apply_substitutions(body, &substitutions));
-
-    let contents = format_curl_contents(
-        &method,
-        &substituted_url,
-        &final_headers,
-        substituted_body.as_deref(),
-        &warnings,
-    );
-
-    Ok(ImportResult {
-        contents,
-        suggested_filename: suggest_file_name(&method, &url),
-        method,
-        url: substituted_url,
-        warnings,
-    })
+    Ok(finish_import(
+        ImportedRequest {
+            name: None,
+            method,
+            url,
+            headers,
+            body_text,
+            body_file: None,
+            warnings,
+        },
+        &options.rules(),
+    ))
 }
 
 #[cfg(test)]
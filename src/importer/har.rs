@@ -0,0 +1,297 @@
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+
+use super::model::{ImportResult, ImportRules};
+use super::substitutions::{encode_pairs, finish_import, ImportedRequest};
+
+#[derive(Deserialize)]
+struct Har {
+    log: Log,
+}
+
+#[derive(Deserialize)]
+struct Log {
+    #[serde(default)]
+    entries: Vec<Entry>,
+}
+
+#[derive(Deserialize)]
+struct Entry {
+    request: Request,
+    #[serde(default)]
+    response: Response,
+}
+
+#[derive(Deserialize)]
+struct Request {
+    method: String,
+    url: String,
+    #[serde(default)]
+    headers: Vec<Pair>,
+    #[serde(rename = "postData")]
+    post_data: Option<PostData>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Response {
+    content: Content,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Content {
+    #[serde(rename = "mimeType")]
+    mime_type: String,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct PostData {
+    #[serde(rename = "mimeType")]
+    mime_type: String,
+    text: Option<String>,
+    params: Vec<Pair>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Pair {
+    name: String,
+    value: String,
+    #[serde(rename = "fileName")]
+    file_name: Option<String>,
+}
+
+/// Limits which HAR entries are imported. Empty filters match every entry.
+#[derive(Debug, Clone, Default)]
+pub struct HarFilter {
+    /// Searched for in the request URL
+    pub url: Option<Regex>,
+    /// Request methods, case-insensitive
+    pub methods: Vec<String>,
+    /// Substrings of the response content type, case-insensitive (`json`, `text/html`)
+    pub content_types: Vec<String>,
+}
+
+impl HarFilter {
+    pub fn is_empty(&self) -> bool {
+        self.url.is_none() && self.methods.is_empty() && self.content_types.is_empty()
+    }
+
+    fn matches(&self, entry: &Entry) -> bool {
+        let mime_type = entry.response.content.mime_type.to_ascii_lowercase();
+        self.url
+            .as_ref()
+            .is_none_or(|pattern| pattern.is_match(&entry.request.url))
+            && (self.methods.is_empty()
+                || self
+                    .methods
+                    .iter()
+                    .any(|method| method.eq_ignore_ascii_case(&entry.request.method)))
+            && (self.content_types.is_empty()
+                || self
+                    .content_types
+                    .iter()
+                    .any(|content_type| mime_type.contains(&content_type.to_ascii_lowercase())))
+    }
+}
+
+/// Imports the HTTP(S) entries of a HAR capture that match `filter`. File names are numbered
+/// so the requests keep the order they were sent in.
+pub fn import_har(
+    contents: &str,
+    filter: &HarFilter,
+    rules: &ImportRules<'_>,
+) -> Result<Vec<ImportResult>> {
+    let har: Har = serde_json::from_str(contents).context("parsing HAR file")?;
+    let entries: Vec<&Entry> = har
+        .log
+        .entries
+        .iter()
+        .filter(|entry| {
+            entry.request.url.starts_with("http://") || entry.request.url.starts_with("https://")
+        })
+        .filter(|entry| filter.matches(entry))
+        .collect();
+    if entries.is_empty() {
+        bail!("No HAR entries match the filters");
+    }
+
+    let width = entries.len().to_string().len().max(3);
+    Ok(entries
+        .into_iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let mut result = finish_import(convert(&entry.request), rules);
+            result.suggested_filename = result
+                .suggested_filename
+                .map(|name| format!("{:0width$}-{name}", index + 1));
+            result
+        })
+        .collect())
+}
+
+fn convert(request: &Request) -> ImportedRequest {
+    let mut warnings = Vec::new();
+    // HTTP/2 pseudo-headers describe the request line, and lengths are computed when sending
+    let mut headers: Vec<(String, String)> = request
+        .headers
+        .iter()
+        .filter(|header| {
+            !header.name.starts_with(':') && !header.name.eq_ignore_ascii_case("content-length")
+        })
+        .map(|header| (header.name.clone(), header.value.clone()))
+        .collect();
+
+    let mut body_text = None;
+    if let Some(post_data) = &request.post_data {
+        if post_data
+            .params
+            .iter()
+            .any(|param| param.file_name.is_some())
+            || post_data.mime_type.starts_with("multipart/")
+        {
+            warnings.push("multipart form data was not imported".to_string());
+            headers.retain(|(name, _)| !name.eq_ignore_ascii_case("content-type"));
+        } else if let Some(text) = post_data.text.as_ref().filter(|text| !text.is_empty()) {
+            body_text = Some(text.clone());
+        } else if !post_data.params.is_empty() {
+            let pairs: Vec<(String, String)> = post_data
+                .params
+                .iter()
+                .map(|param| (param.name.clone(), param.value.clone()))
+                .collect();
+            body_text = Some(encode_pairs(&pairs));
+        }
+        let has_content_type = headers
+            .iter()
+            .any(|(name, _)| name.eq_ignore_ascii_case("content-type"));
+        if body_text.is_some() && !has_content_type && !post_data.mime_type.is_empty() {
+            headers.push(("Content-Type".to_string(), post_data.mime_type.clone()));
+        }
+    }
+
+    ImportedRequest {
+        name: None,
+        method: request.method.to_uppercase(),
+        url: request.url.clone(),
+        headers,
+        body_text,
+        body_file: None,
+        warnings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use once_cell::sync::Lazy;
+    use std::collections::HashMap;
+
+    static TEMPLATE: Lazy<HashMap<String, String>> = Lazy::new(|| {
+        HashMap::from([(
+            "API_BASE".to_string(),
+            "https://api.example.com".to_string(),
+        )])
+    });
+    static ENV: Lazy<HashMap<String, String>> =
+        Lazy::new(|| HashMap::from([("API_TOKEN".to_string(), "secret-token".to_string())]));
+    static EXCLUDE: Lazy<Vec<String>> = Lazy::new(|| vec!["user-agent".to_string()]);
+
+    fn rules() -> ImportRules<'static> {
+        ImportRules {
+            template_variables: &TEMPLATE,
+            env_variables: &ENV,
+            template_variants: &[],
+            include_headers: None,
+            exclude_headers: Some(&EXCLUDE),
+            append_headers: None,
+        }
+    }
+
+    const HAR: &str = r#"{
+  "log": {
+    "version": "1.2",
+    "entries": [
+      {
+        "request": {
+          "method": "GET",
+          "url": "https://api.example.com/users?page=2",
+          "headers": [
+            {"name": ":authority", "value": "api.example.com"},
+            {"name": "Authorization", "value": "Bearer secret-token"},
+            {"name": "User-Agent", "value": "Mozilla/5.0"}
+          ]
+        },
+        "response": {"status": 200, "content": {"mimeType": "application/json; charset=utf-8"}}
+      },
+      {
+        "request": {"method": "GET", "url": "https://cdn.example.com/app.css", "headers": []},
+        "response": {"status": 200, "content": {"mimeType": "text/css"}}
+      },
+      {
+        "request": {
+          "method": "POST",
+          "url": "https://api.example.com/login",
+          "headers": [{"name": "Content-Length", "value": "24"}],
+          "postData": {"mimeType": "application/x-www-form-urlencoded", "params": [
+            {"name": "user", "value": "demo user"}
+          ]}
+        },
+        "response": {"status": 302, "content": {"mimeType": ""}}
+      },
+      {
+        "request": {"method": "GET", "url": "data:image/png;base64,AAAA", "headers": []},
+        "response": {"content": {"mimeType": "image/png"}}
+      }
+    ]
+  }
+}"#;
+
+    #[test]
+    fn imports_entries_in_order_with_substitutions() -> Result<()> {
+        let results = import_har(HAR, &HarFilter::default(), &rules())?;
+        let names: Vec<_> = results
+            .iter()
+            .map(|result| result.suggested_filename.as_deref().unwrap_or_default())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "001-get-api-example-com-users.curl",
+                "002-get-cdn-example-com-app-css.curl",
+                "003-post-api-example-com-login.curl",
+            ]
+        );
+        assert!(results[0]
+            .contents
+            .contains("GET {API_BASE}/users?page=2\nAuthorization: Bearer {API_TOKEN}\n"));
+        assert!(!results[0].contents.contains("User-Agent"));
+        assert!(results[2].contents.contains(
+            "POST {API_BASE}/login\nContent-Type: application/x-www-form-urlencoded\n\nuser=demo%20user\n"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn filters_by_url_method_and_content_type() -> Result<()> {
+        let filter = HarFilter {
+            url: Some(Regex::new(r"api\.example\.com")?),
+            methods: vec!["get".to_string()],
+            content_types: vec!["JSON".to_string()],
+        };
+        let results = import_har(HAR, &filter, &rules())?;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].method, "GET");
+
+        let filter = HarFilter {
+            methods: vec!["DELETE".to_string()],
+            ..HarFilter::default()
+        };
+        let err = import_har(HAR, &filter, &rules()).unwrap_err();
+        assert_eq!(err.to_string(), "No HAR entries match the filters");
+        Ok(())
+    }
+}
@@ -0,0 +1,233 @@
+use anyhow::{bail, Result};
+
+use super::model::{ImportResult, ImportRules};
+use super::substitutions::{convert_double_braces, finish_import, ImportedRequest};
+
+/// Imports every request in a JetBrains or VS Code `.http` file. Requests are separated by
+/// `###` lines, whose text (or a `# @name` comment) names the request.
+pub fn import_http_file(contents: &str, rules: &ImportRules<'_>) -> Result<Vec<ImportResult>> {
+    let variables: Vec<&str> = contents
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix('@')?.split_once('='))
+        .map(|(name, _)| name.trim())
+        .collect();
+
+    let mut requests = Vec::new();
+    let mut block: Vec<&str> = Vec::new();
+    let mut name: Option<String> = None;
+    for line in contents.lines() {
+        if let Some(title) = line.strip_prefix("###") {
+            requests.extend(parse_block(&block, name.take())?);
+            block.clear();
+            let title = title.trim();
+            name = (!title.is_empty()).then(|| title.to_string());
+        } else {
+            block.push(line);
+        }
+    }
+    requests.extend(parse_block(&block, name)?);
+    if requests.is_empty() {
+        bail!("No requests found in .http file");
+    }
+
+    Ok(requests
+        .into_iter()
+        .map(|mut request| {
+            let placeholders = std::iter::once(&request.url)
+                .chain(request.headers.iter().map(|(_, value)| value))
+                .chain(request.body_text.iter())
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join("\n");
+            for variable in &variables {
+                if placeholders.contains(&format!("{{{variable}}}")) {
+                    request.warnings.push(format!(
+                        "file variable @{variable} was not imported; define {variable} in curlpit.json"
+                    ));
+                }
+            }
+            finish_import(request, rules)
+        })
+        .collect())
+}
+
+fn parse_block(lines: &[&str], mut name: Option<String>) -> Result<Option<ImportedRequest>> {
+    let mut warnings = Vec::new();
+    let mut lines = lines.iter().map(|line| line.trim_end()).peekable();
+
+    let mut request_line = None;
+    for line in lines.by_ref() {
+        let trimmed = line.trim();
+        if let Some(tag) = trimmed
+            .strip_prefix('#')
+            .or_else(|| trimmed.strip_prefix("//"))
+        {
+            if let Some(tag_name) = tag.trim().strip_prefix("@name") {
+                name = Some(tag_name.trim().to_string());
+            }
+            continue;
+        }
+        if trimmed.is_empty() || trimmed.starts_with('@') {
+            continue;
+        }
+        request_line = Some(trimmed.to_string());
+        break;
+    }
+    let Some(request_line) = request_line else {
+        return Ok(None);
+    };
+
+    let mut parts: Vec<&str> = request_line.split_whitespace().collect();
+    if parts
+        .last()
+        .is_some_and(|part| part.starts_with("HTTP/") && parts.len() > 1)
+    {
+        parts.pop();
+    }
+    let (method, mut url) = match parts.as_slice() {
+        [url] => ("GET".to_string(), url.to_string()),
+        [method, rest @ ..] if method.chars().all(|ch| ch.is_ascii_uppercase()) => {
+            (method.to_string(), rest.join(" "))
+        }
+        _ => bail!("Invalid request line: {request_line}"),
+    };
+    // Long query strings may continue on indented lines starting with ? or &
+    while let Some(line) = lines.peek() {
+        let trimmed = line.trim_start();
+        if trimmed.len() < line.len() && (trimmed.starts_with('?') || trimmed.starts_with('&')) {
+            url.push_str(trimmed);
+            lines.next();
+        } else {
+            break;
+        }
+    }
+
+    let mut headers = Vec::new();
+    for line in lines.by_ref() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            break;
+        }
+        if trimmed.starts_with('#') || trimmed.starts_with("//") {
+            continue;
+        }
+        match trimmed.split_once(':') {
+            Some((header, value)) => headers.push((
+                header.trim().to_string(),
+                convert_double_braces(value.trim(), &mut warnings),
+            )),
+            None => bail!("Invalid header line: {trimmed}"),
+        }
+    }
+
+    let mut body_lines = Vec::new();
+    for line in lines {
+        if line.starts_with("> ") || line.starts_with(">>") {
+            let warning = "response handlers and redirects were not imported".to_string();
+            if !warnings.contains(&warning) {
+                warnings.push(warning);
+            }
+            break;
+        }
+        if line.starts_with("<> ") {
+            continue;
+        }
+        body_lines.push(line);
+    }
+    while body_lines.last().is_some_and(|line| line.trim().is_empty()) {
+        body_lines.pop();
+    }
+
+    let mut body_text = None;
+    let mut body_file = None;
+    match body_lines.as_slice() {
+        [] => {}
+        [single] if single.starts_with("< ") => {
+            body_file = Some(single[2..].trim().to_string());
+        }
+        lines => body_text = Some(convert_double_braces(&lines.join("\n"), &mut warnings)),
+    }
+
+    Ok(Some(ImportedRequest {
+        name,
+        method,
+        url: convert_double_braces(&url, &mut warnings),
+        headers,
+        body_text,
+        body_file,
+        warnings,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use once_cell::sync::Lazy;
+    use std::collections::HashMap;
+
+    static EMPTY: Lazy<HashMap<String, String>> = Lazy::new(HashMap::new);
+
+    fn rules() -> ImportRules<'static> {
+        ImportRules {
+            template_variables: &EMPTY,
+            env_variables: &EMPTY,
+            template_variants: &[],
+            include_headers: None,
+            exclude_headers: None,
+            append_headers: None,
+        }
+    }
+
+    #[test]
+    fn imports_every_request_in_the_file() -> Result<()> {
+        let contents = r#"@host = https://example.com
+
+### Create user
+POST {{host}}/users HTTP/1.1
+Content-Type: application/json
+Authorization: Bearer {{TOKEN}}
+
+{"name": "demo"}
+
+> {% client.global.set("id", response.body.id); %}
+
+###
+# @name upload
+PUT https://example.com/upload
+    ?overwrite=true
+
+< ./payload.bin
+"#;
+        let results = import_http_file(contents, &rules())?;
+        assert_eq!(results.len(), 2);
+
+        let create = &results[0];
+        assert_eq!(
+            create.suggested_filename.as_deref(),
+            Some("create-user.curl")
+        );
+        assert!(create.contents.contains(
+            "POST {host}/users\nContent-Type: application/json\nAuthorization: Bearer {TOKEN}\n\n{\"name\": \"demo\"}\n"
+        ));
+        assert!(create
+            .warnings
+            .iter()
+            .any(|warning| warning.contains("response handlers")));
+        assert!(create
+            .warnings
+            .iter()
+            .any(|warning| warning.contains("file variable @host")));
+
+        let upload = &results[1];
+        assert_eq!(upload.suggested_filename.as_deref(), Some("upload.curl"));
+        assert_eq!(upload.url, "https://example.com/upload?overwrite=true");
+        assert!(upload.contents.contains("\n@body ./payload.bin\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_files_without_requests() {
+        let err = import_http_file("### nothing here\n# just a comment\n", &rules()).unwrap_err();
+        assert!(err.to_string().contains("No requests found"));
+    }
+}
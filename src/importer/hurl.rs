@@ -0,0 +1,368 @@
+use anyhow::{anyhow, bail, Result};
+
+use super::model::{ImportResult, ImportRules};
+use super::substitutions::{convert_double_braces, encode_pairs, finish_import, ImportedRequest};
+
+/// Sections of a Hurl request that map onto the URL or the body.
+const SUPPORTED_SECTIONS: &[&str] = &["QueryStringParams", "Query", "FormParams", "Form"];
+
+/// Sections of a Hurl request that have no `.curl` equivalent.
+const UNSUPPORTED_SECTIONS: &[&str] = &[
+    "BasicAuth",
+    "Cookies",
+    "MultipartFormData",
+    "Multipart",
+    "Options",
+];
+
+/// Imports every entry of a Hurl file. Response sections (status, captures, asserts) are
+/// dropped with a warning; a comment right above a request names it.
+pub fn import_hurl(contents: &str, rules: &ImportRules<'_>) -> Result<Vec<ImportResult>> {
+    let mut lines = contents.lines().map(str::trim_end).enumerate().peekable();
+    let mut results = Vec::new();
+    let mut comment: Option<String> = None;
+
+    while let Some((index, line)) = lines.next() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            comment = note(trimmed);
+            continue;
+        }
+        let Some((method, url)) = request_line(trimmed) else {
+            bail!(
+                "line {}: expected a request line, found {trimmed}",
+                index + 1
+            );
+        };
+
+        let mut warnings = Vec::new();
+        let mut request = ImportedRequest {
+            name: comment.take(),
+            method: method.to_string(),
+            url: convert_double_braces(&unescape(url), &mut warnings),
+            ..ImportedRequest::default()
+        };
+        let mut query: Vec<(String, String)> = Vec::new();
+        let mut form: Vec<(String, String)> = Vec::new();
+        let mut section: Option<String> = None;
+
+        // Request headers, sections and body, up to the response or the next request
+        while let Some(&(index, line)) = lines.peek() {
+            let trimmed = line.trim();
+            if request_line(trimmed).is_some() || trimmed.starts_with("HTTP") {
+                break;
+            }
+            lines.next();
+            comment = note(trimmed);
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = trimmed
+                .strip_prefix('[')
+                .and_then(|rest| rest.strip_suffix(']'))
+                // Anything else in brackets, such as `[1]`, is a JSON body
+                .filter(|name| {
+                    SUPPORTED_SECTIONS.contains(name) || UNSUPPORTED_SECTIONS.contains(name)
+                })
+            {
+                if UNSUPPORTED_SECTIONS.contains(&name) {
+                    warnings.push(format!("[{name}] section was not imported"));
+                }
+                section = Some(name.to_string());
+                continue;
+            }
+            if let Some(text) = body_start(trimmed, &mut lines)? {
+                match text {
+                    Body::Text(text) => {
+                        request.body_text = Some(convert_double_braces(&text, &mut warnings))
+                    }
+                    Body::File(path) => request.body_file = Some(path),
+                }
+                continue;
+            }
+            let (key, value) = trimmed
+                .split_once(':')
+                .ok_or_else(|| anyhow!("line {}: unsupported syntax {trimmed}", index + 1))?;
+            let entry = (
+                unescape(key.trim()),
+                convert_double_braces(&unescape(value.trim()), &mut warnings),
+            );
+            match section.as_deref() {
+                None => request.headers.push(entry),
+                Some("QueryStringParams" | "Query") => query.push(entry),
+                Some("FormParams" | "Form") => form.push(entry),
+                Some(_) => {}
+            }
+        }
+
+        // The response: status line, headers, captures and asserts
+        let mut has_checks = false;
+        while let Some(&(_, line)) = lines.peek() {
+            let trimmed = line.trim();
+            if request_line(trimmed).is_some() {
+                break;
+            }
+            lines.next();
+            has_checks |= trimmed.starts_with("HTTP") || trimmed.starts_with('[');
+            comment = note(trimmed);
+        }
+        if has_checks {
+            warnings.push("response status, captures and asserts were not imported".to_string());
+        }
+
+        if !query.is_empty() {
+            let separator = if request.url.contains('?') { '&' } else { '?' };
+            request.url = format!("{}{separator}{}", request.url, encode_pairs(&query));
+        }
+        if !form.is_empty() {
+            request.body_text = Some(encode_pairs(&form));
+            if !request
+                .headers
+                .iter()
+                .any(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+            {
+                request.headers.push((
+                    "Content-Type".to_string(),
+                    "application/x-www-form-urlencoded".to_string(),
+                ));
+            }
+        }
+        request.warnings = warnings;
+        results.push(finish_import(request, rules));
+    }
+
+    if results.is_empty() {
+        bail!("No requests found in Hurl file");
+    }
+    Ok(results)
+}
+
+enum Body {
+    Text(String),
+    File(String),
+}
+
+/// The text of a comment line, which names the request that follows it.
+fn note(line: &str) -> Option<String> {
+    line.strip_prefix('#').map(|text| text.trim().to_string())
+}
+
+fn request_line(line: &str) -> Option<(&str, &str)> {
+    let (method, url) = line.split_once(' ')?;
+    let is_method = !method.is_empty() && method.chars().all(|ch| ch.is_ascii_uppercase());
+    (is_method && method != "HTTP").then(|| (method, url.trim()))
+}
+
+/// Reads a body starting at `line`, consuming any further lines it spans.
+fn body_start<'a>(
+    line: &str,
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+) -> Result<Option<Body>> {
+    if line.starts_with("```") {
+        let mut body = Vec::new();
+        for (_, next) in lines.by_ref() {
+            if next.trim() == "```" {
+                return Ok(Some(Body::Text(body.join("\n"))));
+            }
+            body.push(next);
+        }
+        bail!("unterminated ``` body");
+    }
+    if let Some(text) = line
+        .strip_prefix('`')
+        .and_then(|rest| rest.strip_suffix('`'))
+    {
+        return Ok(Some(Body::Text(unescape_oneline(text))));
+    }
+    if let Some(path) = line
+        .strip_prefix("file,")
+        .and_then(|rest| rest.strip_suffix(';'))
+    {
+        return Ok(Some(Body::File(unescape(path.trim()))));
+    }
+    if line.starts_with('{') || line.starts_with('[') {
+        let mut body = vec![line.to_string()];
+        let mut depth = json_depth(line);
+        while depth > 0 {
+            let Some((_, next)) = lines.next() else {
+                bail!("unterminated JSON body");
+            };
+            depth += json_depth(next);
+            body.push(next.to_string());
+        }
+        return Ok(Some(Body::Text(body.join("\n"))));
+    }
+    Ok(None)
+}
+
+/// Net change in bracket nesting over `line`, ignoring brackets inside strings.
+fn json_depth(line: &str) -> i32 {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for ch in line.chars() {
+        match ch {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '{' | '[' if !in_string => depth += 1,
+            '}' | ']' if !in_string => depth -= 1,
+            _ => {}
+        }
+    }
+    depth
+}
+
+/// Reads the escapes of a oneline string, such as `\n` for a line break.
+fn unescape_oneline(value: &str) -> String {
+    let mut output = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            output.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => output.push('\n'),
+            Some('r') => output.push('\r'),
+            Some('t') => output.push('\t'),
+            Some(next @ ('`' | '#' | '\\')) => output.push(next),
+            Some(next) => {
+                output.push('\\');
+                output.push(next);
+            }
+            None => output.push('\\'),
+        }
+    }
+    output
+}
+
+fn unescape(value: &str) -> String {
+    let mut output = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(ch) = chars.next() {
+        if ch == '\\' {
+            match chars.next() {
+                Some(next @ ('#' | '\\' | ':' | ';')) => output.push(next),
+                Some(next) => {
+                    output.push('\\');
+                    output.push(next);
+                }
+                None => output.push('\\'),
+            }
+        } else {
+            output.push(ch);
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use once_cell::sync::Lazy;
+    use std::collections::HashMap;
+
+    static EMPTY: Lazy<HashMap<String, String>> = Lazy::new(HashMap::new);
+
+    fn rules() -> ImportRules<'static> {
+        ImportRules {
+            template_variables: &EMPTY,
+            env_variables: &EMPTY,
+            template_variants: &[],
+            include_headers: None,
+            exclude_headers: None,
+            append_headers: None,
+        }
+    }
+
+    #[test]
+    fn imports_entries_and_drops_response_checks() -> Result<()> {
+        let contents = r#"# Create user
+POST {{API_BASE}}/users\#top
+Authorization: Bearer {{API_TOKEN}}
+{
+  "name": "demo",
+  "tags": ["a}"]
+}
+HTTP 201
+[Captures]
+id: jsonpath "$.id"
+[Asserts]
+jsonpath "$.name" == "demo"
+
+GET https://example.com/search
+[QueryStringParams]
+q: rust lang
+page: {{PAGE}}
+[Options]
+# not a name
+insecure: true
+
+# upload.curl
+PUT https://example.com/upload
+file,data/payload.bin;
+
+POST https://example.com/notes
+```
+line one
+line two
+```
+"#;
+        let results = import_hurl(contents, &rules())?;
+        assert_eq!(results.len(), 4);
+
+        let create = &results[0];
+        assert_eq!(
+            create.suggested_filename.as_deref(),
+            Some("create-user.curl")
+        );
+        assert!(create.contents.contains(
+            "POST {API_BASE}/users#top\nAuthorization: Bearer {API_TOKEN}\n\n{\n  \"name\": \"demo\",\n  \"tags\": [\"a}\"]\n}\n"
+        ));
+        assert!(create
+            .warnings
+            .iter()
+            .any(|warning| warning.contains("asserts were not imported")));
+
+        let search = &results[1];
+        assert_eq!(
+            search.url,
+            "https://example.com/search?q=rust%20lang&page={PAGE}"
+        );
+        assert_eq!(
+            search.warnings,
+            vec!["[Options] section was not imported".to_string()]
+        );
+
+        assert_eq!(
+            results[2].suggested_filename.as_deref(),
+            Some("upload.curl")
+        );
+        assert!(results[2].contents.contains("\n@body data/payload.bin\n"));
+        assert!(results[3].contents.ends_with("\nline one\nline two\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn one_line_json_arrays_are_bodies() -> Result<()> {
+        let results = import_hurl(
+            "POST https://api.example.com/numbers\nContent-Type: application/json\n[1]\n",
+            &rules(),
+        )?;
+        assert!(results[0]
+            .contents
+            .ends_with("Content-Type: application/json\n\n[1]\n"));
+        assert!(results[0].warnings.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn reports_the_line_of_invalid_entries() {
+        let err = import_hurl("\nnot a request\n", &rules()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2: expected a request line, found not a request"
+        );
+    }
+}
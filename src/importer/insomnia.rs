@@ -0,0 +1,556 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::model::{ImportRules, ImportedCollection, ImportedProfile};
+use super::substitutions::{
+    basic_auth_header, convert_double_braces, encode_pairs, finish_import, is_placeholder_name,
+    slugify, value_text, ImportedRequest,
+};
+
+#[derive(Deserialize)]
+struct Export {
+    #[serde(rename = "__export_format", default)]
+    export_format: u32,
+    #[serde(default)]
+    resources: Vec<Resource>,
+}
+
+/// Every resource type shares one shape; fields a type does not use are left empty.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Resource {
+    #[serde(rename = "_id")]
+    id: String,
+    #[serde(rename = "_type")]
+    kind: String,
+    #[serde(rename = "parentId")]
+    parent_id: Option<String>,
+    name: String,
+    method: String,
+    url: String,
+    body: Body,
+    headers: Vec<Pair>,
+    parameters: Vec<Pair>,
+    authentication: HashMap<String, Value>,
+    #[serde(rename = "preRequestScript")]
+    pre_request_script: String,
+    #[serde(rename = "afterResponseScript")]
+    after_response_script: String,
+    /// Variables of an environment
+    data: BTreeMap<String, Value>,
+    /// Variables of a request group
+    environment: BTreeMap<String, Value>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Body {
+    #[serde(rename = "mimeType")]
+    mime_type: Option<String>,
+    text: Option<String>,
+    params: Vec<Pair>,
+    #[serde(rename = "fileName")]
+    file_name: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Pair {
+    name: String,
+    value: Value,
+    disabled: bool,
+}
+
+/// Imports an Insomnia v4 export, in JSON or YAML. Workspaces and request groups become
+/// directories, base environments shared variables and sub-environments profiles.
+pub fn import_insomnia(contents: &str, rules: &ImportRules<'_>) -> Result<ImportedCollection> {
+    let export: Export = if contents.trim_start().starts_with('{') {
+        serde_json::from_str(contents).context("parsing Insomnia export")?
+    } else {
+        serde_yaml::from_str(contents).context("parsing Insomnia export")?
+    };
+    if export.export_format != 4 {
+        bail!(
+            "Only Insomnia v4 exports are supported (found format {})",
+            export.export_format
+        );
+    }
+
+    let mut children: HashMap<&str, Vec<&Resource>> = HashMap::new();
+    for resource in &export.resources {
+        if let Some(parent) = &resource.parent_id {
+            children.entry(parent.as_str()).or_default().push(resource);
+        }
+    }
+    let workspaces: Vec<&Resource> = export
+        .resources
+        .iter()
+        .filter(|resource| resource.kind == "workspace")
+        .collect();
+
+    let mut output = ImportedCollection::default();
+    for workspace in &workspaces {
+        let directory = directory_name(&workspace.name, "workspace");
+        let mut folders = vec![(workspace.name.as_str(), directory.clone())];
+        walk(workspace, &children, &mut folders, rules, &mut output);
+
+        // The base environment holds shared variables; its children are the switchable ones
+        for base in children
+            .get(workspace.id.as_str())
+            .into_iter()
+            .flatten()
+            .filter(|resource| resource.kind == "environment")
+        {
+            let scope = format!("{}: base environment", workspace.name);
+            for (key, value) in variables(&base.data, &scope, &mut output.warnings) {
+                match output.variables.get(&key) {
+                    Some(existing) if *existing != value => output.warnings.push(format!(
+                        "{scope}: {key} differs from another workspace and was not imported"
+                    )),
+                    _ => {
+                        output.variables.insert(key, value);
+                    }
+                }
+            }
+            for environment in children
+                .get(base.id.as_str())
+                .into_iter()
+                .flatten()
+                .filter(|resource| resource.kind == "environment")
+            {
+                let name = directory_name(&environment.name, "environment");
+                let scope = format!("{}: environment {}", workspace.name, environment.name);
+                let profile = ImportedProfile {
+                    // Workspaces share one config, so their profile names must not collide
+                    name: if workspaces.len() > 1 {
+                        format!("{directory}-{name}")
+                    } else {
+                        name
+                    },
+                    variables: variables(&environment.data, &scope, &mut output.warnings),
+                };
+                output.push_profile(&scope, profile);
+            }
+        }
+    }
+    if output.requests.is_empty() {
+        bail!("No requests found in Insomnia export");
+    }
+    Ok(output)
+}
+
+/// `folders` holds the name and directory of `parent` and each group above it.
+fn walk<'a>(
+    parent: &Resource,
+    children: &HashMap<&str, Vec<&'a Resource>>,
+    folders: &mut Vec<(&'a str, String)>,
+    rules: &ImportRules<'_>,
+    output: &mut ImportedCollection,
+) {
+    for resource in children.get(parent.id.as_str()).into_iter().flatten() {
+        let path = folders
+            .iter()
+            .map(|(name, _)| *name)
+            .chain([resource.name.as_str()])
+            .collect::<Vec<_>>()
+            .join("/");
+        match resource.kind.as_str() {
+            "request_group" => {
+                if !resource.environment.is_empty() {
+                    output
+                        .warnings
+                        .push(format!("{path}: folder environment was not imported"));
+                }
+                folders.push((&resource.name, directory_name(&resource.name, "folder")));
+                walk(resource, children, folders, rules, output);
+                folders.pop();
+            }
+            "request" => {
+                let mut result = finish_import(convert(resource), rules);
+                let directories: Vec<&str> = folders.iter().map(|(_, dir)| dir.as_str()).collect();
+                result.suggested_filename = result
+                    .suggested_filename
+                    .map(|name| format!("{}/{name}", directories.join("/")));
+                output.requests.push(result);
+            }
+            "grpc_request" | "websocket_request" => output.warnings.push(format!(
+                "{path}: {} was not imported",
+                resource.kind.replace('_', " ")
+            )),
+            "unit_test_suite" => output
+                .warnings
+                .push(format!("{path}: unit tests were not imported")),
+            _ => {}
+        }
+    }
+}
+
+fn convert(resource: &Resource) -> ImportedRequest {
+    let mut warnings = Vec::new();
+    let mut url = convert_template(&resource.url, &mut warnings);
+    let mut query = enabled(&resource.parameters, &mut warnings);
+    let mut headers = enabled(&resource.headers, &mut warnings);
+
+    let auth = &resource.authentication;
+    let setting = |name: &str, warnings: &mut Vec<String>| {
+        convert_template(
+            &auth.get(name).map(value_text).unwrap_or_default(),
+            warnings,
+        )
+    };
+    let auth_kind = auth.get("type").and_then(Value::as_str).unwrap_or("none");
+    if auth.get("disabled").and_then(Value::as_bool) != Some(true) {
+        match auth_kind {
+            "none" | "" => {}
+            "bearer" => {
+                let prefix = match setting("prefix", &mut warnings) {
+                    prefix if prefix.is_empty() => "Bearer".to_string(),
+                    prefix => prefix,
+                };
+                headers.push((
+                    "Authorization".to_string(),
+                    format!("{prefix} {}", setting("token", &mut warnings)),
+                ));
+            }
+            "basic" => {
+                // Check the raw credentials, since converted ones no longer contain `{{`
+                let raw = |name: &str| auth.get(name).map(value_text).unwrap_or_default();
+                headers.extend(basic_auth_header(
+                    &raw("username"),
+                    &raw("password"),
+                    &mut warnings,
+                ));
+            }
+            "apikey" => {
+                let pair = (
+                    setting("key", &mut warnings),
+                    setting("value", &mut warnings),
+                );
+                match auth.get("addTo").and_then(Value::as_str) {
+                    Some("queryParams") => query.push(pair),
+                    Some("cookie") => {
+                        headers.push(("Cookie".to_string(), format!("{}={}", pair.0, pair.1)))
+                    }
+                    _ => headers.push(pair),
+                }
+            }
+            other => warnings.push(format!("{other} auth was not imported")),
+        }
+    }
+    if !query.is_empty() {
+        let separator = if url.contains('?') { '&' } else { '?' };
+        url = format!("{url}{separator}{}", encode_pairs(&query));
+    }
+
+    let body = &resource.body;
+    let mut body_text = None;
+    let mut body_file = None;
+    let mut content_type = body.mime_type.clone();
+    match body.mime_type.as_deref() {
+        Some("application/x-www-form-urlencoded") => {
+            body_text = Some(encode_pairs(&enabled(&body.params, &mut warnings)));
+        }
+        Some("multipart/form-data") => {
+            warnings.push("multipart form data was not imported".to_string());
+            content_type = None;
+        }
+        // Insomnia stores GraphQL requests as the JSON payload it sends
+        Some("application/graphql") => {
+            body_text = body
+                .text
+                .as_deref()
+                .map(|text| convert_template(text, &mut warnings));
+            content_type = Some("application/json".to_string());
+        }
+        _ => {
+            if let Some(file) = &body.file_name {
+                body_file = Some(file.clone());
+            } else {
+                body_text = body
+                    .text
+                    .as_deref()
+                    .filter(|text| !text.is_empty())
+                    .map(|text| convert_template(text, &mut warnings));
+            }
+        }
+    }
+    if let Some(content_type) = content_type.filter(|_| body_text.is_some() || body_file.is_some())
+    {
+        if !headers
+            .iter()
+            .any(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+        {
+            headers.push(("Content-Type".to_string(), content_type));
+        }
+    }
+
+    if !resource.pre_request_script.trim().is_empty() {
+        warnings.push("pre-request script was not imported".to_string());
+    }
+    if !resource.after_response_script.trim().is_empty() {
+        warnings.push("after-response script was not imported".to_string());
+    }
+
+    ImportedRequest {
+        name: Some(resource.name.clone()),
+        method: match resource.method.to_uppercase() {
+            method if method.is_empty() => "GET".to_string(),
+            method => method,
+        },
+        url,
+        headers,
+        body_text,
+        body_file,
+        warnings,
+    }
+}
+
+fn enabled(pairs: &[Pair], warnings: &mut Vec<String>) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .filter(|pair| !pair.disabled && !pair.name.is_empty())
+        .map(|pair| {
+            (
+                convert_template(&pair.name, warnings),
+                convert_template(&value_text(&pair.value), warnings),
+            )
+        })
+        .collect()
+}
+
+/// Rewrites Nunjucks variables such as `{{ _.API_BASE }}` into placeholders. Template tags
+/// (`{% uuid 'v4' %}`, `{% response ... %}`) are kept as-is with a warning.
+fn convert_template(input: &str, warnings: &mut Vec<String>) -> String {
+    let mut rest = input;
+    while let Some(start) = rest.find("{%") {
+        let Some(end) = rest[start..].find("%}") else {
+            break;
+        };
+        let tag = &rest[start..start + end + 2];
+        let warning = format!("template tag {tag} has no curlpit equivalent and was kept as-is");
+        if !warnings.contains(&warning) {
+            warnings.push(warning);
+        }
+        rest = &rest[start + end + 2..];
+    }
+    let unscoped = input.replace("{{ _.", "{{ ").replace("{{_.", "{{");
+    convert_double_braces(&unscoped, warnings)
+}
+
+/// Flattens nested environment objects into dotted names (`auth.token`), which placeholders
+/// accept as well.
+fn variables(
+    data: &BTreeMap<String, Value>,
+    scope: &str,
+    warnings: &mut Vec<String>,
+) -> BTreeMap<String, String> {
+    fn flatten(prefix: &str, value: &Value, output: &mut Vec<(String, String)>) {
+        match value {
+            Value::Object(entries) => {
+                for (key, value) in entries {
+                    flatten(&format!("{prefix}.{key}"), value, output);
+                }
+            }
+            other => output.push((prefix.to_string(), value_text(other))),
+        }
+    }
+
+    let mut entries = Vec::new();
+    for (key, value) in data {
+        flatten(key, value, &mut entries);
+    }
+    let mut variables = BTreeMap::new();
+    for (key, value) in entries {
+        if !is_placeholder_name(&key) {
+            warnings.push(format!(
+                "{scope}: variable {key:?} is not a valid placeholder name and was not imported"
+            ));
+            continue;
+        }
+        let mut value_warnings = Vec::new();
+        let value = convert_template(&value, &mut value_warnings);
+        warnings.extend(
+            value_warnings
+                .into_iter()
+                .map(|warning| format!("{scope}: {warning}")),
+        );
+        variables.insert(key, value);
+    }
+    variables
+}
+
+fn directory_name(name: &str, fallback: &str) -> String {
+    match slugify(name) {
+        slug if slug.is_empty() => fallback.to_string(),
+        slug => slug,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use once_cell::sync::Lazy;
+
+    static EMPTY: Lazy<HashMap<String, String>> = Lazy::new(HashMap::new);
+
+    fn rules() -> ImportRules<'static> {
+        ImportRules {
+            template_variables: &EMPTY,
+            env_variables: &EMPTY,
+            template_variants: &[],
+            include_headers: None,
+            exclude_headers: None,
+            append_headers: None,
+        }
+    }
+
+    #[test]
+    fn imports_workspaces_groups_and_environments() -> Result<()> {
+        let contents = r#"{
+  "_type": "export",
+  "__export_format": 4,
+  "resources": [
+    {"_id": "wrk_1", "_type": "workspace", "name": "Shop API"},
+    {"_id": "env_base", "_type": "environment", "parentId": "wrk_1", "name": "Base Environment",
+     "data": {"base_url": "https://api.example.com", "auth": {"token": "abc"}}},
+    {"_id": "env_stage", "_type": "environment", "parentId": "env_base", "name": "Staging",
+     "data": {"base_url": "https://staging.example.com"}},
+    {"_id": "fld_1", "_type": "request_group", "parentId": "wrk_1", "name": "Orders",
+     "environment": {"page_size": 10}},
+    {"_id": "req_1", "_type": "request", "parentId": "fld_1", "name": "List orders",
+     "method": "GET", "url": "{{ _.base_url }}/orders",
+     "parameters": [{"name": "page", "value": "{{ _.page }}"}, {"name": "x", "value": "1", "disabled": true}],
+     "headers": [{"name": "Accept", "value": "application/json"}, {"name": "X-Request-Id", "value": "{% uuid 'v4' %}"}],
+     "authentication": {"type": "bearer", "token": "{{ _.auth.token }}"},
+     "body": {}},
+    {"_id": "req_2", "_type": "request", "parentId": "wrk_1", "name": "Login",
+     "method": "POST", "url": "{{ _.base_url }}/login",
+     "headers": [],
+     "authentication": {"type": "basic", "username": "demo", "password": "secret"},
+     "body": {"mimeType": "application/x-www-form-urlencoded",
+              "params": [{"name": "remember", "value": "yes please"}]}},
+    {"_id": "req_3", "_type": "grpc_request", "parentId": "wrk_1", "name": "Stream"}
+  ]
+}"#;
+        let imported = import_insomnia(contents, &rules())?;
+        assert_eq!(imported.requests.len(), 2);
+
+        let list = &imported.requests[0];
+        assert_eq!(
+            list.suggested_filename.as_deref(),
+            Some("shop-api/orders/list-orders.curl")
+        );
+        assert!(list.contents.contains(
+            "GET {base_url}/orders?page={page}\nAccept: application/json\nX-Request-Id: {% uuid 'v4' %}\nAuthorization: Bearer {auth.token}\n"
+        ));
+        assert_eq!(
+            list.warnings,
+            vec![
+                "template tag {% uuid 'v4' %} has no curlpit equivalent and was kept as-is"
+                    .to_string()
+            ]
+        );
+
+        let login = &imported.requests[1];
+        assert_eq!(
+            login.suggested_filename.as_deref(),
+            Some("shop-api/login.curl")
+        );
+        assert!(login.contents.contains(
+            "POST {base_url}/login\nAuthorization: Basic ZGVtbzpzZWNyZXQ=\nContent-Type: application/x-www-form-urlencoded\n\nremember=yes%20please\n"
+        ));
+
+        assert_eq!(
+            imported.variables,
+            BTreeMap::from([
+                ("auth.token".to_string(), "abc".to_string()),
+                (
+                    "base_url".to_string(),
+                    "https://api.example.com".to_string()
+                ),
+            ])
+        );
+        assert_eq!(imported.profiles.len(), 1);
+        assert_eq!(imported.profiles[0].name, "staging");
+        assert_eq!(
+            imported.warnings,
+            vec![
+                "Shop API/Orders: folder environment was not imported".to_string(),
+                "Shop API/Stream: grpc request was not imported".to_string(),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn reads_yaml_exports() -> Result<()> {
+        let contents = r#"_type: export
+__export_format: 4
+resources:
+  - _id: wrk_1
+    _type: workspace
+    name: Demo
+  - _id: req_1
+    _type: request
+    parentId: wrk_1
+    name: Health
+    method: get
+    url: "{{ host }}/health"
+"#;
+        let imported = import_insomnia(contents, &rules())?;
+        assert_eq!(
+            imported.requests[0].suggested_filename.as_deref(),
+            Some("demo/health.curl")
+        );
+        assert!(imported.requests[0]
+            .contents
+            .contains("GET {host}/health\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn environments_with_the_same_slug_stay_separate_profiles() -> Result<()> {
+        let contents = r#"{"__export_format": 4, "resources": [
+  {"_id": "wrk_1", "_type": "workspace", "name": "Demo"},
+  {"_id": "wrk_2", "_type": "workspace", "name": "demo!"},
+  {"_id": "req_1", "_type": "request", "parentId": "wrk_1", "name": "Health", "url": "https://example.com"},
+  {"_id": "env_1", "_type": "environment", "parentId": "wrk_1", "data": {}},
+  {"_id": "env_2", "_type": "environment", "parentId": "env_1", "name": "Staging", "data": {"host": "s"}},
+  {"_id": "env_3", "_type": "environment", "parentId": "env_1", "name": "staging!", "data": {"host": "t"}},
+  {"_id": "env_4", "_type": "environment", "parentId": "wrk_2", "data": {}},
+  {"_id": "env_5", "_type": "environment", "parentId": "env_4", "name": "Staging", "data": {"host": "u"}}
+]}"#;
+        let imported = import_insomnia(contents, &rules())?;
+        let profiles: Vec<(&str, &str)> = imported
+            .profiles
+            .iter()
+            .map(|profile| (profile.name.as_str(), profile.variables["host"].as_str()))
+            .collect();
+        assert_eq!(
+            profiles,
+            [
+                ("demo-staging", "s"),
+                ("demo-staging-2", "t"),
+                ("demo-staging-3", "u")
+            ]
+        );
+        assert!(imported.warnings.contains(
+            &"demo!: environment Staging: profile demo-staging already exists; imported as demo-staging-3"
+                .to_string()
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_other_export_formats() {
+        let err =
+            import_insomnia(r#"{"__export_format": 3, "resources": []}"#, &rules()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Only Insomnia v4 exports are supported (found format 3)"
+        );
+    }
+}
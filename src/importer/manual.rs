@@ -1,11 +1,8 @@
 use anyhow::{anyhow, Result};
 use shell_words::split;
 
-use super::headers::apply_header_rules;
 use super::model::{ImportOptions, ImportResult};
-use super::substitutions::{
-    apply_substitutions, build_substitutions, format_curl_contents, suggest_file_name,
-};
+use super::substitutions::{finish_import, ImportedRequest};
 
 pub(crate) fn import_via_manual(options: &ImportOptions<'_>) -> Result<ImportResult> {
     let tokens = split(options.command.trim()).map_err(|err| anyhow!("{err}"))?;
@@ -29,43 +26,18 @@ pub(crate) fn import_via_manual(options: &ImportOptions<'_>) -> Result<ImportRes
         .clone()
         .ok_or_else(|| anyhow!("Unable to determine request URL"))?;
 
-    let substitutions = build_substitutions(
-        options.template_variables,
-        options.env_variables,
-        options.template_variants,
-    );
-    let substituted_url = apply_substitutions(&url, &substitutions);
-    let substituted_headers: Vec<(String, String)> = parsed
-        .headers
-        .iter()
-        .map(|(name, value)| (name.clone(), apply_substitutions(value, &substitutions)))
-        .collect();
-    let final_headers = apply_header_rules(
-        substituted_headers,
-        options.include_headers,
-        options.exclude_headers,
-        options.append_headers,
-    );
-    let substituted_body = parsed
-        .body_text
-        .as_ref()
-        .map(|body| apply_substitutions(body, &substitutions));
-
-    let contents = format_curl_contents(
-        &method,
-        &substituted_url,
-        &final_headers,
-        substituted_body.as_deref(),
-        &parsed.warnings,
-    );
-
-    Ok(ImportResult {
-        contents,
-        suggested_filename: suggest_file_name(&method, &url),
-        method,
-        url: substituted_url,
-        warnings: parsed.warnings,
-    })
+    Ok(finish_import(
+        ImportedRequest {
+            name: None,
+            method,
+            url,
+            headers: parsed.headers,
+            body_text: parsed.body_text,
+            body_file: parsed.body_file,
+            warnings: parsed.warnings,
+        },
+        &options.rules(),
+    ))
 }
 
 #[derive(Debug, Default)]
@@ -184,7 +156,7 @@ fn parse_tokens(tokens: &[String]) -> Result<ParsedCurl> {
                 }
                 option if DATA_OPTIONS.contains(&option) => {
                     let value = next_value(tokens, &mut index)?;
-                    handle_data_option(&mut parsed, value);
+                    handle_data_option(&mut parsed, option, value);
                     continue;
                 }
                 option
@@ -192,11 +164,8 @@ fn parse_tokens(tokens: &[String]) -> Result<ParsedCurl> {
                         .iter()
                         .any(|prefix| option.starts_with(prefix) && option.contains('=')) =>
                 {
-                    let value = option
-                        .split_once('=')
-                        .map(|(_, v)| v.to_string())
-                        .unwrap_or_default();
-                    handle_data_option(&mut parsed, value);
+                    let (name, value) = option.split_once('=').unwrap_or((option, ""));
+                    handle_data_option(&mut parsed, name, value.to_string());
                     index += 1;
                 }
                 _ => {
@@ -238,8 +207,9 @@ fn next_value(tokens: &[String], index: &mut usize) -> Result<String> {
     Ok(value)
 }
 
-fn handle_data_option(parsed: &mut ParsedCurl, value: String) {
-    if value.starts_with('@') {
+fn handle_data_option(parsed: &mut ParsedCurl, option: &str, value: String) {
+    // `--data-raw` sends a leading `@` literally instead of reading a file
+    if value.starts_with('@') && option != "--data-raw" {
         if parsed.body_file.is_none() {
             parsed.body_file = Some(value.trim_start_matches('@').to_string());
         } else {
@@ -356,6 +326,20 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn manual_import_keeps_data_raw_at_signs_and_emits_body_files() -> Result<()> {
+        let result = import_via_manual(&options(
+            "curl https://api.example.com --data-raw '@handle'",
+        ))?;
+        assert!(result.contents.ends_with("\n\n@handle\n"));
+
+        let result = import_via_manual(&options(
+            "curl https://api.example.com --data-binary @payload.bin",
+        ))?;
+        assert!(result.contents.ends_with("\n\n@body payload.bin\n"));
+        Ok(())
+    }
+
     #[test]
     fn manual_import_errors_when_missing_value() {
         let err =
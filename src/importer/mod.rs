@@ -1,10 +1,20 @@
 mod curl_parser;
+mod har;
 mod headers;
+mod http_file;
+mod hurl;
+mod insomnia;
 mod manual;
 mod model;
+mod postman;
 mod substitutions;
 
-pub use model::{ImportOptions, ImportResult};
+pub use har::{import_har, HarFilter};
+pub use http_file::import_http_file;
+pub use hurl::import_hurl;
+pub use insomnia::import_insomnia;
+pub use model::{ImportOptions, ImportResult, ImportRules, ImportedCollection, ImportedProfile};
+pub use postman::import_postman;
 
 use anyhow::{anyhow, Result};
 
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use serde::Serialize;
 
@@ -13,6 +13,30 @@ pub struct ImportOptions<'a> {
     pub append_headers: Option<&'a HashMap<String, String>>,
 }
 
+impl<'a> ImportOptions<'a> {
+    pub fn rules(&self) -> ImportRules<'a> {
+        ImportRules {
+            template_variables: self.template_variables,
+            env_variables: self.env_variables,
+            template_variants: self.template_variants,
+            include_headers: self.include_headers,
+            exclude_headers: self.exclude_headers,
+            append_headers: self.append_headers,
+        }
+    }
+}
+
+/// Known variable values and header rules applied to every imported request.
+#[derive(Debug, Clone, Copy)]
+pub struct ImportRules<'a> {
+    pub template_variables: &'a HashMap<String, String>,
+    pub env_variables: &'a HashMap<String, String>,
+    pub template_variants: &'a [(String, String)],
+    pub include_headers: Option<&'a [String]>,
+    pub exclude_headers: Option<&'a [String]>,
+    pub append_headers: Option<&'a HashMap<String, String>>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct ImportResult {
     pub contents: String,
@@ -21,3 +45,41 @@ pub struct ImportResult {
     pub url: String,
     pub warnings: Vec<String>,
 }
+
+/// Requests plus the variables and profiles read from another tool's export.
+#[derive(Debug, Clone, Default)]
+pub struct ImportedCollection {
+    /// Requests whose `suggested_filename` may include folders, as in `users/create.curl`
+    pub requests: Vec<ImportResult>,
+    /// Variables shared by every profile
+    pub variables: BTreeMap<String, String>,
+    pub profiles: Vec<ImportedProfile>,
+    /// Warnings that are not about a single request
+    pub warnings: Vec<String>,
+}
+
+impl ImportedCollection {
+    /// Adds a profile read from `source`. A name an earlier profile already took gets a numeric
+    /// suffix and a warning, so no profile silently replaces another.
+    pub(crate) fn push_profile(&mut self, source: &str, mut profile: ImportedProfile) {
+        let base = profile.name.clone();
+        let mut suffix = 2;
+        while self.profiles.iter().any(|other| other.name == profile.name) {
+            profile.name = format!("{base}-{suffix}");
+            suffix += 1;
+        }
+        if profile.name != base {
+            self.warnings.push(format!(
+                "{source}: profile {base} already exists; imported as {}",
+                profile.name
+            ));
+        }
+        self.profiles.push(profile);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportedProfile {
+    pub name: String,
+    pub variables: BTreeMap<String, String>,
+}
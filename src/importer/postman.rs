@@ -0,0 +1,723 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::model::{ImportRules, ImportedCollection, ImportedProfile};
+use super::substitutions::{
+    basic_auth_header, convert_double_braces, encode_pairs, finish_import, is_placeholder_name,
+    slugify, value_text, ImportedRequest,
+};
+
+#[derive(Deserialize)]
+struct Collection {
+    info: Info,
+    #[serde(default)]
+    item: Vec<Item>,
+    #[serde(default)]
+    variable: Vec<KeyValue>,
+    auth: Option<Auth>,
+    #[serde(default)]
+    event: Vec<Event>,
+}
+
+#[derive(Deserialize)]
+struct Info {
+    #[serde(default)]
+    schema: String,
+}
+
+#[derive(Deserialize)]
+struct Item {
+    #[serde(default)]
+    name: String,
+    /// Present on folders only
+    item: Option<Vec<Item>>,
+    request: Option<RequestField>,
+    auth: Option<Auth>,
+    #[serde(default)]
+    event: Vec<Event>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RequestField {
+    Url(String),
+    Request(Box<Request>),
+}
+
+#[derive(Deserialize)]
+struct Request {
+    #[serde(default)]
+    method: Option<String>,
+    #[serde(default)]
+    header: Vec<KeyValue>,
+    url: Option<UrlField>,
+    body: Option<Body>,
+    auth: Option<Auth>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum UrlField {
+    Raw(String),
+    Parts(Url),
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Url {
+    raw: String,
+    /// Values for `:name` path segments
+    variable: Vec<KeyValue>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct KeyValue {
+    key: String,
+    value: Value,
+    disabled: bool,
+    /// Environments mark entries with `enabled` rather than `disabled`
+    enabled: Option<bool>,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+}
+
+impl KeyValue {
+    fn is_enabled(&self) -> bool {
+        !self.disabled && self.enabled != Some(false)
+    }
+
+    fn text(&self) -> String {
+        value_text(&self.value)
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Body {
+    mode: String,
+    disabled: bool,
+    raw: String,
+    urlencoded: Vec<KeyValue>,
+    file: Option<FileBody>,
+    graphql: Option<GraphQl>,
+    options: Value,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct FileBody {
+    src: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct GraphQl {
+    query: String,
+    variables: String,
+}
+
+#[derive(Deserialize)]
+struct Auth {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(flatten)]
+    settings: HashMap<String, Value>,
+}
+
+impl Auth {
+    /// Looks up a setting, stored as `[{key, value}]` in v2.1 and as an object in v2.0.
+    fn setting(&self, name: &str) -> String {
+        match self.settings.get(&self.kind) {
+            Some(Value::Array(entries)) => entries
+                .iter()
+                .find(|entry| entry.get("key").and_then(Value::as_str) == Some(name))
+                .and_then(|entry| entry.get("value"))
+                .map(value_text)
+                .unwrap_or_default(),
+            Some(Value::Object(settings)) => settings.get(name).map(value_text).unwrap_or_default(),
+            _ => String::new(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Event {
+    listen: String,
+    script: Option<Script>,
+}
+
+#[derive(Deserialize)]
+struct Script {
+    #[serde(default)]
+    exec: Value,
+}
+
+impl Event {
+    /// Postman exports empty scripts as `[""]`, which are not worth a warning.
+    fn warning(&self) -> Option<String> {
+        let exec = &self.script.as_ref()?.exec;
+        let has_code = match exec {
+            Value::String(line) => !line.trim().is_empty(),
+            Value::Array(lines) => lines
+                .iter()
+                .any(|line| line.as_str().is_some_and(|line| !line.trim().is_empty())),
+            _ => false,
+        };
+        let kind = match self.listen.as_str() {
+            "prerequest" => "pre-request",
+            other => other,
+        };
+        has_code.then(|| format!("{kind} script was not imported"))
+    }
+}
+
+#[derive(Deserialize)]
+struct Environment {
+    #[serde(default)]
+    name: String,
+    values: Vec<KeyValue>,
+}
+
+/// Imports a Postman v2.0/v2.1 collection and any exported environments. Folders become
+/// directories, collection variables shared variables and each environment a profile.
+pub fn import_postman(
+    collection: &str,
+    environments: &[String],
+    rules: &ImportRules<'_>,
+) -> Result<ImportedCollection> {
+    let collection: Collection =
+        serde_json::from_str(collection).context("parsing Postman collection")?;
+    if !collection.info.schema.is_empty() && !collection.info.schema.contains("/v2.") {
+        bail!("Only Postman collection v2.0 and v2.1 exports are supported");
+    }
+
+    let mut output = ImportedCollection::default();
+    let scripts: Vec<String> = collection
+        .event
+        .iter()
+        .filter_map(Event::warning)
+        .map(|warning| format!("collection: {warning}"))
+        .collect();
+    output.warnings.extend(scripts.iter().cloned());
+    output.variables = variables(&collection.variable, "collection", &mut output.warnings);
+    walk(
+        &collection.item,
+        &mut Vec::new(),
+        collection.auth.as_ref(),
+        &scripts,
+        rules,
+        &mut output,
+    );
+    if output.requests.is_empty() {
+        bail!("No requests found in Postman collection");
+    }
+
+    for contents in environments {
+        let environment: Environment =
+            serde_json::from_str(contents).context("parsing Postman environment")?;
+        let name = match slugify(&environment.name) {
+            name if name.is_empty() => "postman".to_string(),
+            name => name,
+        };
+        let scope = format!("environment {}", environment.name);
+        let profile = ImportedProfile {
+            variables: variables(&environment.values, &scope, &mut output.warnings),
+            name,
+        };
+        output.push_profile(&scope, profile);
+    }
+    Ok(output)
+}
+
+/// `folders` holds the name and directory of each enclosing folder, `scripts` warnings about the
+/// scripts of the collection and those folders, which would have run before each request.
+fn walk<'a>(
+    items: &'a [Item],
+    folders: &mut Vec<(&'a str, String)>,
+    auth: Option<&Auth>,
+    scripts: &[String],
+    rules: &ImportRules<'_>,
+    output: &mut ImportedCollection,
+) {
+    for item in items {
+        let auth = match &item.auth {
+            Some(own) if own.kind != "inherit" => Some(own),
+            _ => auth,
+        };
+        let path = folders
+            .iter()
+            .map(|(name, _)| *name)
+            .chain([item.name.as_str()])
+            .collect::<Vec<_>>()
+            .join("/");
+
+        if let Some(children) = &item.item {
+            let mut scripts = scripts.to_vec();
+            for event in &item.event {
+                if let Some(warning) = event.warning() {
+                    scripts.push(format!("{path}: {warning}"));
+                    output.warnings.push(format!("{path}: {warning}"));
+                }
+            }
+            let directory = match slugify(&item.name) {
+                name if name.is_empty() => "folder".to_string(),
+                name => name,
+            };
+            folders.push((&item.name, directory));
+            walk(children, folders, auth, &scripts, rules, output);
+            folders.pop();
+            continue;
+        }
+
+        let Some(request) = &item.request else {
+            continue;
+        };
+        let Some(mut imported) = convert(item, request, auth) else {
+            output
+                .warnings
+                .push(format!("{path}: request has no URL and was skipped"));
+            continue;
+        };
+        imported.warnings.extend(scripts.iter().cloned());
+        imported
+            .warnings
+            .extend(item.event.iter().filter_map(Event::warning));
+
+        let mut result = finish_import(imported, rules);
+        if !folders.is_empty() {
+            let directories: Vec<&str> = folders.iter().map(|(_, dir)| dir.as_str()).collect();
+            result.suggested_filename = result
+                .suggested_filename
+                .map(|name| format!("{}/{name}", directories.join("/")));
+        }
+        output.requests.push(result);
+    }
+}
+
+fn convert(item: &Item, request: &RequestField, auth: Option<&Auth>) -> Option<ImportedRequest> {
+    let mut warnings = Vec::new();
+    let request = match request {
+        RequestField::Url(url) => {
+            return Some(ImportedRequest {
+                name: Some(item.name.clone()),
+                method: "GET".to_string(),
+                url: convert_double_braces(url, &mut warnings),
+                warnings,
+                ..ImportedRequest::default()
+            });
+        }
+        RequestField::Request(request) => request,
+    };
+
+    let mut url = match &request.url {
+        Some(UrlField::Raw(raw)) => raw.clone(),
+        Some(UrlField::Parts(url)) => with_path_variables(&url.raw, &url.variable),
+        None => String::new(),
+    };
+    if url.trim().is_empty() {
+        return None;
+    }
+
+    let mut headers: Vec<(String, String)> = request
+        .header
+        .iter()
+        .filter(|header| header.is_enabled())
+        .map(|header| (header.key.clone(), header.text()))
+        .collect();
+    let has_header = |headers: &[(String, String)], name: &str| {
+        headers
+            .iter()
+            .any(|(header, _)| header.eq_ignore_ascii_case(name))
+    };
+
+    let auth = match &request.auth {
+        Some(own) if own.kind != "inherit" => Some(own),
+        _ => auth,
+    };
+    if let Some(auth) = auth {
+        match auth.kind.as_str() {
+            "noauth" => {}
+            "bearer" => headers.push((
+                "Authorization".to_string(),
+                format!("Bearer {}", auth.setting("token")),
+            )),
+            "basic" => headers.extend(basic_auth_header(
+                &auth.setting("username"),
+                &auth.setting("password"),
+                &mut warnings,
+            )),
+            "apikey" => {
+                let pair = (auth.setting("key"), auth.setting("value"));
+                if auth.setting("in") == "query" {
+                    let separator = if url.contains('?') { '&' } else { '?' };
+                    url = format!("{url}{separator}{}", encode_pairs(&[pair]));
+                } else {
+                    headers.push(pair);
+                }
+            }
+            other => warnings.push(format!("{other} auth was not imported")),
+        }
+    }
+    for (_, value) in headers.iter_mut() {
+        *value = convert_double_braces(value, &mut warnings);
+    }
+
+    let mut body_text = None;
+    let mut body_file = None;
+    let mut content_type = None;
+    match request.body.as_ref().filter(|body| !body.disabled) {
+        None => {}
+        Some(body) => match body.mode.as_str() {
+            "raw" if !body.raw.is_empty() => {
+                body_text = Some(convert_double_braces(&body.raw, &mut warnings));
+                content_type = match body
+                    .options
+                    .pointer("/raw/language")
+                    .and_then(Value::as_str)
+                {
+                    Some("json") => Some("application/json"),
+                    Some("xml") => Some("application/xml"),
+                    Some("html") => Some("text/html"),
+                    Some("javascript") => Some("application/javascript"),
+                    _ => None,
+                };
+            }
+            "urlencoded" => {
+                let pairs: Vec<(String, String)> = body
+                    .urlencoded
+                    .iter()
+                    .filter(|pair| pair.is_enabled())
+                    .map(|pair| {
+                        (
+                            convert_double_braces(&pair.key, &mut warnings),
+                            convert_double_braces(&pair.text(), &mut warnings),
+                        )
+                    })
+                    .collect();
+                body_text = Some(encode_pairs(&pairs));
+                content_type = Some("application/x-www-form-urlencoded");
+            }
+            "file" => match body.file.as_ref().and_then(|file| file.src.clone()) {
+                Some(src) => body_file = Some(src),
+                None => warnings.push("file body has no path and was not imported".to_string()),
+            },
+            "graphql" => {
+                let graphql = body.graphql.as_ref();
+                let variables = graphql
+                    .and_then(|graphql| serde_json::from_str(&graphql.variables).ok())
+                    .unwrap_or_else(|| Value::Object(Default::default()));
+                let payload = serde_json::json!({
+                    "query": graphql.map(|graphql| graphql.query.as_str()).unwrap_or_default(),
+                    "variables": variables,
+                });
+                let text = serde_json::to_string_pretty(&payload).expect("values serialize");
+                body_text = Some(convert_double_braces(&text, &mut warnings));
+                content_type = Some("application/json");
+            }
+            "formdata" => warnings.push("multipart form data was not imported".to_string()),
+            _ => {}
+        },
+    }
+    if let Some(content_type) = content_type {
+        if !has_header(&headers, "content-type") {
+            headers.push(("Content-Type".to_string(), content_type.to_string()));
+        }
+    }
+
+    Some(ImportedRequest {
+        name: Some(item.name.clone()),
+        method: request
+            .method
+            .clone()
+            .unwrap_or_else(|| "GET".to_string())
+            .to_uppercase(),
+        url: convert_double_braces(&url, &mut warnings),
+        headers,
+        body_text,
+        body_file,
+        warnings,
+    })
+}
+
+/// Fills `:name` path segments with the values Postman sends for them.
+fn with_path_variables(raw: &str, variables: &[KeyValue]) -> String {
+    if variables.is_empty() {
+        return raw.to_string();
+    }
+    let (path, query) = match raw.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (raw, None),
+    };
+    let path = path
+        .split('/')
+        .map(|segment| {
+            segment
+                .strip_prefix(':')
+                .and_then(|name| variables.iter().find(|variable| variable.key == name))
+                .map(KeyValue::text)
+                .unwrap_or_else(|| segment.to_string())
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    match query {
+        Some(query) => format!("{path}?{query}"),
+        None => path,
+    }
+}
+
+/// Enabled variables with names curlpit can use as placeholders. Secret values are left out
+/// of the config, which is usually committed.
+fn variables(
+    entries: &[KeyValue],
+    scope: &str,
+    warnings: &mut Vec<String>,
+) -> BTreeMap<String, String> {
+    let mut variables = BTreeMap::new();
+    for entry in entries.iter().filter(|entry| entry.is_enabled()) {
+        if !is_placeholder_name(&entry.key) {
+            warnings.push(format!(
+                "{scope}: variable {:?} is not a valid placeholder name and was not imported",
+                entry.key
+            ));
+        } else if entry.kind.as_deref() == Some("secret") {
+            warnings.push(format!(
+                "{scope}: secret {} was not imported; set it in an env file",
+                entry.key
+            ));
+        } else {
+            let mut value_warnings = Vec::new();
+            let value = convert_double_braces(&entry.text(), &mut value_warnings);
+            warnings.extend(
+                value_warnings
+                    .into_iter()
+                    .map(|warning| format!("{scope}: {warning}")),
+            );
+            variables.insert(entry.key.clone(), value);
+        }
+    }
+    variables
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use once_cell::sync::Lazy;
+
+    static EMPTY: Lazy<HashMap<String, String>> = Lazy::new(HashMap::new);
+
+    fn rules() -> ImportRules<'static> {
+        ImportRules {
+            template_variables: &EMPTY,
+            env_variables: &EMPTY,
+            template_variants: &[],
+            include_headers: None,
+            exclude_headers: None,
+            append_headers: None,
+        }
+    }
+
+    const COLLECTION: &str = r#"{
+  "info": {
+    "name": "Demo API",
+    "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json"
+  },
+  "auth": {"type": "bearer", "bearer": [{"key": "token", "value": "{{API_TOKEN}}"}]},
+  "variable": [
+    {"key": "API_BASE", "value": "https://api.example.com"},
+    {"key": "user id", "value": "1"}
+  ],
+  "item": [
+    {
+      "name": "Users",
+      "event": [{"listen": "prerequest", "script": {"exec": ["pm.variables.set('a', 1)"]}}],
+      "item": [
+        {
+          "name": "Get user",
+          "event": [{"listen": "test", "script": {"exec": ["pm.test('ok')"]}}],
+          "request": {
+            "method": "GET",
+            "header": [
+              {"key": "Accept", "value": "application/json"},
+              {"key": "X-Debug", "value": "1", "disabled": true}
+            ],
+            "url": {
+              "raw": "{{API_BASE}}/users/:id?expand=true",
+              "variable": [{"key": "id", "value": "{{USER_ID}}"}]
+            }
+          }
+        },
+        {
+          "name": "Create user",
+          "request": {
+            "auth": {"type": "basic", "basic": [
+              {"key": "username", "value": "demo"},
+              {"key": "password", "value": "secret"}
+            ]},
+            "method": "POST",
+            "url": "{{API_BASE}}/users",
+            "body": {
+              "mode": "raw",
+              "raw": "{\"name\": \"{{$randomFirstName}}\"}",
+              "options": {"raw": {"language": "json"}}
+            }
+          }
+        }
+      ]
+    },
+    {
+      "name": "Login",
+      "request": {
+        "auth": {"type": "noauth"},
+        "method": "POST",
+        "url": {"raw": "{{API_BASE}}/login"},
+        "body": {"mode": "urlencoded", "urlencoded": [
+          {"key": "user", "value": "demo user"},
+          {"key": "remember", "value": "1", "disabled": true}
+        ]}
+      }
+    }
+  ]
+}"#;
+
+    #[test]
+    fn imports_folders_auth_and_bodies() -> Result<()> {
+        let imported = import_postman(COLLECTION, &[], &rules())?;
+        assert_eq!(imported.requests.len(), 3);
+
+        let get = &imported.requests[0];
+        assert_eq!(
+            get.suggested_filename.as_deref(),
+            Some("users/get-user.curl")
+        );
+        assert!(get.contents.contains(
+            "GET {API_BASE}/users/{USER_ID}?expand=true\nAccept: application/json\nAuthorization: Bearer {API_TOKEN}\n"
+        ));
+        assert_eq!(
+            get.warnings,
+            vec![
+                "Users: pre-request script was not imported".to_string(),
+                "test script was not imported".to_string(),
+            ]
+        );
+
+        let create = &imported.requests[1];
+        assert_eq!(
+            create.suggested_filename.as_deref(),
+            Some("users/create-user.curl")
+        );
+        assert!(create.contents.contains(
+            "POST {API_BASE}/users\nAuthorization: Basic ZGVtbzpzZWNyZXQ=\nContent-Type: application/json\n\n{\"name\": \"{{$randomFirstName}}\"}\n"
+        ));
+        assert!(create.warnings[0].contains("{{$randomFirstName}}"));
+        assert_eq!(
+            create.warnings[1..],
+            ["Users: pre-request script was not imported".to_string()]
+        );
+
+        let login = &imported.requests[2];
+        assert_eq!(login.suggested_filename.as_deref(), Some("login.curl"));
+        assert!(login.warnings.is_empty());
+        assert!(login.contents.contains(
+            "POST {API_BASE}/login\nContent-Type: application/x-www-form-urlencoded\n\nuser=demo%20user\n"
+        ));
+
+        assert_eq!(
+            imported.variables,
+            BTreeMap::from([(
+                "API_BASE".to_string(),
+                "https://api.example.com".to_string()
+            )])
+        );
+        assert_eq!(
+            imported.warnings,
+            vec![
+                "collection: variable \"user id\" is not a valid placeholder name and was not imported"
+                    .to_string(),
+                "Users: pre-request script was not imported".to_string(),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn environments_become_profiles_without_secrets() -> Result<()> {
+        let environment = r#"{
+  "name": "Staging EU",
+  "values": [
+    {"key": "API_BASE", "value": "https://staging.example.com", "enabled": true},
+    {"key": "API_TOKEN", "value": "abc", "type": "secret", "enabled": true},
+    {"key": "OLD", "value": "x", "enabled": false}
+  ],
+  "_postman_variable_scope": "environment"
+}"#;
+        let imported = import_postman(COLLECTION, &[environment.to_string()], &rules())?;
+        assert_eq!(imported.profiles.len(), 1);
+        assert_eq!(imported.profiles[0].name, "staging-eu");
+        assert_eq!(
+            imported.profiles[0].variables,
+            BTreeMap::from([(
+                "API_BASE".to_string(),
+                "https://staging.example.com".to_string()
+            )])
+        );
+        assert!(imported.warnings.contains(
+            &"environment Staging EU: secret API_TOKEN was not imported; set it in an env file"
+                .to_string()
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn collection_scripts_reach_every_request() -> Result<()> {
+        let collection = r#"{
+  "info": {"name": "Ping"},
+  "event": [{"listen": "prerequest", "script": {"exec": "pm.environment.set('TOKEN', 'x')"}}],
+  "item": [{"name": "Ping", "request": {"url": "https://example.com/ping"}}]
+}"#;
+        let imported = import_postman(collection, &[], &rules())?;
+        assert_eq!(
+            imported.requests[0].warnings,
+            vec!["collection: pre-request script was not imported".to_string()]
+        );
+        assert!(imported.requests[0]
+            .contents
+            .contains("# WARNING: collection: pre-request script was not imported"));
+        Ok(())
+    }
+
+    #[test]
+    fn environments_with_the_same_slug_stay_separate_profiles() -> Result<()> {
+        let environments = [
+            r#"{"name": "Staging", "values": [{"key": "HOST", "value": "s"}]}"#.to_string(),
+            r#"{"name": "staging!", "values": [{"key": "HOST", "value": "t"}]}"#.to_string(),
+        ];
+        let imported = import_postman(COLLECTION, &environments, &rules())?;
+        let names: Vec<&str> = imported
+            .profiles
+            .iter()
+            .map(|profile| profile.name.as_str())
+            .collect();
+        assert_eq!(names, ["staging", "staging-2"]);
+        assert_eq!(imported.profiles[1].variables["HOST"], "t");
+        assert!(imported.warnings.contains(
+            &"environment staging!: profile staging already exists; imported as staging-2"
+                .to_string()
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_v1_collections() {
+        let err = import_postman(
+            r#"{"info": {"schema": "https://schema.getpostman.com/json/collection/v1.0.0/collection.json"}}"#,
+            &[],
+            &rules(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("v2.0 and v2.1"));
+    }
+}
@@ -1,13 +1,210 @@
 use std::collections::HashMap;
 
 use chrono::Utc;
+use serde_json::Value;
 use url::Url;
 
+use crate::env::expand_placeholders;
+
+use super::headers::apply_header_rules;
+use super::model::{ImportResult, ImportRules};
+
+/// A request read from another format, before substitutions and header rules are applied.
+#[derive(Debug, Default)]
+pub(crate) struct ImportedRequest {
+    /// Name the source gave the request, used for the file name when present.
+    pub name: Option<String>,
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body_text: Option<String>,
+    pub body_file: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Applies variable substitutions and header rules and renders the request as a `.curl` file.
+pub(crate) fn finish_import(request: ImportedRequest, rules: &ImportRules<'_>) -> ImportResult {
+    let substitutions = build_substitutions(
+        rules.template_variables,
+        rules.env_variables,
+        rules.template_variants,
+    );
+
+    let substituted_url = apply_substitutions(&request.url, &substitutions);
+    let substituted_headers: Vec<(String, String)> = request
+        .headers
+        .iter()
+        .map(|(name, value)| (name.clone(), apply_substitutions(value, &substitutions)))
+        .collect();
+    let final_headers = apply_header_rules(
+        substituted_headers,
+        rules.include_headers,
+        rules.exclude_headers,
+        rules.append_headers,
+    );
+    let substituted_body = request
+        .body_text
+        .as_ref()
+        .map(|body| apply_substitutions(body, &substitutions));
+
+    let contents = format_curl_contents(
+        &request.method,
+        &substituted_url,
+        &final_headers,
+        substituted_body.as_deref(),
+        request.body_file.as_deref(),
+        &request.warnings,
+    );
+
+    // Names exported by curlpit itself are request paths such as `users/list.curl`
+    let name = request
+        .name
+        .as_deref()
+        .map(|name| slugify(name.strip_suffix(".curl").unwrap_or(name)));
+    let suggested_filename = match name {
+        Some(name) if !name.is_empty() => Some(format!("{name}.curl")),
+        _ => suggest_file_name(&request.method, &request.url).or_else(|| {
+            let name = slugify(&format!("{} {}", request.method, request.url));
+            (!name.is_empty()).then(|| format!("{name}.curl"))
+        }),
+    };
+
+    ImportResult {
+        contents,
+        suggested_filename,
+        method: request.method,
+        url: substituted_url,
+        warnings: request.warnings,
+    }
+}
+
+/// Rewrites `{{NAME}}` variables, as used by `.http` files, Hurl and Postman, into curlpit
+/// placeholders. Anything that is not a plain variable name is left as-is with a warning.
+pub(crate) fn convert_double_braces(input: &str, warnings: &mut Vec<String>) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            output.push_str(&rest[start..]);
+            return output;
+        };
+        let name = after[..end].trim();
+        if is_placeholder_name(name) {
+            output.push('{');
+            output.push_str(name);
+            output.push('}');
+        } else {
+            let warning = format!("{{{{{name}}}}} has no curlpit equivalent and was kept as-is");
+            if !warnings.contains(&warning) {
+                warnings.push(warning);
+            }
+            output.push_str(&rest[start..start + end + 4]);
+        }
+        rest = &after[end + 2..];
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Whether `name` can be used as a `{NAME}` placeholder.
+pub(crate) fn is_placeholder_name(name: &str) -> bool {
+    name.starts_with(|ch: char| ch.is_ascii_alphabetic() || ch == '_')
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '.' | '-'))
+}
+
+/// Lowercases `name` and joins its alphanumeric runs with dashes, for file and folder names.
+pub(crate) fn slugify(name: &str) -> String {
+    name.split(|ch: char| !ch.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+pub(crate) fn encode_pairs(pairs: &[(String, String)]) -> String {
+    pairs
+        .iter()
+        .map(|(key, value)| format!("{}={}", encode(key), encode(value)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Percent-encodes a query or form value, leaving `{NAME}` placeholders intact.
+fn encode(value: &str) -> String {
+    let mut output = String::new();
+    for ch in value.chars() {
+        if ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.' | '~' | '{' | '}') {
+            output.push(ch);
+        } else {
+            let mut buffer = [0; 4];
+            for byte in ch.encode_utf8(&mut buffer).bytes() {
+                output.push_str(&format!("%{byte:02X}"));
+            }
+        }
+    }
+    output
+}
+
+/// An `Authorization: Basic` header for literal credentials. Credentials that use `{{...}}`
+/// variables cannot be encoded ahead of time, so those only produce a warning.
+pub(crate) fn basic_auth_header(
+    username: &str,
+    password: &str,
+    warnings: &mut Vec<String>,
+) -> Option<(String, String)> {
+    let credentials = format!("{username}:{password}");
+    if credentials.contains("{{") {
+        warnings.push("basic auth with variables was not imported".to_string());
+        return None;
+    }
+    Some((
+        "Authorization".to_string(),
+        format!("Basic {}", base64(credentials.as_bytes())),
+    ))
+}
+
+fn base64(input: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut output = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let group = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for index in 0..4 {
+            if index <= chunk.len() {
+                output.push(ALPHABET[(group >> (18 - 6 * index) & 63) as usize] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+    output
+}
+
+/// The text of a JSON scalar, as used for variable and header values in exports.
+pub(crate) fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
 pub(crate) fn build_substitutions(
     template_vars: &HashMap<String, String>,
     env_vars: &HashMap<String, String>,
     variants: &[(String, String)],
 ) -> Vec<(String, String)> {
+    // Values may reference other variables; match against what they expand to
+    let expand = |value: &String| expand_placeholders(value, env_vars).unwrap_or(value.clone());
+
     let mut entries: Vec<(String, String)> = template_vars
         .iter()
         .chain(env_vars.iter())
@@ -15,19 +212,19 @@ pub(crate) fn build_substitutions(
             if value.is_empty() {
                 None
             } else {
-                Some((key.clone(), value.clone()))
+                Some((key.clone(), expand(value)))
             }
         })
         .collect();
 
     for (key, value) in variants {
         if !value.is_empty() {
-            entries.push((key.clone(), value.clone()));
+            entries.push((key.clone(), expand(value)));
         }
     }
 
     // Sort longest value first to avoid partial replacements
-    entries.sort_by(|a, b| b.1.len().cmp(&a.1.len()));
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.1.len()));
     entries
 }
 
@@ -46,6 +243,7 @@ pub(crate) fn format_curl_contents(
     url: &str,
     headers: &[(String, String)],
     body_text: Option<&str>,
+    body_file: Option<&str>,
     warnings: &[String],
 ) -> String {
     let mut lines = Vec::new();
@@ -60,11 +258,13 @@ pub(crate) fn format_curl_contents(
         lines.push(format!("{}: {}", name, value));
     }
 
-    if body_text.is_some() {
+    if body_text.is_some() || body_file.is_some() {
         lines.push(String::new());
     }
 
-    if let Some(text) = body_text {
+    if let Some(file) = body_file {
+        lines.push(format!("@body {file}"));
+    } else if let Some(text) = body_text {
         lines.push(text.to_string());
     }
 
@@ -106,6 +306,30 @@ pub(crate) fn suggest_file_name(method: &str, url: &str) -> Option<String> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn convert_double_braces_maps_variables_and_warns_about_the_rest() {
+        let mut warnings = Vec::new();
+        let converted = convert_double_braces(
+            "{{ API_BASE }}/users/{{id}}?trace={{$uuid}}&x={{$uuid}}&open={{",
+            &mut warnings,
+        );
+        assert_eq!(
+            converted,
+            "{API_BASE}/users/{id}?trace={{$uuid}}&x={{$uuid}}&open={{"
+        );
+        assert_eq!(
+            warnings,
+            vec!["{{$uuid}} has no curlpit equivalent and was kept as-is".to_string()]
+        );
+    }
+
+    #[test]
+    fn base64_pads_partial_chunks() {
+        assert_eq!(base64(b"a"), "YQ==");
+        assert_eq!(base64(b"ab"), "YWI=");
+        assert_eq!(base64(b"abc"), "YWJj");
+    }
+
     #[test]
     fn substitutions_favor_longer_matches_first() {
         let mut template = HashMap::new();
@@ -96,7 +96,8 @@ pub(crate) async fn run_interactive_with_ui(
                     profile.clone(),
                     options.explicit_env.clone(),
                     options.explicit_output_dir.clone(),
-                );
+                )
+                .for_request(&file.absolute)?;
                 let env = builder.build().await?;
                 ui.print("");
                 ui.print(&format!(
@@ -274,7 +275,7 @@ fn discover_curl_files(base_dir: &Path) -> Result<Vec<CurlFile>> {
             }
         }
     }
-    files.sort_by(|a, b| b.modified.cmp(&a.modified));
+    files.sort_by_key(|file| std::cmp::Reverse(file.modified));
     Ok(files)
 }
 
@@ -885,6 +886,7 @@ mod tests {
             },
             path: PathBuf::new(),
             dir: PathBuf::new(),
+            ..LoadedConfig::default()
         };
 
         let mut ui = TestUi::new(vec![1]);
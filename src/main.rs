@@ -1,9 +1,12 @@
 use std::path::{Path, PathBuf};
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use clap::{Parser, Subcommand};
-use curlpit::config::{load_config, EnvironmentBuilder};
-use curlpit::executor::{execute_request_file, ExecutionOptions};
+use colored::Colorize;
+use curlpit::config::{
+    check_config, config_schema, load_config, with_profile_names, EnvironmentBuilder,
+};
+use curlpit::executor::{dry_run_request_file, execute_request_file, ExecutionOptions};
 use curlpit::interactive::run_interactive;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -51,6 +54,10 @@ struct Cli {
     #[arg(long = "output", short = 'O')]
     output: Option<PathBuf>,
 
+    /// Print the resolved request without sending it
+    #[arg(long, requires = "request")]
+    dry_run: bool,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -60,14 +67,135 @@ enum Commands {
     /// Export a request using a named template
     Export {
         /// Export template name (e.g. js-fetch)
-        #[arg(value_name = "TEMPLATE")]
-        template: String,
-        /// Request file to export
-        #[arg(value_name = "REQUEST")]
-        request: PathBuf,
+        #[arg(value_name = "TEMPLATE", required_unless_present = "list")]
+        template: Option<String>,
+        /// Request file to export, or a folder of them for templates such as ts-client
+        #[arg(value_name = "REQUEST", required_unless_present = "list")]
+        request: Option<PathBuf>,
+        /// Output file (defaults to stdout)
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+        /// List the built-in and custom templates instead of exporting
+        #[arg(long, conflicts_with_all = ["template", "request", "out"])]
+        list: bool,
+        /// Keep placeholders: env values become runtime lookups, config variables parameters
+        #[arg(long)]
+        keep_placeholders: bool,
+    },
+    /// Import requests from another tool into .curl files
+    Import {
+        /// Source format
+        #[arg(value_name = "FORMAT", value_parser = ["http-file", "hurl", "postman", "insomnia", "har"])]
+        format: String,
+        /// File to import
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+        /// Postman environment export to add to curlpit.json as a profile (repeatable)
+        #[arg(long = "environment", value_name = "FILE")]
+        environments: Vec<PathBuf>,
+        /// Only import HAR entries whose URL matches this regular expression
+        #[arg(long = "url", value_name = "PATTERN")]
+        url_pattern: Option<String>,
+        /// Only import HAR entries with this method (repeatable)
+        #[arg(long = "method", value_name = "METHOD")]
+        methods: Vec<String>,
+        /// Only import HAR entries whose response content type contains this (repeatable)
+        #[arg(long = "content-type", value_name = "TYPE")]
+        content_types: Vec<String>,
+        /// Directory to write the request files to (defaults to the working directory)
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+        /// Overwrite existing request files
+        #[arg(long)]
+        force: bool,
+    },
+    /// Print the JSON Schema for curlpit config files
+    Schema {
         /// Output file (defaults to stdout)
         #[arg(short, long)]
         out: Option<PathBuf>,
+        /// Restrict defaultProfile and extends to the profiles in the loaded config
+        #[arg(long)]
+        with_profiles: bool,
+    },
+    /// Validate the configuration
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+    /// Manage encrypted env files
+    Secrets {
+        #[command(subcommand)]
+        command: SecretsCommands,
+    },
+    /// Inspect the variables profiles resolve to
+    Env {
+        #[command(subcommand)]
+        command: EnvCommands,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigCommands {
+    /// Report unknown keys, missing env files and invalid references
+    Check,
+}
+
+#[derive(Subcommand, Debug)]
+enum SecretsCommands {
+    /// Decrypt a secrets file into $EDITOR and encrypt it again on save
+    Edit {
+        /// Encrypted env file (created if missing)
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+    /// Set a variable in a secrets file
+    Set {
+        /// Encrypted env file (created if missing)
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+        /// Variable name
+        #[arg(value_name = "KEY")]
+        key: String,
+        /// Value (prompted for when omitted)
+        #[arg(value_name = "VALUE")]
+        value: Option<String>,
+    },
+    /// Print a variable from a secrets file
+    Get {
+        /// Encrypted env file
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+        /// Variable name
+        #[arg(value_name = "KEY")]
+        key: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum EnvCommands {
+    /// List the profiles defined in the configuration
+    List,
+    /// Show the variables and default headers a profile resolves to
+    Show {
+        /// Profile name
+        #[arg(value_name = "PROFILE")]
+        profile: String,
+        /// Print values instead of redacting them
+        #[arg(long)]
+        reveal: bool,
+    },
+    /// Compare the variables and default headers of two profiles
+    Diff {
+        /// First profile
+        #[arg(value_name = "A")]
+        left: String,
+        /// Second profile
+        #[arg(value_name = "B")]
+        right: String,
+        /// Print values instead of redacting them
+        #[arg(long)]
+        reveal: bool,
     },
 }
 
@@ -100,6 +228,22 @@ async fn main() -> Result<()> {
         }
     });
 
+    let checking = matches!(
+        cli.command,
+        Some(Commands::Config {
+            command: ConfigCommands::Check
+        })
+    );
+    if let (Some(loaded), false) = (&cfg, checking) {
+        for issue in check_config(loaded) {
+            eprintln!(
+                "{} {} {issue}",
+                "warning:".yellow().bold(),
+                loaded.path.display()
+            );
+        }
+    }
+
     let output_override = cli.output.as_ref().map(|p| resolve_relative(&base_dir, p));
 
     match &cli.command {
@@ -107,18 +251,139 @@ async fn main() -> Result<()> {
             template,
             request,
             out,
+            list,
+            keep_placeholders,
         }) => {
-            exports::handle_export(
-                template.clone(),
-                resolve_relative(&base_dir, request),
-                out.as_ref().map(|p| resolve_relative(&base_dir, p)),
-                cfg.as_ref(),
-                cli.profile.as_deref(),
-                cli.env.as_ref().map(|p| resolve_relative(&config_dir, p)),
-            )
+            match (template, request) {
+                (Some(template), Some(request)) if !*list => {
+                    exports::handle_export(
+                        template.clone(),
+                        resolve_relative(&base_dir, request),
+                        out.as_ref().map(|p| resolve_relative(&base_dir, p)),
+                        cfg.as_ref(),
+                        cli.profile.as_deref(),
+                        cli.env.as_ref().map(|p| resolve_relative(&config_dir, p)),
+                        *keep_placeholders,
+                    )
+                    .await?;
+                }
+                // clap only allows a missing template or request together with --list
+                _ => exports::list_templates(cfg.as_ref()),
+            }
+            return Ok(());
+        }
+        Some(Commands::Import {
+            format,
+            file,
+            environments,
+            url_pattern,
+            methods,
+            content_types,
+            out,
+            force,
+        }) => {
+            let har_filter = curlpit::importer::HarFilter {
+                url: url_pattern
+                    .as_deref()
+                    .map(regex::Regex::new)
+                    .transpose()
+                    .context("invalid --url pattern")?,
+                methods: methods.clone(),
+                content_types: content_types.clone(),
+            };
+            import_commands::handle_import(import_commands::ImportArgs {
+                format,
+                file: resolve_relative(&base_dir, file),
+                environments: environments
+                    .iter()
+                    .map(|p| resolve_relative(&base_dir, p))
+                    .collect(),
+                har_filter,
+                out_dir: out
+                    .as_ref()
+                    .map(|p| resolve_relative(&base_dir, p))
+                    .unwrap_or_else(|| base_dir.clone()),
+                base_dir: base_dir.clone(),
+                config: cfg.as_ref(),
+                profile: cli.profile.as_deref(),
+                explicit_env: cli.env.as_ref().map(|p| resolve_relative(&config_dir, p)),
+                force: *force,
+            })
             .await?;
             return Ok(());
         }
+        Some(Commands::Schema { out, with_profiles }) => {
+            let mut schema = config_schema();
+            if *with_profiles {
+                let mut names: Vec<String> = cfg
+                    .as_ref()
+                    .map(|c| c.config.profiles.keys().cloned().collect())
+                    .unwrap_or_default();
+                names.sort();
+                schema = with_profile_names(schema, &names);
+            }
+            let rendered = serde_json::to_string_pretty(&schema)? + "\n";
+            match out {
+                Some(path) => {
+                    let path = resolve_relative(&base_dir, path);
+                    std::fs::write(&path, rendered)
+                        .with_context(|| format!("writing {}", path.display()))?;
+                }
+                None => print!("{rendered}"),
+            }
+            return Ok(());
+        }
+        Some(Commands::Config {
+            command: ConfigCommands::Check,
+        }) => {
+            let loaded = cfg
+                .as_ref()
+                .ok_or_else(|| anyhow!("No curlpit configuration found"))?;
+            let issues = check_config(loaded);
+            if issues.is_empty() {
+                println!("{} {}", "✓".green(), loaded.path.display());
+                return Ok(());
+            }
+            println!("{}", loaded.path.display().to_string().bold());
+            for issue in &issues {
+                println!("  {} {issue}", "✗".red());
+            }
+            bail!("{} issue(s) found", issues.len());
+        }
+        Some(Commands::Secrets { command }) => {
+            match command {
+                SecretsCommands::Edit { file } => {
+                    secrets_commands::edit(&resolve_relative(&base_dir, file))?
+                }
+                SecretsCommands::Set { file, key, value } => {
+                    secrets_commands::set(&resolve_relative(&base_dir, file), key, value.clone())?
+                }
+                SecretsCommands::Get { file, key } => {
+                    secrets_commands::get(&resolve_relative(&base_dir, file), key)?
+                }
+            }
+            return Ok(());
+        }
+        Some(Commands::Env { command }) => {
+            let env_options = env_commands::EnvOptions {
+                base_dir: base_dir.clone(),
+                config_dir: config_dir.clone(),
+                config: cfg.clone(),
+                explicit_env: cli.env.as_ref().map(|p| resolve_relative(&config_dir, p)),
+            };
+            match command {
+                EnvCommands::List => env_commands::list(&env_options)?,
+                EnvCommands::Show { profile, reveal } => {
+                    env_commands::show(&env_options, profile, *reveal).await?
+                }
+                EnvCommands::Diff {
+                    left,
+                    right,
+                    reveal,
+                } => env_commands::diff(&env_options, left, right, *reveal).await?,
+            }
+            return Ok(());
+        }
         None => {}
     }
 
@@ -145,10 +410,17 @@ async fn main() -> Result<()> {
         cli.profile.clone(),
         cli.env.as_ref().map(|p| resolve_relative(&config_dir, p)),
         output_override.clone(),
-    );
+    )
+    .for_request(&request_path)?;
 
     let environment = env_builder.build().await?;
 
+    if cli.dry_run {
+        let result = dry_run_request_file(&request_path, &environment).await?;
+        curlpit::executor::print_dry_run_result(&result);
+        return Ok(());
+    }
+
     let result = execute_request_file(
         &request_path,
         ExecutionOptions {
@@ -245,6 +517,7 @@ mod tests {
             None,
             None,
             None,
+            false,
         )
         .await?;
 
@@ -263,24 +536,50 @@ mod tests {
             "POST https://example.com/items\n\n{\"ok\":true}\n",
         )?;
 
-        exports::handle_export("js-fetch".to_string(), request_path, None, None, None, None)
-            .await?;
+        exports::handle_export(
+            "js-fetch".to_string(),
+            request_path,
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .await?;
 
         Ok(())
     }
 }
 
 mod exports {
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
 
-    use anyhow::{Context, Result};
+    use anyhow::{bail, Context, Result};
+    use colored::Colorize;
+    use walkdir::WalkDir;
 
     use curlpit::{
-        config::{EnvironmentBuilder, LoadedConfig},
-        parser::{parse_request_file, RequestTemplate},
-        template,
+        config::{apply_overlays, EnvironmentBuilder, LoadedConfig},
+        parser::{parse_request_file, parse_request_template, RequestTemplate},
+        template::{self, NamedRequest},
     };
 
+    pub fn list_templates(config: Option<&LoadedConfig>) {
+        println!("{}", "Built-in templates".bold());
+        for name in template::BUILTIN_TEMPLATES {
+            println!("  {name}");
+        }
+        let custom = config
+            .map(LoadedConfig::export_templates)
+            .unwrap_or_default();
+        if !custom.is_empty() {
+            println!("{}", "Custom templates".bold());
+            for (name, path) in custom {
+                println!("  {name}  {}", path.display().to_string().dimmed());
+            }
+        }
+    }
+
     pub async fn handle_export(
         template_name: String,
         request_path: PathBuf,
@@ -288,28 +587,112 @@ mod exports {
         config: Option<&LoadedConfig>,
         profile: Option<&str>,
         explicit_env: Option<PathBuf>,
+        keep_placeholders: bool,
     ) -> Result<()> {
+        if request_path.is_dir() {
+            let rendered =
+                export_folder(&template_name, &request_path, config, profile, explicit_env).await?;
+            return write_export(rendered, out_path);
+        }
+
+        let environment = environment_builder(&request_path, config, profile, explicit_env)?
+            .build()
+            .await?;
+        let keep_placeholders =
+            keep_placeholders || template::FOLDER_TEMPLATES.contains(&template_name.as_str());
+        let tpl = if keep_placeholders {
+            parse_request_template(&request_path, &environment).await
+        } else {
+            parse_request_file(&request_path, &environment)
+                .await
+                .map(|parsed| RequestTemplate::from(&parsed.request))
+        }
+        .with_context(|| format!("parsing request {}", request_path.display()))?;
+
+        let custom = config
+            .map(|c| apply_overlays(c, &request_path))
+            .transpose()?
+            .map(|c| c.export_templates())
+            .unwrap_or_default();
+        let rendered = template::render_named_template(&template_name, &tpl, &custom)?;
+        write_export(rendered, out_path)
+    }
+
+    /// Renders every `.curl` file under `dir` into one export. Placeholders are always kept,
+    /// and default headers shared by the whole folder are passed to the template separately.
+    async fn export_folder(
+        template_name: &str,
+        dir: &Path,
+        config: Option<&LoadedConfig>,
+        profile: Option<&str>,
+        explicit_env: Option<PathBuf>,
+    ) -> Result<String> {
+        let shared = environment_builder(dir, config, profile, explicit_env.clone())?
+            .build()
+            .await?
+            .default_headers;
+        let mut default_headers: Vec<(String, String)> = shared.clone().into_iter().collect();
+        default_headers.sort();
+
+        let mut requests = Vec::new();
+        for entry in WalkDir::new(dir).sort_by_file_name() {
+            let entry = entry.with_context(|| format!("reading directory {}", dir.display()))?;
+            let path = entry.path();
+            if !entry.file_type().is_file() || path.extension().is_none_or(|ext| ext != "curl") {
+                continue;
+            }
+            let mut environment = environment_builder(path, config, profile, explicit_env.clone())?
+                .build()
+                .await?;
+            environment
+                .default_headers
+                .retain(|name, value| shared.get(name) != Some(value));
+            let template = parse_request_template(path, &environment)
+                .await
+                .with_context(|| format!("parsing request {}", path.display()))?;
+            let name = path
+                .strip_prefix(dir)
+                .unwrap_or(path)
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            requests.push(NamedRequest { name, template });
+        }
+        if requests.is_empty() {
+            bail!("No .curl files found in {}", dir.display());
+        }
+
+        template::render_folder_template(template_name, &requests, &default_headers)
+    }
+
+    fn environment_builder(
+        request_path: &Path,
+        config: Option<&LoadedConfig>,
+        profile: Option<&str>,
+        explicit_env: Option<PathBuf>,
+    ) -> Result<EnvironmentBuilder> {
         let cwd = std::env::current_dir()?;
-        let builder = EnvironmentBuilder::new(
+        let base_dir = if request_path.is_dir() {
+            request_path.to_path_buf()
+        } else {
             request_path
                 .parent()
                 .map(|p| p.to_path_buf())
-                .unwrap_or_else(|| cwd.clone()),
+                .unwrap_or_else(|| cwd.clone())
+        };
+        EnvironmentBuilder::new(
+            base_dir,
             config.map(|c| c.dir.clone()).unwrap_or_else(|| cwd.clone()),
             config.cloned(),
             profile.map(|s| s.to_string()),
             explicit_env,
             None,
-        );
-
-        let environment = builder.build().await?;
-        let parsed = parse_request_file(&request_path, &environment)
-            .await
-            .with_context(|| format!("parsing request {}", request_path.display()))?;
-
-        let tpl = RequestTemplate::from(&parsed.request);
-        let rendered = template::render_export_template(&template_name, &tpl)?;
+        )
+        .for_request(request_path)
+    }
 
+    fn write_export(rendered: String, out_path: Option<PathBuf>) -> Result<()> {
         if let Some(out) = out_path {
             std::fs::write(&out, rendered)
                 .with_context(|| format!("writing export to {}", out.display()))?;
@@ -320,3 +703,600 @@ mod exports {
         Ok(())
     }
 }
+
+mod import_commands {
+    use std::collections::HashSet;
+    use std::path::{Path, PathBuf};
+
+    use anyhow::{bail, Context, Result};
+    use colored::Colorize;
+    use serde_json::{json, Map, Value};
+
+    use curlpit::{
+        config::{set_json_member, EnvironmentBuilder, LoadedConfig},
+        importer::{
+            import_har, import_http_file, import_hurl, import_insomnia, import_postman, HarFilter,
+            ImportResult, ImportRules, ImportedCollection,
+        },
+    };
+
+    pub struct ImportArgs<'a> {
+        pub format: &'a str,
+        pub file: PathBuf,
+        /// Postman environment exports, added to the config as profiles
+        pub environments: Vec<PathBuf>,
+        pub har_filter: HarFilter,
+        pub out_dir: PathBuf,
+        /// Where a new `curlpit.json` is created when the project has none
+        pub base_dir: PathBuf,
+        pub config: Option<&'a LoadedConfig>,
+        pub profile: Option<&'a str>,
+        pub explicit_env: Option<PathBuf>,
+        pub force: bool,
+    }
+
+    pub async fn handle_import(args: ImportArgs<'_>) -> Result<()> {
+        if !args.environments.is_empty() && args.format != "postman" {
+            bail!("--environment is only supported when importing from postman");
+        }
+        if !args.har_filter.is_empty() && args.format != "har" {
+            bail!("--url, --method and --content-type are only supported when importing from har");
+        }
+        let contents = read(&args.file)?;
+
+        // Known variable values are turned back into placeholders, as with pasted commands
+        let environment = EnvironmentBuilder::new(
+            args.out_dir.clone(),
+            args.config
+                .map(|c| c.dir.clone())
+                .unwrap_or_else(|| args.out_dir.clone()),
+            args.config.cloned(),
+            args.profile.map(|s| s.to_string()),
+            args.explicit_env.clone(),
+            None,
+        )
+        .build()
+        .await?;
+        let import_cfg = args.config.and_then(|c| c.config.import.as_ref());
+        let rules = ImportRules {
+            template_variables: &environment.template_variables,
+            env_variables: &environment.initial_env,
+            template_variants: &environment.template_variants,
+            include_headers: import_cfg.and_then(|c| c.include_headers.as_deref()),
+            exclude_headers: import_cfg.and_then(|c| c.exclude_headers.as_deref()),
+            append_headers: import_cfg
+                .map(|c| &c.append_headers)
+                .filter(|headers| !headers.is_empty()),
+        };
+
+        let collection = match args.format {
+            "http-file" => ImportedCollection {
+                requests: import_http_file(&contents, &rules)?,
+                ..ImportedCollection::default()
+            },
+            "hurl" => ImportedCollection {
+                requests: import_hurl(&contents, &rules)?,
+                ..ImportedCollection::default()
+            },
+            "postman" => {
+                let environments = args
+                    .environments
+                    .iter()
+                    .map(|path| read(path))
+                    .collect::<Result<Vec<_>>>()?;
+                import_postman(&contents, &environments, &rules)?
+            }
+            "insomnia" => import_insomnia(&contents, &rules)?,
+            "har" => ImportedCollection {
+                requests: import_har(&contents, &args.har_filter, &rules)?,
+                ..ImportedCollection::default()
+            },
+            other => bail!("Unknown import format {other}"),
+        };
+
+        // Check every target before writing anything
+        let requests = plan_requests(&collection.requests, &args.out_dir, args.force)?;
+        let config_update = plan_config(&collection, args.config, &args.base_dir, args.force)?;
+
+        for (path, result) in requests {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, &result.contents)
+                .with_context(|| format!("writing {}", path.display()))?;
+            let display = path.strip_prefix(&args.out_dir).unwrap_or(&path);
+            println!("{} {}", "✓".green(), display.display());
+            for warning in &result.warnings {
+                println!("  {} {warning}", "warning:".yellow().bold());
+            }
+        }
+        if let Some((path, rendered)) = config_update {
+            std::fs::write(&path, rendered)
+                .with_context(|| format!("writing {}", path.display()))?;
+            println!(
+                "{} {} ({} variable(s), {} profile(s))",
+                "✓".green(),
+                path.display(),
+                collection.variables.len(),
+                collection.profiles.len()
+            );
+        }
+        for warning in &collection.warnings {
+            println!("{} {warning}", "warning:".yellow().bold());
+        }
+        Ok(())
+    }
+
+    fn read(path: &Path) -> Result<String> {
+        std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))
+    }
+
+    /// Picks a path for every imported request, refusing to replace existing files unless
+    /// `force` is set.
+    fn plan_requests<'a>(
+        results: &'a [ImportResult],
+        out_dir: &Path,
+        force: bool,
+    ) -> Result<Vec<(PathBuf, &'a ImportResult)>> {
+        let mut taken = HashSet::new();
+        let mut planned = Vec::new();
+        for result in results {
+            let name = result
+                .suggested_filename
+                .clone()
+                .unwrap_or_else(|| "request.curl".to_string());
+            let path = unique_path(out_dir, &name, &mut taken);
+            if path.exists() && !force {
+                bail!(
+                    "Refusing to overwrite {} (use --force to replace it)",
+                    path.display()
+                );
+            }
+            planned.push((path, result));
+        }
+        Ok(planned)
+    }
+
+    /// Requests that suggest the same file name get `-2`, `-3`, ... suffixes.
+    fn unique_path(out_dir: &Path, name: &str, taken: &mut HashSet<PathBuf>) -> PathBuf {
+        let stem = name.strip_suffix(".curl").unwrap_or(name);
+        let mut path = out_dir.join(name);
+        let mut counter = 2;
+        while !taken.insert(path.clone()) {
+            path = out_dir.join(format!("{stem}-{counter}.curl"));
+            counter += 1;
+        }
+        path
+    }
+
+    /// Adds imported variables and profiles to the project's JSON config, or to a new
+    /// `curlpit.json` in `base_dir`. Returns the path and its new contents.
+    fn plan_config(
+        collection: &ImportedCollection,
+        config: Option<&LoadedConfig>,
+        base_dir: &Path,
+        force: bool,
+    ) -> Result<Option<(PathBuf, String)>> {
+        if collection.variables.is_empty() && collection.profiles.is_empty() {
+            return Ok(None);
+        }
+        let Some(loaded) = config else {
+            return new_config(collection, base_dir, force).map(Some);
+        };
+
+        let path = &loaded.path;
+        let extension = path.extension().and_then(|ext| ext.to_str());
+        if !matches!(extension, Some("json" | "jsonc")) {
+            bail!(
+                "Imported variables and profiles can only be added to a JSON config, not {}",
+                path.display()
+            );
+        }
+        let edit_failed = || format!("{} cannot be updated automatically", path.display());
+
+        // Edits touch only the imported entries, so the rest of the file stays as written
+        let mut contents = read(path)?;
+        for (key, value) in &collection.variables {
+            match loaded.config.variables.get(key) {
+                Some(existing) if *existing == value.as_str() => continue,
+                Some(_) if !force => bail!(
+                    "Variable {key} is already set in {} (use --force to replace it)",
+                    path.display()
+                ),
+                _ => {}
+            }
+            contents = set_json_member(&contents, &["variables", key], &json!(value))
+                .with_context(edit_failed)?;
+        }
+        for profile in &collection.profiles {
+            if loaded.config.profiles.contains_key(&profile.name) && !force {
+                bail!(
+                    "Profile {} already exists in {} (use --force to replace it)",
+                    profile.name,
+                    path.display()
+                );
+            }
+            contents = set_json_member(
+                &contents,
+                &["profiles", &profile.name],
+                &json!({ "variables": profile.variables }),
+            )
+            .with_context(edit_failed)?;
+        }
+
+        // An existing default stays; a config without profiles gets the first imported one
+        if loaded.config.profiles.is_empty() {
+            let default = match collection.profiles.first() {
+                Some(profile) => profile.name.clone(),
+                None => {
+                    contents = set_json_member(&contents, &["profiles", "default"], &json!({}))
+                        .with_context(edit_failed)?;
+                    "default".to_string()
+                }
+            };
+            if loaded.config.default_profile.is_none() {
+                contents = set_json_member(&contents, &["defaultProfile"], &json!(default))
+                    .with_context(edit_failed)?;
+            }
+        }
+
+        Ok(Some((path.clone(), contents)))
+    }
+
+    fn new_config(
+        collection: &ImportedCollection,
+        base_dir: &Path,
+        force: bool,
+    ) -> Result<(PathBuf, String)> {
+        let path = base_dir.join("curlpit.json");
+        if path.exists() && !force {
+            bail!(
+                "Refusing to overwrite {} (use --force to replace it)",
+                path.display()
+            );
+        }
+        let mut profiles: Map<String, Value> = collection
+            .profiles
+            .iter()
+            .map(|profile| {
+                (
+                    profile.name.clone(),
+                    json!({ "variables": profile.variables }),
+                )
+            })
+            .collect();
+        // Running requests needs at least one profile
+        let default = match collection.profiles.first() {
+            Some(profile) => profile.name.clone(),
+            None => {
+                profiles.insert("default".to_string(), json!({}));
+                "default".to_string()
+            }
+        };
+        let root = json!({
+            "defaultProfile": default,
+            "variables": collection.variables,
+            "profiles": profiles,
+        });
+        Ok((path, serde_json::to_string_pretty(&root)? + "\n"))
+    }
+}
+
+mod env_commands {
+    use std::path::PathBuf;
+
+    use anyhow::{anyhow, Result};
+    use colored::Colorize;
+
+    use curlpit::{
+        config::{
+            diff_environments, inspect_variables, redact, resolve_profile, Difference, EntryDiff,
+            EntryValue, EnvironmentBuilder, EnvironmentContext, LoadedConfig,
+        },
+        env::describe_chain,
+    };
+
+    pub struct EnvOptions {
+        pub base_dir: PathBuf,
+        pub config_dir: PathBuf,
+        pub config: Option<LoadedConfig>,
+        pub explicit_env: Option<PathBuf>,
+    }
+
+    impl EnvOptions {
+        fn loaded(&self) -> Result<&LoadedConfig> {
+            self.config
+                .as_ref()
+                .ok_or_else(|| anyhow!("No curlpit configuration found"))
+        }
+
+        async fn build(&self, profile: &str) -> Result<EnvironmentContext> {
+            EnvironmentBuilder::new(
+                self.base_dir.clone(),
+                self.config_dir.clone(),
+                Some(self.loaded()?.clone()),
+                Some(profile.to_string()),
+                self.explicit_env.clone(),
+                None,
+            )
+            .build()
+            .await
+        }
+    }
+
+    pub fn list(options: &EnvOptions) -> Result<()> {
+        let loaded = options.loaded()?;
+        let default = resolve_profile(&loaded.config, None)?.name;
+
+        let mut names: Vec<&String> = loaded.config.profiles.keys().collect();
+        names.sort();
+        for name in names {
+            let profile = resolve_profile(&loaded.config, Some(name))?;
+            let marker = if *name == default { "*" } else { " " };
+            let mut details = vec![format!("{} variables", profile.config.variables.len())];
+            if let Some(extends) = &profile.config.extends {
+                details.push(format!("extends: {}", extends.parents().join(", ")));
+            }
+            if !profile.env_files.is_empty() {
+                details.push(format!("env: {}", profile.env_files.join(", ")));
+            }
+            println!(
+                "{} {} {}",
+                marker,
+                name.bold(),
+                format!("({})", details.join(", ")).dimmed()
+            );
+        }
+        Ok(())
+    }
+
+    pub async fn show(options: &EnvOptions, profile: &str, reveal: bool) -> Result<()> {
+        let environment = options.build(profile).await?;
+
+        println!("{} {}", "Profile:".bold(), profile.cyan());
+        for file in &environment.env_files {
+            println!(
+                "{} {}",
+                "Env file:".bold(),
+                file.display().to_string().dimmed()
+            );
+        }
+
+        println!("{}", "Variables".bold());
+        let variables = inspect_variables(&environment);
+        let mut keys: Vec<&String> = variables.keys().collect();
+        keys.sort();
+        for key in keys {
+            println!(
+                "  {} = {} {}",
+                key.cyan(),
+                display_entry(&variables[key], reveal),
+                format!("({})", describe_chain(environment.provenance.chain(key))).dimmed()
+            );
+        }
+
+        if !environment.default_headers.is_empty() {
+            println!("{}", "Default headers".bold());
+            let mut names: Vec<&String> = environment.default_headers.keys().collect();
+            names.sort();
+            for name in names {
+                println!(
+                    "  {}: {}",
+                    name.cyan(),
+                    redact_unless(&environment.default_headers[name], reveal)
+                );
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn diff(options: &EnvOptions, left: &str, right: &str, reveal: bool) -> Result<()> {
+        let left_env = options.build(left).await?;
+        let right_env = options.build(right).await?;
+        let diff = diff_environments(&left_env, &right_env);
+
+        if diff.is_empty() {
+            println!("Profiles {left} and {right} resolve to the same values");
+            return Ok(());
+        }
+
+        print_section("Variables", &diff.variables, left, right, reveal);
+        print_section(
+            "Default headers",
+            &diff.default_headers,
+            left,
+            right,
+            reveal,
+        );
+        Ok(())
+    }
+
+    fn print_section(title: &str, entries: &[EntryDiff], left: &str, right: &str, reveal: bool) {
+        if entries.is_empty() {
+            return;
+        }
+        let display = |value: &EntryValue| display_entry(value, reveal);
+
+        println!("{}", title.bold());
+        for entry in entries {
+            match &entry.difference {
+                Difference::OnlyLeft(value) => println!(
+                    "  {} {} {}",
+                    "-".red(),
+                    entry.key.cyan(),
+                    format!("only in {left}: {}", display(value)).dimmed()
+                ),
+                Difference::OnlyRight(value) => println!(
+                    "  {} {} {}",
+                    "+".green(),
+                    entry.key.cyan(),
+                    format!("only in {right}: {}", display(value)).dimmed()
+                ),
+                Difference::Changed {
+                    left: left_value,
+                    right: right_value,
+                } => println!(
+                    "  {} {} {}",
+                    "~".yellow(),
+                    entry.key.cyan(),
+                    format!(
+                        "{left}: {} | {right}: {}",
+                        display(left_value),
+                        display(right_value)
+                    )
+                    .dimmed()
+                ),
+            }
+        }
+    }
+
+    fn display_entry(value: &EntryValue, reveal: bool) -> String {
+        match value {
+            // Commands only run when a request uses them
+            EntryValue::Unresolved { command } => format!("$({command}) (unresolved)"),
+            EntryValue::Resolved(value) => redact_unless(value, reveal),
+        }
+    }
+
+    fn redact_unless(value: &str, reveal: bool) -> String {
+        if reveal {
+            value.to_string()
+        } else {
+            redact(value)
+        }
+    }
+}
+
+mod secrets_commands {
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+        process::Command,
+    };
+
+    use anyhow::{anyhow, bail, Context, Result};
+    use inquire::Password;
+
+    use curlpit::env::secrets::{
+        decrypt_env, encrypt_env, get_assignment, passphrase_from_env, set_assignment,
+    };
+
+    /// Decrypted contents of a secrets file plus the passphrase that opened it.
+    struct Opened {
+        plaintext: String,
+        passphrase: String,
+    }
+
+    fn open(path: &Path) -> Result<Opened> {
+        if !path.exists() {
+            let passphrase = match passphrase_from_env() {
+                Some(passphrase) => passphrase,
+                None => Password::new("New secrets passphrase:").prompt()?,
+            };
+            return Ok(Opened {
+                plaintext: String::new(),
+                passphrase,
+            });
+        }
+
+        let ciphertext =
+            fs::read(path).with_context(|| format!("reading secrets file {}", path.display()))?;
+        let passphrase = match passphrase_from_env() {
+            Some(passphrase) => passphrase,
+            None => Password::new("Secrets passphrase:")
+                .without_confirmation()
+                .prompt()?,
+        };
+        let plaintext = decrypt_env(&ciphertext, &passphrase)
+            .with_context(|| format!("decrypting {}", path.display()))?;
+        Ok(Opened {
+            plaintext,
+            passphrase,
+        })
+    }
+
+    fn save(path: &Path, plaintext: &str, passphrase: &str) -> Result<()> {
+        let encrypted = encrypt_env(plaintext, passphrase)?;
+        fs::write(path, encrypted).with_context(|| format!("writing {}", path.display()))
+    }
+
+    pub fn get(path: &Path, key: &str) -> Result<()> {
+        let opened = open(path)?;
+        let value = get_assignment(&opened.plaintext, key)?
+            .ok_or_else(|| anyhow!("{key} is not set in {}", path.display()))?;
+        println!("{value}");
+        Ok(())
+    }
+
+    pub fn set(path: &Path, key: &str, value: Option<String>) -> Result<()> {
+        let opened = open(path)?;
+        let value = match value {
+            Some(value) => value,
+            None => Password::new(&format!("{key}:"))
+                .without_confirmation()
+                .prompt()?,
+        };
+        let updated = set_assignment(&opened.plaintext, key, &value);
+        save(path, &updated, &opened.passphrase)?;
+        println!("Set {key} in {}", path.display());
+        Ok(())
+    }
+
+    pub fn edit(path: &Path) -> Result<()> {
+        let opened = open(path)?;
+        let scratch = scratch_file(&opened.plaintext)?;
+        let result = run_editor(&scratch);
+        let edited = fs::read_to_string(&scratch);
+        let _ = fs::remove_file(&scratch);
+        result?;
+        let edited = edited?;
+
+        if edited == opened.plaintext {
+            println!("No changes to {}", path.display());
+            return Ok(());
+        }
+        save(path, &edited, &opened.passphrase)?;
+        println!("Encrypted {}", path.display());
+        Ok(())
+    }
+
+    /// Writes the plaintext to a file only the current user can read.
+    fn scratch_file(plaintext: &str) -> Result<PathBuf> {
+        use std::io::Write;
+
+        let path =
+            std::env::temp_dir().join(format!("curlpit-secrets-{}.env", uuid::Uuid::new_v4()));
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options
+            .open(&path)
+            .with_context(|| format!("creating {}", path.display()))?;
+        file.write_all(plaintext.as_bytes())?;
+        Ok(path)
+    }
+
+    fn run_editor(path: &Path) -> Result<()> {
+        let editor = std::env::var("VISUAL")
+            .or_else(|_| std::env::var("EDITOR"))
+            .unwrap_or_else(|_| "vi".to_string());
+        let mut parts = shell_words::split(&editor)?;
+        if parts.is_empty() {
+            bail!("EDITOR is empty");
+        }
+        let program = parts.remove(0);
+        let status = Command::new(&program)
+            .args(parts)
+            .arg(path)
+            .status()
+            .with_context(|| format!("launching editor {program}"))?;
+        if !status.success() {
+            bail!("editor {program} exited with {status}");
+        }
+        Ok(())
+    }
+}
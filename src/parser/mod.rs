@@ -2,6 +2,8 @@ mod model;
 #[cfg(feature = "cli")]
 mod reader;
 
-pub use model::{ParsedRequest, RequestBody, RequestDefinition, RequestTemplate};
+pub use model::{
+    ParsedRequest, RequestBody, RequestDefinition, RequestTemplate, ResolvedPlaceholder,
+};
 #[cfg(feature = "cli")]
-pub use reader::parse_request_file;
+pub use reader::{parse_request_file, parse_request_template};
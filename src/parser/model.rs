@@ -1,5 +1,7 @@
 use std::path::PathBuf;
 
+use crate::env::VariableSource;
+
 #[derive(Debug, Clone)]
 pub enum RequestBody {
     Text(String),
@@ -21,15 +23,31 @@ pub struct RequestDefinition {
 pub struct ParsedRequest {
     pub request: RequestDefinition,
     pub env_files: Vec<PathBuf>,
+    pub placeholders: Vec<ResolvedPlaceholder>,
 }
 
+/// A placeholder used by a request together with the value it resolved to.
 #[derive(Debug, Clone)]
+pub struct ResolvedPlaceholder {
+    pub key: String,
+    pub value: String,
+    /// Every source that assigned the key; the last one supplied `value`.
+    pub chain: Vec<VariableSource>,
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct RequestTemplate {
     pub method: String,
     pub url: String,
     pub headers: Vec<(String, String)>,
     pub body_text: Option<String>,
     pub body_file: Option<PathBuf>,
+    /// Variables left as `{NAME}` placeholders in the text above; exported code reads them
+    /// from the environment at runtime instead of embedding their values.
+    pub env_vars: Vec<String>,
+    /// Variables left as `{NAME}` placeholders that came from config or profile variables;
+    /// exported code takes them as parameters.
+    pub params: Vec<String>,
 }
 
 impl From<&RequestDefinition> for RequestTemplate {
@@ -40,6 +58,8 @@ impl From<&RequestDefinition> for RequestTemplate {
             headers: value.headers.clone(),
             body_text: value.body_text.clone(),
             body_file: value.body_file.clone(),
+            env_vars: Vec::new(),
+            params: Vec::new(),
         }
     }
 }
@@ -7,10 +7,15 @@ use anyhow::{anyhow, bail, Context, Result};
 use once_cell::sync::Lazy;
 use tokio::fs;
 
-use crate::config::EnvironmentContext;
-use crate::env::{expand_placeholders, load_env_directive};
+use crate::config::{DeferredValues, EnvironmentContext};
+use crate::env::{
+    expand_placeholders_deferred, load_env_directive, resolve_variable, Deferred, EnvMap,
+    VariableSource,
+};
 
-use super::model::{ParsedRequest, RequestBody, RequestDefinition};
+use super::model::{
+    ParsedRequest, RequestBody, RequestDefinition, RequestTemplate, ResolvedPlaceholder,
+};
 
 static HTTP_METHODS: Lazy<HashSet<&'static str>> = Lazy::new(|| {
     [
@@ -28,16 +33,48 @@ pub async fn parse_request_file(
         .await
         .with_context(|| format!("reading request file {}", path.display()))?;
 
-    parse_request_contents(&raw, path, environment).await
+    parse_request_contents(&raw, path, environment, false).await
+}
+
+/// Parses a request for export, leaving placeholders in the request line, headers and body as
+/// `{NAME}`. Variables defined in the config become parameters; everything else (env files,
+/// `@env` directives, command variables, the process environment) is read at runtime.
+pub async fn parse_request_template(
+    path: &Path,
+    environment: &EnvironmentContext,
+) -> Result<RequestTemplate> {
+    let raw = fs::read_to_string(path)
+        .await
+        .with_context(|| format!("reading request file {}", path.display()))?;
+
+    let parsed = parse_request_contents(&raw, path, environment, true).await?;
+    let mut template = RequestTemplate::from(&parsed.request);
+    for placeholder in parsed.placeholders {
+        let from_config = matches!(
+            placeholder.chain.last(),
+            Some(VariableSource::Config { .. })
+        ) && !environment.command_variables.contains_key(&placeholder.key);
+        if from_config {
+            template.params.push(placeholder.key);
+        } else {
+            template.env_vars.push(placeholder.key);
+        }
+    }
+    Ok(template)
 }
 
 async fn parse_request_contents(
     contents: &str,
     path: &Path,
     environment: &EnvironmentContext,
+    keep_placeholders: bool,
 ) -> Result<ParsedRequest> {
     let mut env = environment.initial_env.clone();
     let mut env_files = environment.env_files.clone();
+    let mut provenance = environment.provenance.clone();
+    let mut used: Vec<String> = Vec::new();
+    let mut deferred = DeferredValues::new(&environment.command_variables, &environment.config_dir);
+    let mut lookup = |key: &str| deferred.lookup(key);
     let request_dir = path
         .parent()
         .map(|p| p.to_path_buf())
@@ -61,13 +98,14 @@ async fn parse_request_contents(
             if path_raw.is_empty() {
                 bail!("@env directive requires a file path");
             }
-            let expanded_path = expand_placeholders(path_raw, &env)?;
+            let expanded_path =
+                expand_placeholders_deferred(path_raw, &env, &mut used, &mut lookup)?;
             let env_path = request_dir.join(expanded_path);
-            load_env_directive(&env_path, &mut env, &mut env_files)?;
+            load_env_directive(&env_path, &mut env, &mut env_files, &mut provenance)?;
             continue;
         }
 
-        let expanded = expand_placeholders(trimmed, &env)?;
+        let expanded = expand_text(trimmed, &env, &mut used, &mut lookup, keep_placeholders)?;
         let mut parts = expanded.split_whitespace();
         if let Some(first) = parts.next() {
             let upper = first.to_ascii_uppercase();
@@ -101,7 +139,7 @@ async fn parse_request_contents(
             break;
         }
         lines.next();
-        let expanded = expand_placeholders(trimmed, &env)?;
+        let expanded = expand_text(trimmed, &env, &mut used, &mut lookup, keep_placeholders)?;
         if let Some((name, value)) = expanded.split_once(':') {
             headers.push((name.trim().to_string(), value.trim().to_string()));
         } else {
@@ -132,7 +170,8 @@ async fn parse_request_contents(
             if body_path_raw.is_empty() {
                 bail!("@body directive requires a file path");
             }
-            let expanded_path = expand_placeholders(body_path_raw, &env)?;
+            let expanded_path =
+                expand_placeholders_deferred(body_path_raw, &env, &mut used, &mut lookup)?;
             let resolved = request_dir.join(expanded_path);
             let bytes = fs::read(&resolved)
                 .await
@@ -147,7 +186,8 @@ async fn parse_request_contents(
             }
             let raw_body = body_lines.join("\n");
             if !raw_body.trim().is_empty() {
-                let expanded_body = expand_placeholders(&raw_body, &env)?;
+                let expanded_body =
+                    expand_text(&raw_body, &env, &mut used, &mut lookup, keep_placeholders)?;
                 body_bytes = Some(expanded_body.len());
                 body_text = Some(expanded_body.clone());
                 body = Some(RequestBody::Text(expanded_body));
@@ -155,6 +195,27 @@ async fn parse_request_contents(
         }
     }
 
+    let placeholders = used
+        .into_iter()
+        .map(|key| match (env.get(&key), deferred.resolved().get(&key)) {
+            (Some(value), _) => ResolvedPlaceholder {
+                value: resolve_variable(&key, &env).unwrap_or_else(|_| value.clone()),
+                chain: provenance.chain(&key).to_vec(),
+                key,
+            },
+            (None, Some(value)) => ResolvedPlaceholder {
+                value: value.clone(),
+                chain: provenance.chain(&key).to_vec(),
+                key,
+            },
+            (None, None) => ResolvedPlaceholder {
+                value: std::env::var(&key).unwrap_or_default(),
+                chain: vec![VariableSource::ProcessEnv],
+                key,
+            },
+        })
+        .collect();
+
     Ok(ParsedRequest {
         request: RequestDefinition {
             method,
@@ -166,9 +227,26 @@ async fn parse_request_contents(
             body_file,
         },
         env_files,
+        placeholders,
     })
 }
 
+/// Expands placeholders in request text, or with `keep` records them and leaves them as-is.
+fn expand_text(
+    input: &str,
+    env: &EnvMap,
+    used: &mut Vec<String>,
+    lookup: &mut Deferred<'_>,
+    keep: bool,
+) -> Result<String> {
+    if keep {
+        let mut placeholder = |key: &str| Some(Ok(format!("{{{key}}}")));
+        expand_placeholders_deferred(input, &EnvMap::new(), used, &mut placeholder)
+    } else {
+        expand_placeholders_deferred(input, env, used, lookup)
+    }
+}
+
 fn apply_default_headers(headers: &mut Vec<(String, String)>, defaults: &HashMap<String, String>) {
     for (name, value) in defaults {
         if !headers
@@ -199,6 +277,8 @@ mod tests {
             response_output_dir: None,
             default_headers: HashMap::new(),
             template_variants: Vec::new(),
+            provenance: Default::default(),
+            command_variables: HashMap::new(),
         }
     }
 
@@ -320,6 +400,16 @@ mod tests {
 
         assert_eq!(parsed.env_files.len(), 1);
         assert!(parsed.env_files[0].ends_with("extra.env"));
+        let keys: Vec<_> = parsed.placeholders.iter().map(|p| p.key.as_str()).collect();
+        assert_eq!(keys, vec!["PATH_SEGMENT", "TOKEN"]);
+        assert_eq!(
+            parsed.placeholders[1].chain,
+            vec![VariableSource::EnvDirective {
+                path: env_file.clone(),
+                line: Some(2)
+            }]
+        );
+        assert_eq!(parsed.placeholders[1].value, "abc123");
         assert_eq!(parsed.request.url, "https://example.com/widgets");
         assert_eq!(parsed.request.headers.len(), 1);
         assert_eq!(
@@ -329,4 +419,56 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn parse_request_template_keeps_placeholders() -> Result<()> {
+        let temp = tempdir()?;
+        let base = temp.path();
+
+        let mut env_context = base_environment(base);
+        env_context
+            .initial_env
+            .insert("API_BASE".to_string(), "http://localhost".to_string());
+        env_context.provenance.record(
+            "API_BASE",
+            VariableSource::Config {
+                path: base.join("curlpit.json"),
+                key: "profiles.dev.variables.API_BASE".to_string(),
+            },
+        );
+        tokio::fs::write(
+            base.join("extra.env"),
+            "TOKEN=abc123
+",
+        )
+        .await?;
+
+        let request_path = base.join("keep.curl");
+        tokio::fs::write(
+            &request_path,
+            "@env extra.env
+POST {API_BASE}/items
+Authorization: Bearer {TOKEN}
+
+{\"user\": \"{USER_ID}\"}
+",
+        )
+        .await?;
+
+        let template = parse_request_template(&request_path, &env_context).await?;
+
+        assert_eq!(template.method, "POST");
+        assert_eq!(template.url, "{API_BASE}/items");
+        assert_eq!(
+            template.headers,
+            vec![("Authorization".to_string(), "Bearer {TOKEN}".to_string())]
+        );
+        assert_eq!(
+            template.body_text.as_deref(),
+            Some("{\"user\": \"{USER_ID}\"}")
+        );
+        assert_eq!(template.params, vec!["API_BASE"]);
+        assert_eq!(template.env_vars, vec!["TOKEN", "USER_ID"]);
+        Ok(())
+    }
 }
@@ -0,0 +1,305 @@
+use anyhow::Result;
+
+use crate::parser::RequestTemplate;
+
+use super::{camel_case, expression, referenced_env_vars, ParamNames};
+
+/// Identifiers the generated method declares.
+const RESERVED: &[&str] = &["request", "response"];
+
+/// C# keywords.
+const KEYWORDS: &[&str] = &[
+    "abstract",
+    "as",
+    "base",
+    "bool",
+    "break",
+    "byte",
+    "case",
+    "catch",
+    "char",
+    "checked",
+    "class",
+    "const",
+    "continue",
+    "decimal",
+    "default",
+    "delegate",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "event",
+    "explicit",
+    "extern",
+    "false",
+    "finally",
+    "fixed",
+    "float",
+    "for",
+    "foreach",
+    "goto",
+    "if",
+    "implicit",
+    "in",
+    "int",
+    "interface",
+    "internal",
+    "is",
+    "lock",
+    "long",
+    "namespace",
+    "new",
+    "null",
+    "object",
+    "operator",
+    "out",
+    "override",
+    "params",
+    "private",
+    "protected",
+    "public",
+    "readonly",
+    "ref",
+    "return",
+    "sbyte",
+    "sealed",
+    "short",
+    "sizeof",
+    "stackalloc",
+    "static",
+    "string",
+    "struct",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "uint",
+    "ulong",
+    "unchecked",
+    "unsafe",
+    "ushort",
+    "using",
+    "virtual",
+    "void",
+    "volatile",
+    "while",
+];
+
+pub(super) fn render_csharp_httpclient(template: &RequestTemplate) -> Result<String> {
+    let params = ParamNames::new(template, camel_case, &[RESERVED, KEYWORDS].concat());
+    let string = |input: &str| {
+        expression(
+            input,
+            template,
+            quote,
+            |name| format!("Env({})", quote(name)),
+            |name| params.get(name),
+            " + ",
+        )
+    };
+
+    let has_body = template.body_file.is_some() || template.body_text.is_some();
+    let mut lines = vec![
+        "// Generated by curlpit".to_string(),
+        "using System;".to_string(),
+    ];
+    if template.body_file.is_some() {
+        lines.push("using System.IO;".to_string());
+    }
+    lines.push("using System.Net.Http;".to_string());
+    if has_body {
+        lines.push("using System.Net.Http.Headers;".to_string());
+    }
+    lines.extend(
+        [
+            "using System.Threading.Tasks;",
+            "",
+            "public static class CurlpitRequest",
+            "{",
+            "    private static readonly HttpClient Client = new HttpClient();",
+            "",
+        ]
+        .map(String::from),
+    );
+    let signature: Vec<String> = params
+        .iter()
+        .map(|(_, identifier)| format!("string {identifier}"))
+        .collect();
+    lines.push(format!(
+        "    public static async Task<HttpResponseMessage> RunRequestAsync({})",
+        signature.join(", ")
+    ));
+    lines.push("    {".to_string());
+    lines.push(format!(
+        "        using var request = new HttpRequestMessage(new HttpMethod({}), {});",
+        quote(&template.method.to_uppercase()),
+        string(&template.url)
+    ));
+
+    if let Some(body_file) = &template.body_file {
+        lines.push(format!(
+            "        request.Content = new StreamContent(File.OpenRead({}));",
+            quote(&body_file.to_string_lossy())
+        ));
+    } else if let Some(body) = &template.body_text {
+        lines.push(format!(
+            "        request.Content = new StringContent({});",
+            string(body)
+        ));
+    }
+
+    // Content-* headers belong to the body; duplicate names are appended rather than replaced
+    for (name, value) in &template.headers {
+        let lowered = name.to_ascii_lowercase();
+        if has_body && lowered == "content-type" {
+            lines.push(format!(
+                "        request.Content.Headers.ContentType = MediaTypeHeaderValue.Parse({});",
+                string(value)
+            ));
+        } else if has_body && lowered.starts_with("content-") {
+            lines.push(format!(
+                "        request.Content.Headers.TryAddWithoutValidation({}, {});",
+                quote(name),
+                string(value)
+            ));
+        } else {
+            lines.push(format!(
+                "        request.Headers.TryAddWithoutValidation({}, {});",
+                quote(name),
+                string(value)
+            ));
+        }
+    }
+
+    lines.extend(
+        [
+            "        var response = await Client.SendAsync(request);",
+            "        response.EnsureSuccessStatusCode();",
+            "        return response;",
+            "    }",
+        ]
+        .map(String::from),
+    );
+    if !referenced_env_vars(template).is_empty() {
+        lines.extend(
+            [
+                "",
+                "    private static string Env(string name) =>",
+                "        Environment.GetEnvironmentVariable(name)",
+                "            ?? throw new InvalidOperationException($\"{name} is not set\");",
+            ]
+            .map(String::from),
+        );
+    }
+    lines.push("}".to_string());
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// A verbatim string literal, which only needs quotes doubled.
+fn quote(value: &str) -> String {
+    format!("@\"{}\"", value.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn template() -> RequestTemplate {
+        RequestTemplate {
+            method: "post".to_string(),
+            url: "{API_BASE}/items".to_string(),
+            headers: vec![
+                (
+                    "Content-Type".to_string(),
+                    "application/json; charset=utf-8".to_string(),
+                ),
+                ("Accept".to_string(), "application/json".to_string()),
+                ("Accept".to_string(), "text/plain".to_string()),
+            ],
+            body_text: Some("{\"name\":\"demo\"}".to_string()),
+            body_file: None,
+            env_vars: vec!["API_BASE".to_string()],
+            params: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn json_body_snapshot() -> Result<()> {
+        assert_eq!(
+            render_csharp_httpclient(&template())?,
+            r#"// Generated by curlpit
+using System;
+using System.Net.Http;
+using System.Net.Http.Headers;
+using System.Threading.Tasks;
+
+public static class CurlpitRequest
+{
+    private static readonly HttpClient Client = new HttpClient();
+
+    public static async Task<HttpResponseMessage> RunRequestAsync()
+    {
+        using var request = new HttpRequestMessage(new HttpMethod(@"POST"), Env(@"API_BASE") + @"/items");
+        request.Content = new StringContent(@"{""name"":""demo""}");
+        request.Content.Headers.ContentType = MediaTypeHeaderValue.Parse(@"application/json; charset=utf-8");
+        request.Headers.TryAddWithoutValidation(@"Accept", @"application/json");
+        request.Headers.TryAddWithoutValidation(@"Accept", @"text/plain");
+        var response = await Client.SendAsync(request);
+        response.EnsureSuccessStatusCode();
+        return response;
+    }
+
+    private static string Env(string name) =>
+        Environment.GetEnvironmentVariable(name)
+            ?? throw new InvalidOperationException($"{name} is not set");
+}
+"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn file_bodies_stream_from_disk() -> Result<()> {
+        let mut template = template();
+        template.url = "https://api.example.com/upload".to_string();
+        template.env_vars.clear();
+        template.headers = vec![
+            (
+                "Content-Type".to_string(),
+                "application/octet-stream".to_string(),
+            ),
+            ("Content-Encoding".to_string(), "gzip".to_string()),
+        ];
+        template.body_text = None;
+        template.body_file = Some(PathBuf::from("payload.bin.gz"));
+        let rendered = render_csharp_httpclient(&template)?;
+        assert!(rendered.contains("using System.IO;\n"));
+        assert!(rendered.contains(
+            "        request.Content = new StreamContent(File.OpenRead(@\"payload.bin.gz\"));\n"
+        ));
+        assert!(rendered.contains(
+            "        request.Content.Headers.TryAddWithoutValidation(@\"Content-Encoding\", @\"gzip\");\n"
+        ));
+        assert!(!rendered.contains("private static string Env"));
+        Ok(())
+    }
+
+    #[test]
+    fn params_named_like_keywords_are_renamed() -> Result<()> {
+        let template = RequestTemplate {
+            method: "get".to_string(),
+            url: "{STRING}/items?event={EVENT}".to_string(),
+            params: vec!["STRING".to_string(), "EVENT".to_string()],
+            ..RequestTemplate::default()
+        };
+        let rendered = render_csharp_httpclient(&template)?;
+        assert!(rendered.contains("RunRequestAsync(string string2, string event2)"));
+        Ok(())
+    }
+}
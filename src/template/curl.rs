@@ -0,0 +1,120 @@
+use anyhow::Result;
+
+use crate::parser::RequestTemplate;
+
+use super::shell_word;
+
+pub(super) fn render_curl(template: &RequestTemplate) -> Result<String> {
+    let mut args = vec![format!(
+        "curl -X {} {}",
+        template.method.to_uppercase(),
+        shell_word(&template.url, template)
+    )];
+    for (name, value) in &template.headers {
+        // `Name:` with nothing after it would remove the header instead of sending it empty
+        let header = if value.is_empty() {
+            format!("{name};")
+        } else {
+            format!("{name}: {value}")
+        };
+        args.push(format!("-H {}", shell_word(&header, template)));
+    }
+    if let Some(body_file) = &template.body_file {
+        let file = format!("@{}", body_file.to_string_lossy());
+        args.push(format!("--data-binary {}", shell_words::quote(&file)));
+    } else if let Some(body) = &template.body_text {
+        args.push(format!("--data-raw {}", shell_word(body, template)));
+    }
+
+    Ok(args.join(" \\\n  ") + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::importer::{import_curl_command, ImportOptions};
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn import(command: &str) -> Result<crate::importer::ImportResult> {
+        let empty = HashMap::new();
+        import_curl_command(&ImportOptions {
+            command,
+            template_variables: &empty,
+            env_variables: &empty,
+            template_variants: &[],
+            include_headers: None,
+            exclude_headers: None,
+            append_headers: None,
+        })
+    }
+
+    fn template() -> RequestTemplate {
+        RequestTemplate {
+            method: "post".to_string(),
+            url: "https://api.example.com/items?page=1&size=2".to_string(),
+            headers: vec![
+                ("Content-Type".to_string(), "application/json".to_string()),
+                ("X-Note".to_string(), "it's here".to_string()),
+            ],
+            body_text: Some("{\"name\":\"O'Brien\"}".to_string()),
+            ..RequestTemplate::default()
+        }
+    }
+
+    #[test]
+    fn quotes_arguments_for_the_shell() -> Result<()> {
+        assert_eq!(
+            render_curl(&template())?,
+            r#"curl -X POST 'https://api.example.com/items?page=1&size=2' \
+  -H 'Content-Type: application/json' \
+  -H 'X-Note: it'\''s here' \
+  --data-raw '{"name":"O'\''Brien"}'
+"#
+        );
+
+        let mut with_env = template();
+        with_env.url = "{API_BASE}/items".to_string();
+        with_env.headers = vec![("Authorization".to_string(), "Bearer {TOKEN}".to_string())];
+        with_env.body_text = Some("cost: $5".to_string());
+        with_env.env_vars = vec!["API_BASE".to_string(), "TOKEN".to_string()];
+        let rendered = render_curl(&with_env)?;
+        assert!(rendered.starts_with("curl -X POST \"${API_BASE}/items\" \\\n"));
+        assert!(rendered.contains("-H \"Authorization: Bearer ${TOKEN}\""));
+        assert!(rendered.contains("--data-raw 'cost: $5'"));
+        Ok(())
+    }
+
+    #[test]
+    fn reads_names_the_shell_cannot_expand_with_printenv() -> Result<()> {
+        let mut template = template();
+        template.url = "{api.base}/items".to_string();
+        template.headers = vec![("X-Trace".to_string(), "{trace-id}".to_string())];
+        template.env_vars = vec!["api.base".to_string(), "trace-id".to_string()];
+        let rendered = render_curl(&template)?;
+        assert!(rendered.starts_with("curl -X POST \"$(printenv api.base)/items\" \\\n"));
+        assert!(rendered.contains("-H \"X-Trace: $(printenv trace-id)\""));
+        Ok(())
+    }
+
+    #[test]
+    fn round_trips_through_the_importer() -> Result<()> {
+        let imported = import(&render_curl(&template())?)?;
+        assert_eq!(imported.method, "POST");
+        assert_eq!(imported.url, "https://api.example.com/items?page=1&size=2");
+        assert!(imported.contents.contains(
+            "Content-Type: application/json\nX-Note: it's here\n\n{\"name\":\"O'Brien\"}\n"
+        ));
+
+        let mut with_file = template();
+        with_file.body_text = None;
+        with_file.body_file = Some(PathBuf::from("payloads/item one.json"));
+        let imported = import(&render_curl(&with_file)?)?;
+        assert_eq!(imported.method, "POST");
+        assert!(imported
+            .contents
+            .ends_with("\n\n@body payloads/item one.json\n"));
+        Ok(())
+    }
+}
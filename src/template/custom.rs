@@ -0,0 +1,412 @@
+use anyhow::{anyhow, bail, Result};
+use serde_json::{json, Value};
+
+use crate::parser::RequestTemplate;
+
+use super::{segments, shell_word, Segment};
+
+/// Helpers that may prefix a variable, as in `{{json url}}`.
+const HELPERS: &[&str] = &["json", "shell", "upper", "lower"];
+
+/// Renders a user-defined template written in a small Mustache dialect.
+///
+/// Supported tags are `{{name}}`, `{{helper name}}`, `{{#section}}`, `{{^inverted}}`,
+/// `{{/section}}` and `{{! comments }}`. Values are inserted as-is, never HTML-escaped.
+pub fn render_custom_template(source: &str, template: &RequestTemplate) -> Result<String> {
+    let nodes = parse(source)?;
+    let context = template_context(template);
+    let mut output = String::new();
+    render_nodes(&nodes, &mut vec![&context], template, &mut output)?;
+    Ok(output)
+}
+
+/// The values a custom template can reference.
+fn template_context(template: &RequestTemplate) -> Value {
+    let parts = |input: &str| -> Value {
+        segments(input, template)
+            .into_iter()
+            .map(|segment| match segment {
+                Segment::Text(text) => json!({ "text": text }),
+                Segment::Env(name) => json!({ "env": name }),
+                Segment::Param(name) => json!({ "param": name }),
+            })
+            .collect()
+    };
+    let headers: Vec<Value> = template
+        .headers
+        .iter()
+        .map(|(name, value)| {
+            json!({
+                "name": name,
+                "value": value,
+                "value_parts": parts(value),
+            })
+        })
+        .collect();
+
+    json!({
+        "method": template.method.to_uppercase(),
+        "url": template.url,
+        "url_parts": parts(&template.url),
+        "headers": headers,
+        "body": template.body_text,
+        "body_parts": template.body_text.as_deref().map(parts),
+        "body_file": template.body_file.as_ref().map(|path| path.to_string_lossy()),
+        "env_vars": template.env_vars,
+        "params": template.params,
+    })
+}
+
+#[derive(Debug)]
+enum Token {
+    Text(String),
+    Variable {
+        helper: Option<String>,
+        name: String,
+    },
+    Open {
+        name: String,
+        inverted: bool,
+        line: usize,
+    },
+    Close {
+        name: String,
+        line: usize,
+    },
+    Comment,
+}
+
+impl Token {
+    fn standalone(&self) -> bool {
+        matches!(
+            self,
+            Token::Open { .. } | Token::Close { .. } | Token::Comment
+        )
+    }
+}
+
+#[derive(Debug)]
+enum Node {
+    Text(String),
+    Variable {
+        helper: Option<String>,
+        name: String,
+    },
+    Section {
+        name: String,
+        inverted: bool,
+        children: Vec<Node>,
+    },
+}
+
+fn parse(source: &str) -> Result<Vec<Node>> {
+    let mut tokens = tokenize(source)?;
+    trim_standalone_lines(&mut tokens);
+
+    let mut stack: Vec<(String, bool, usize, Vec<Node>)> = Vec::new();
+    let mut nodes = Vec::new();
+    for token in tokens {
+        let node = match token {
+            Token::Text(text) => Node::Text(text),
+            Token::Variable { helper, name } => Node::Variable { helper, name },
+            Token::Comment => continue,
+            Token::Open {
+                name,
+                inverted,
+                line,
+            } => {
+                stack.push((name, inverted, line, std::mem::take(&mut nodes)));
+                continue;
+            }
+            Token::Close { name, line } => {
+                let Some((open, inverted, _, parent)) = stack.pop() else {
+                    bail!("line {line}: {{{{/{name}}}}} closes a section that was never opened");
+                };
+                if open != name {
+                    bail!("line {line}: {{{{/{name}}}}} does not match {{{{#{open}}}}}");
+                }
+                let children = std::mem::replace(&mut nodes, parent);
+                Node::Section {
+                    name,
+                    inverted,
+                    children,
+                }
+            }
+        };
+        nodes.push(node);
+    }
+    if let Some((name, _, line, _)) = stack.pop() {
+        bail!("line {line}: section {name} is never closed");
+    }
+    Ok(nodes)
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+    let mut line = 1;
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            tokens.push(Token::Text(rest[..start].to_string()));
+        }
+        line += rest[..start].matches('\n').count();
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| anyhow!("line {line}: unterminated tag"))?;
+        let tag = after[..end].trim();
+        tokens.push(parse_tag(tag, line)?);
+        line += after[..end].matches('\n').count();
+        rest = &after[end + 2..];
+    }
+    if !rest.is_empty() {
+        tokens.push(Token::Text(rest.to_string()));
+    }
+    Ok(tokens)
+}
+
+fn parse_tag(tag: &str, line: usize) -> Result<Token> {
+    if tag.starts_with('!') {
+        return Ok(Token::Comment);
+    }
+    if let Some(name) = tag.strip_prefix('#') {
+        return Ok(Token::Open {
+            name: name.trim().to_string(),
+            inverted: false,
+            line,
+        });
+    }
+    if let Some(name) = tag.strip_prefix('^') {
+        return Ok(Token::Open {
+            name: name.trim().to_string(),
+            inverted: true,
+            line,
+        });
+    }
+    if let Some(name) = tag.strip_prefix('/') {
+        return Ok(Token::Close {
+            name: name.trim().to_string(),
+            line,
+        });
+    }
+
+    let words: Vec<&str> = tag.split_whitespace().collect();
+    match words.as_slice() {
+        [name] => Ok(Token::Variable {
+            helper: None,
+            name: name.to_string(),
+        }),
+        [helper, name] if HELPERS.contains(helper) => Ok(Token::Variable {
+            helper: Some(helper.to_string()),
+            name: name.to_string(),
+        }),
+        [helper, _] => bail!(
+            "line {line}: unknown helper {helper} (expected one of {})",
+            HELPERS.join(", ")
+        ),
+        _ => bail!("line {line}: invalid tag {{{{{tag}}}}}"),
+    }
+}
+
+/// Drops the indentation and line break around section and comment tags that sit on a line
+/// of their own, so templates can use them without leaving blank lines behind.
+fn trim_standalone_lines(tokens: &mut [Token]) {
+    let standalone: Vec<usize> = (0..tokens.len())
+        .filter(|&index| tokens[index].standalone())
+        .filter(|&index| {
+            let starts_line = match index.checked_sub(1).map(|prev| &tokens[prev]) {
+                None => true,
+                Some(Token::Text(text)) => {
+                    let tail = text.rsplit('\n').next().unwrap_or_default();
+                    tail.trim().is_empty() && (text.contains('\n') || index == 1)
+                }
+                Some(_) => false,
+            };
+            let ends_line = match tokens.get(index + 1) {
+                None => true,
+                Some(Token::Text(text)) => text
+                    .split('\n')
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .is_empty(),
+                Some(_) => false,
+            };
+            starts_line && ends_line
+        })
+        .collect();
+
+    for index in standalone {
+        if let Some(Token::Text(text)) = index.checked_sub(1).map(|prev| &mut tokens[prev]) {
+            let keep = text.rfind('\n').map(|pos| pos + 1).unwrap_or(0);
+            text.truncate(keep);
+        }
+        if let Some(Token::Text(text)) = tokens.get_mut(index + 1) {
+            *text = match text.find('\n') {
+                Some(pos) => text[pos + 1..].to_string(),
+                None => String::new(),
+            };
+        }
+    }
+}
+
+fn render_nodes<'a>(
+    nodes: &'a [Node],
+    stack: &mut Vec<&'a Value>,
+    template: &RequestTemplate,
+    output: &mut String,
+) -> Result<()> {
+    for node in nodes {
+        match node {
+            Node::Text(text) => output.push_str(text),
+            Node::Variable { helper, name } => {
+                let value = display(lookup(stack, name));
+                output.push_str(&apply_helper(helper.as_deref(), &value, template));
+            }
+            Node::Section {
+                name,
+                inverted,
+                children,
+            } => {
+                let value = lookup(stack, name);
+                if *inverted {
+                    if !truthy(value) {
+                        render_nodes(children, stack, template, output)?;
+                    }
+                    continue;
+                }
+                match value {
+                    Some(Value::Array(items)) => {
+                        for item in items {
+                            stack.push(item);
+                            render_nodes(children, stack, template, output)?;
+                            stack.pop();
+                        }
+                    }
+                    Some(value) if truthy(Some(value)) => {
+                        stack.push(value);
+                        render_nodes(children, stack, template, output)?;
+                        stack.pop();
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn lookup<'a>(stack: &[&'a Value], name: &str) -> Option<&'a Value> {
+    if name == "." {
+        return stack.last().copied();
+    }
+    let mut keys = name.split('.');
+    let first = keys.next()?;
+    let mut value = stack.iter().rev().find_map(|scope| scope.get(first))?;
+    for key in keys {
+        value = value.get(key)?;
+    }
+    Some(value)
+}
+
+fn truthy(value: Option<&Value>) -> bool {
+    match value {
+        None | Some(Value::Null) | Some(Value::Bool(false)) => false,
+        Some(Value::String(text)) => !text.is_empty(),
+        Some(Value::Array(items)) => !items.is_empty(),
+        Some(_) => true,
+    }
+}
+
+fn display(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(text)) => text.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+fn apply_helper(helper: Option<&str>, value: &str, template: &RequestTemplate) -> String {
+    match helper {
+        Some("json") => serde_json::to_string(value).expect("strings serialize"),
+        Some("shell") => shell_word(value, template),
+        Some("upper") => value.to_uppercase(),
+        Some("lower") => value.to_lowercase(),
+        _ => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn template() -> RequestTemplate {
+        RequestTemplate {
+            method: "post".to_string(),
+            url: "{API_BASE}/items".to_string(),
+            headers: vec![
+                ("Accept".to_string(), "application/json".to_string()),
+                ("X-Note".to_string(), "it's \"quoted\"".to_string()),
+            ],
+            body_text: Some("{\"ok\":true}".to_string()),
+            body_file: None,
+            env_vars: vec!["API_BASE".to_string()],
+            params: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn renders_sections_helpers_and_env_parts() -> Result<()> {
+        let source = r#"{{! a tiny Python exporter }}
+url = {{#url_parts}}{{#text}}{{json text}}{{/text}}{{#env}}os.environ["{{env}}"]{{/env}} + {{/url_parts}}""
+headers = {
+  {{#headers}}
+  {{json name}}: {{json value}},
+  {{/headers}}
+}
+{{#body}}
+body = {{json body}}
+{{/body}}
+{{^body_file}}
+# no body file
+{{/body_file}}
+# {{lower method}} with {{#env_vars}}{{.}}{{/env_vars}}
+"#;
+        assert_eq!(
+            render_custom_template(source, &template())?,
+            r#"url = os.environ["API_BASE"] + "/items" + ""
+headers = {
+  "Accept": "application/json",
+  "X-Note": "it's \"quoted\"",
+}
+body = "{\"ok\":true}"
+# no body file
+# post with API_BASE
+"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn shell_helper_expands_env_vars() -> Result<()> {
+        let rendered = render_custom_template("curl {{shell url}}", &template())?;
+        assert_eq!(rendered, "curl \"${API_BASE}/items\"");
+        Ok(())
+    }
+
+    #[test]
+    fn reports_malformed_templates() {
+        let err = render_custom_template("{{#headers}}\n{{name}}\n", &template()).unwrap_err();
+        assert_eq!(err.to_string(), "line 1: section headers is never closed");
+
+        let err = render_custom_template("a\n{{#body}}{{/headers}}", &template()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2: {{/headers}} does not match {{#body}}"
+        );
+
+        let err = render_custom_template("{{yaml body}}", &template()).unwrap_err();
+        assert!(err.to_string().contains("unknown helper yaml"), "{err}");
+    }
+}
@@ -0,0 +1,239 @@
+use anyhow::Result;
+
+use crate::parser::RequestTemplate;
+
+use super::{camel_case, expression, referenced_env_vars, ParamNames};
+
+/// Identifiers the generated function declares or imports.
+const RESERVED: &[&str] = &[
+    "body", "context", "ctx", "err", "http", "os", "req", "strings", "url",
+];
+
+/// Go keywords.
+const KEYWORDS: &[&str] = &[
+    "break",
+    "case",
+    "chan",
+    "const",
+    "continue",
+    "default",
+    "defer",
+    "else",
+    "fallthrough",
+    "for",
+    "func",
+    "go",
+    "goto",
+    "if",
+    "import",
+    "interface",
+    "map",
+    "package",
+    "range",
+    "return",
+    "select",
+    "struct",
+    "switch",
+    "type",
+    "var",
+];
+
+pub(super) fn render_go_nethttp(template: &RequestTemplate) -> Result<String> {
+    let params = ParamNames::new(template, camel_case, &[RESERVED, KEYWORDS].concat());
+    let string = |input: &str| {
+        expression(
+            input,
+            template,
+            quote,
+            |name| format!("os.Getenv({})", quote(name)),
+            |name| params.get(name),
+            " + ",
+        )
+    };
+
+    let text_body = template
+        .body_text
+        .as_deref()
+        .filter(|_| template.body_file.is_none());
+    let mut imports = vec!["context", "net/http"];
+    if template.body_file.is_some() || !referenced_env_vars(template).is_empty() {
+        imports.push("os");
+    }
+    if text_body.is_some() {
+        imports.push("strings");
+    }
+
+    let mut lines = vec![
+        "// Generated by curlpit".to_string(),
+        "package curlpit".to_string(),
+        String::new(),
+        "import (".to_string(),
+    ];
+    lines.extend(imports.iter().map(|import| format!("\t{}", quote(import))));
+    lines.push(")".to_string());
+    lines.push(String::new());
+    let signature: String = params
+        .iter()
+        .map(|(_, identifier)| format!(", {identifier} string"))
+        .collect();
+    lines.push(format!(
+        "func RunRequest(ctx context.Context{signature}) (*http.Response, error) {{"
+    ));
+    lines.push(format!("\turl := {}", string(&template.url)));
+
+    let method = quote(&template.method.to_uppercase());
+    if let Some(body_file) = &template.body_file {
+        lines.push(format!(
+            "\tbody, err := os.Open({})",
+            quote(&body_file.to_string_lossy())
+        ));
+        push_check(&mut lines, None);
+        lines.push(format!(
+            "\treq, err := http.NewRequestWithContext(ctx, {method}, url, body)"
+        ));
+        // The client closes the body once the request is sent, but not if it is never built
+        push_check(&mut lines, Some("body.Close()"));
+    } else {
+        let body = match text_body {
+            Some(text) => {
+                lines.push(format!("\tbody := strings.NewReader({})", string(text)));
+                "body"
+            }
+            None => "http.NoBody",
+        };
+        lines.push(format!(
+            "\treq, err := http.NewRequestWithContext(ctx, {method}, url, {body})"
+        ));
+        push_check(&mut lines, None);
+    }
+
+    for (name, value) in &template.headers {
+        lines.push(format!(
+            "\treq.Header.Add({}, {})",
+            quote(name),
+            string(value)
+        ));
+    }
+    lines.push("\treturn http.DefaultClient.Do(req)".to_string());
+    lines.push("}".to_string());
+
+    Ok(lines.join("\n") + "\n")
+}
+
+fn push_check(lines: &mut Vec<String>, cleanup: Option<&str>) {
+    lines.push("\tif err != nil {".to_string());
+    if let Some(cleanup) = cleanup {
+        lines.push(format!("\t\t{cleanup}"));
+    }
+    lines.push("\t\treturn nil, err".to_string());
+    lines.push("\t}".to_string());
+}
+
+/// JSON string literals are valid Go interpreted string literals.
+fn quote(value: &str) -> String {
+    serde_json::to_string(value).expect("strings serialize")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn template() -> RequestTemplate {
+        RequestTemplate {
+            method: "post".to_string(),
+            url: "{API_BASE}/items".to_string(),
+            headers: vec![
+                ("Content-Type".to_string(), "application/json".to_string()),
+                ("X-Tag".to_string(), "a".to_string()),
+                ("X-Tag".to_string(), "b".to_string()),
+            ],
+            body_text: Some("{\"name\":\"demo\"}".to_string()),
+            body_file: None,
+            env_vars: vec!["API_BASE".to_string()],
+            params: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn text_bodies_use_string_readers() -> Result<()> {
+        let rendered = render_go_nethttp(&template())?;
+        assert_eq!(
+            rendered,
+            r#"// Generated by curlpit
+package curlpit
+
+import (
+	"context"
+	"net/http"
+	"os"
+	"strings"
+)
+
+func RunRequest(ctx context.Context) (*http.Response, error) {
+	url := os.Getenv("API_BASE") + "/items"
+	body := strings.NewReader("{\"name\":\"demo\"}")
+	req, err := http.NewRequestWithContext(ctx, "POST", url, body)
+	if err != nil {
+		return nil, err
+	}
+	req.Header.Add("Content-Type", "application/json")
+	req.Header.Add("X-Tag", "a")
+	req.Header.Add("X-Tag", "b")
+	return http.DefaultClient.Do(req)
+}
+"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn file_bodies_and_empty_bodies() -> Result<()> {
+        let mut file = template();
+        file.body_text = None;
+        file.body_file = Some(PathBuf::from("payload.bin"));
+        let rendered = render_go_nethttp(&file)?;
+        assert!(!rendered.contains("\"strings\""));
+        assert!(rendered.contains("\tbody, err := os.Open(\"payload.bin\")\n"));
+        assert!(rendered.contains("\t\tbody.Close()\n\t\treturn nil, err\n"));
+
+        let mut empty = template();
+        empty.body_text = None;
+        empty.env_vars.clear();
+        let rendered = render_go_nethttp(&empty)?;
+        assert!(!rendered.contains("\"os\""));
+        assert!(rendered.contains("(ctx, \"POST\", url, http.NoBody)"));
+        Ok(())
+    }
+
+    #[test]
+    fn params_named_like_locals_are_renamed() -> Result<()> {
+        let template = RequestTemplate {
+            method: "get".to_string(),
+            url: "{URL}/items?ctx={CTX}&again={url}".to_string(),
+            params: vec!["URL".to_string(), "CTX".to_string(), "url".to_string()],
+            ..RequestTemplate::default()
+        };
+        let rendered = render_go_nethttp(&template)?;
+        assert!(rendered.contains(
+            "func RunRequest(ctx context.Context, url2 string, ctx2 string, url3 string) (*http.Response, error) {\n\turl := url2 + \"/items?ctx=\" + ctx2 + \"&again=\" + url3\n"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn params_named_like_keywords_are_renamed() -> Result<()> {
+        let template = RequestTemplate {
+            method: "get".to_string(),
+            url: "{TYPE}/items?func={FUNC}".to_string(),
+            params: vec!["TYPE".to_string(), "FUNC".to_string()],
+            ..RequestTemplate::default()
+        };
+        let rendered = render_go_nethttp(&template)?;
+        assert!(rendered.contains(
+            "func RunRequest(ctx context.Context, type2 string, func2 string) (*http.Response, error) {\n\turl := type2 + \"/items?func=\" + func2\n"
+        ));
+        Ok(())
+    }
+}
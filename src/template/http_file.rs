@@ -0,0 +1,177 @@
+use anyhow::Result;
+
+use crate::parser::RequestTemplate;
+
+use super::{double_braced, with_default_headers, NamedRequest};
+
+/// Line starts that end a body: a new request, a response handler or reference, a file body.
+const SYNTAX_PREFIXES: &[&str] = &["###", "> ", ">>", "< ", "<> "];
+
+/// Renders requests in the `.http` format read by the JetBrains HTTP client and VS Code's REST
+/// Client, one `###` section per request.
+pub(super) fn render_http_file(
+    requests: &[NamedRequest],
+    default_headers: &[(String, String)],
+) -> Result<String> {
+    let mut requests: Vec<&NamedRequest> = requests.iter().collect();
+    requests.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut lines = Vec::new();
+    for request in requests {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.push(format!("### {}", request.name));
+        push_request(&mut lines, &request.template, default_headers);
+    }
+    Ok(lines.join("\n") + "\n")
+}
+
+pub(super) fn render_http_request(template: &RequestTemplate) -> Result<String> {
+    let mut lines = Vec::new();
+    push_request(&mut lines, template, &[]);
+    Ok(lines.join("\n") + "\n")
+}
+
+fn push_request(
+    lines: &mut Vec<String>,
+    template: &RequestTemplate,
+    default_headers: &[(String, String)],
+) {
+    let text = |input: &str| double_braced(input, template, str::to_string);
+    lines.push(format!(
+        "{} {}",
+        template.method.to_uppercase(),
+        text(&template.url)
+    ));
+    for (name, value) in with_default_headers(&template.headers, default_headers) {
+        lines.push(format!("{name}: {}", text(&value)));
+    }
+    if let Some(body_file) = &template.body_file {
+        lines.push(String::new());
+        lines.push(format!("< {}", body_file.to_string_lossy()));
+    } else if let Some(body) = &template.body_text {
+        let body = text(body);
+        if body.lines().any(|line| {
+            SYNTAX_PREFIXES
+                .iter()
+                .any(|prefix| line.starts_with(prefix))
+        }) {
+            // The format has no escapes, so the body would turn into another request, a response
+            // handler or a file reference
+            lines.push(
+                "# WARNING: body not exported: it has lines the .http format reads as syntax"
+                    .to_string(),
+            );
+        } else {
+            lines.push(String::new());
+            lines.push(body);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::importer::{import_http_file, ImportRules};
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    #[test]
+    fn folder_snapshot() -> Result<()> {
+        let requests = vec![
+            NamedRequest {
+                name: "users/create.curl".to_string(),
+                template: RequestTemplate {
+                    method: "post".to_string(),
+                    url: "{API_BASE}/users".to_string(),
+                    headers: vec![
+                        ("Content-Type".to_string(), "application/json".to_string()),
+                        ("X-Env".to_string(), "local".to_string()),
+                    ],
+                    body_text: Some("{\"name\":\"{USER}\"}".to_string()),
+                    env_vars: vec!["USER".to_string()],
+                    params: vec!["API_BASE".to_string()],
+                    ..RequestTemplate::default()
+                },
+            },
+            NamedRequest {
+                name: "upload.curl".to_string(),
+                template: RequestTemplate {
+                    method: "put".to_string(),
+                    url: "https://example.com/upload".to_string(),
+                    body_file: Some(PathBuf::from("payload.bin")),
+                    ..RequestTemplate::default()
+                },
+            },
+        ];
+        let defaults = vec![("X-Env".to_string(), "prod".to_string())];
+
+        assert_eq!(
+            render_http_file(&requests, &defaults)?,
+            r#"### upload.curl
+PUT https://example.com/upload
+X-Env: prod
+
+< payload.bin
+
+### users/create.curl
+POST {{API_BASE}}/users
+Content-Type: application/json
+X-Env: local
+
+{"name":"{{USER}}"}
+"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn bodies_the_format_cannot_hold_are_left_out() -> Result<()> {
+        let request = |name: &str, body: &str| NamedRequest {
+            name: name.to_string(),
+            template: RequestTemplate {
+                method: "post".to_string(),
+                url: format!("https://example.com/{name}"),
+                body_text: Some(body.to_string()),
+                ..RequestTemplate::default()
+            },
+        };
+        let requests = vec![
+            request("a.curl", "notes\n### not a request\n> not a handler"),
+            request("b.curl", "< not a file"),
+            request("c.curl", "plain\n  ### indented"),
+        ];
+        let rendered = render_http_file(&requests, &[])?;
+        assert_eq!(rendered.matches("# WARNING: body not exported").count(), 2);
+
+        let empty = HashMap::new();
+        let rules = ImportRules {
+            template_variables: &empty,
+            env_variables: &empty,
+            template_variants: &[],
+            include_headers: None,
+            exclude_headers: None,
+            append_headers: None,
+        };
+        let imported = import_http_file(&rendered, &rules)?;
+        let urls: Vec<&str> = imported.iter().map(|result| result.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://example.com/a.curl",
+                "https://example.com/b.curl",
+                "https://example.com/c.curl"
+            ]
+        );
+        assert!(imported[0]
+            .contents
+            .ends_with("POST https://example.com/a.curl\n"));
+        assert!(!imported[1].contents.contains("@body"));
+        assert!(imported[2]
+            .contents
+            .ends_with("\n\nplain\n  ### indented\n"));
+        Ok(())
+    }
+}
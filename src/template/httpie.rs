@@ -0,0 +1,89 @@
+use anyhow::Result;
+
+use crate::parser::RequestTemplate;
+
+use super::shell_word;
+
+pub(super) fn render_httpie(template: &RequestTemplate) -> Result<String> {
+    let mut args = vec![format!(
+        "http {} {}",
+        template.method.to_uppercase(),
+        shell_word(&template.url, template)
+    )];
+    for (name, value) in &template.headers {
+        // HTTPie sends `Name;` as an empty header; `Name:` alone would unset it
+        let item = if value.is_empty() {
+            format!("{name};")
+        } else {
+            format!("{name}:{value}")
+        };
+        args.push(shell_word(&item, template));
+    }
+
+    if let Some(body_file) = &template.body_file {
+        args.push(format!(
+            "< {}",
+            shell_words::quote(&body_file.to_string_lossy())
+        ));
+    } else if let Some(body) = &template.body_text {
+        args.push(format!("--raw {}", shell_word(body, template)));
+    }
+
+    Ok(args.join(" \\\n  ") + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn template() -> RequestTemplate {
+        RequestTemplate {
+            method: "post".to_string(),
+            url: "{API_BASE}/items?tag=a&tag=b".to_string(),
+            headers: vec![
+                ("Content-Type".to_string(), "application/json".to_string()),
+                (
+                    "Authorization".to_string(),
+                    "Bearer {API_TOKEN}".to_string(),
+                ),
+                ("X-Empty".to_string(), String::new()),
+            ],
+            body_text: Some("{\"name\":\"O'Brien\"}".to_string()),
+            body_file: None,
+            env_vars: vec!["API_BASE".to_string(), "API_TOKEN".to_string()],
+            params: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn text_body_snapshot() -> Result<()> {
+        assert_eq!(
+            render_httpie(&template())?,
+            r#"http POST "${API_BASE}/items?tag=a&tag=b" \
+  Content-Type:application/json \
+  "Authorization:Bearer ${API_TOKEN}" \
+  'X-Empty;' \
+  --raw '{"name":"O'\''Brien"}'
+"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn file_body_snapshot() -> Result<()> {
+        let mut template = template();
+        template.headers.truncate(1);
+        template.body_text = None;
+        template.body_file = Some(PathBuf::from("payloads/new item.json"));
+        assert_eq!(
+            render_httpie(&template)?,
+            r#"http POST "${API_BASE}/items?tag=a&tag=b" \
+  Content-Type:application/json \
+  < 'payloads/new item.json'
+"#
+        );
+        Ok(())
+    }
+}
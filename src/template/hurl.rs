@@ -0,0 +1,172 @@
+use anyhow::Result;
+
+use crate::parser::RequestTemplate;
+
+use super::{double_braced, with_default_headers, NamedRequest};
+
+/// Renders requests as Hurl entries, each preceded by a comment naming its request file.
+pub(super) fn render_hurl(
+    requests: &[NamedRequest],
+    default_headers: &[(String, String)],
+) -> Result<String> {
+    let mut requests: Vec<&NamedRequest> = requests.iter().collect();
+    requests.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut lines = Vec::new();
+    for request in requests {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.push(format!("# {}", request.name));
+        push_entry(&mut lines, &request.template, default_headers);
+    }
+    Ok(lines.join("\n") + "\n")
+}
+
+pub(super) fn render_hurl_request(template: &RequestTemplate) -> Result<String> {
+    let mut lines = Vec::new();
+    push_entry(&mut lines, template, &[]);
+    Ok(lines.join("\n") + "\n")
+}
+
+fn push_entry(
+    lines: &mut Vec<String>,
+    template: &RequestTemplate,
+    default_headers: &[(String, String)],
+) {
+    let value = |input: &str| double_braced(input, template, escape);
+    lines.push(format!(
+        "{} {}",
+        template.method.to_uppercase(),
+        value(&template.url)
+    ));
+    for (name, header) in with_default_headers(&template.headers, default_headers) {
+        lines.push(format!("{}: {}", escape(&name), value(&header)));
+    }
+    if let Some(body_file) = &template.body_file {
+        lines.push(format!(
+            "file,{};",
+            escape(&body_file.to_string_lossy()).replace(';', "\\;")
+        ));
+    } else if let Some(body) = &template.body_text {
+        let body = double_braced(body, template, str::to_string);
+        if body.lines().any(|line| line.trim() == "```") {
+            // A ``` line would end a multiline string early; a oneline string escapes instead
+            lines.push(format!("`{}`", escape_oneline(&body)));
+        } else {
+            // Multiline strings keep the body verbatim apart from templates
+            lines.push("```".to_string());
+            lines.push(body);
+            lines.push("```".to_string());
+        }
+    }
+}
+
+/// Escapes a body for a Hurl oneline string, which cannot span lines.
+fn escape_oneline(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('`', "\\`")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+/// Escapes the characters Hurl treats specially in unquoted values.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('#', "\\#")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::importer::{import_hurl, ImportRules};
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    #[test]
+    fn folder_snapshot() -> Result<()> {
+        let requests = vec![
+            NamedRequest {
+                name: "users/create.curl".to_string(),
+                template: RequestTemplate {
+                    method: "post".to_string(),
+                    url: "{API_BASE}/users#top".to_string(),
+                    headers: vec![(
+                        "Authorization".to_string(),
+                        "Bearer {API_TOKEN}".to_string(),
+                    )],
+                    body_text: Some("{\n  \"name\": \"demo\"\n}".to_string()),
+                    env_vars: vec!["API_TOKEN".to_string()],
+                    params: vec!["API_BASE".to_string()],
+                    ..RequestTemplate::default()
+                },
+            },
+            NamedRequest {
+                name: "upload.curl".to_string(),
+                template: RequestTemplate {
+                    method: "put".to_string(),
+                    url: "https://example.com/upload".to_string(),
+                    body_file: Some(PathBuf::from("data/payload.bin")),
+                    ..RequestTemplate::default()
+                },
+            },
+        ];
+        let defaults = vec![("Accept".to_string(), "application/json".to_string())];
+
+        assert_eq!(
+            render_hurl(&requests, &defaults)?,
+            r#"# upload.curl
+PUT https://example.com/upload
+Accept: application/json
+file,data/payload.bin;
+
+# users/create.curl
+POST {{API_BASE}}/users\#top
+Authorization: Bearer {{API_TOKEN}}
+Accept: application/json
+```
+{
+  "name": "demo"
+}
+```
+"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn bodies_with_fence_lines_round_trip() -> Result<()> {
+        let template = RequestTemplate {
+            method: "post".to_string(),
+            url: "https://example.com/notes".to_string(),
+            body_text: Some("Use:\n```\nlet a = `b` \\ {NAME};\n```\n".to_string()),
+            params: vec!["NAME".to_string()],
+            ..RequestTemplate::default()
+        };
+        let rendered = render_hurl_request(&template)?;
+        assert_eq!(
+            rendered,
+            "POST https://example.com/notes\n`Use:\\n\\`\\`\\`\\nlet a = \\`b\\` \\\\ {{NAME}};\\n\\`\\`\\`\\n`\n"
+        );
+
+        let empty = HashMap::new();
+        let rules = ImportRules {
+            template_variables: &empty,
+            env_variables: &empty,
+            template_variants: &[],
+            include_headers: None,
+            exclude_headers: None,
+            append_headers: None,
+        };
+        let imported = import_hurl(
+            &format!("{rendered}\nGET https://example.com/next\n"),
+            &rules,
+        )?;
+        assert_eq!(imported.len(), 2);
+        assert!(imported[0].contents.contains(
+            "POST https://example.com/notes\n\nUse:\n```\nlet a = `b` \\ {NAME};\n```\n"
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,252 @@
+use anyhow::Result;
+
+use crate::parser::RequestTemplate;
+
+use super::{camel_case, expression, referenced_env_vars, ParamNames};
+
+/// Headers `java.net.http.HttpClient` refuses to set by default.
+const RESTRICTED_HEADERS: &[&str] = &["connection", "content-length", "expect", "host", "upgrade"];
+
+/// Identifiers the generated method declares.
+const RESERVED: &[&str] = &["request"];
+
+/// Java keywords and literals.
+const KEYWORDS: &[&str] = &[
+    "abstract",
+    "assert",
+    "boolean",
+    "break",
+    "byte",
+    "case",
+    "catch",
+    "char",
+    "class",
+    "const",
+    "continue",
+    "default",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extends",
+    "false",
+    "final",
+    "finally",
+    "float",
+    "for",
+    "goto",
+    "if",
+    "implements",
+    "import",
+    "instanceof",
+    "int",
+    "interface",
+    "long",
+    "native",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "short",
+    "static",
+    "strictfp",
+    "super",
+    "switch",
+    "synchronized",
+    "this",
+    "throw",
+    "throws",
+    "transient",
+    "true",
+    "try",
+    "void",
+    "volatile",
+    "while",
+];
+
+pub(super) fn render_java_httpclient(template: &RequestTemplate) -> Result<String> {
+    let params = ParamNames::new(template, camel_case, &[RESERVED, KEYWORDS].concat());
+    let string = |input: &str| {
+        expression(
+            input,
+            template,
+            quote,
+            |name| format!("env({})", quote(name)),
+            |name| params.get(name),
+            " + ",
+        )
+    };
+
+    let mut lines = vec![
+        "// Generated by curlpit".to_string(),
+        "import java.net.URI;".to_string(),
+        "import java.net.http.HttpClient;".to_string(),
+        "import java.net.http.HttpRequest;".to_string(),
+        "import java.net.http.HttpResponse;".to_string(),
+    ];
+    if template.body_file.is_some() {
+        lines.push("import java.nio.file.Path;".to_string());
+    }
+    lines.push(String::new());
+    lines.push("public class CurlpitRequest {".to_string());
+    let signature: Vec<String> = params
+        .iter()
+        .map(|(_, identifier)| format!("String {identifier}"))
+        .collect();
+    lines.push(format!(
+        "    public static HttpResponse<String> runRequest({}) throws Exception {{",
+        signature.join(", ")
+    ));
+    lines.push("        HttpRequest request = HttpRequest.newBuilder()".to_string());
+    lines.push(format!(
+        "            .uri(URI.create({}))",
+        string(&template.url)
+    ));
+    for (name, value) in &template.headers {
+        if RESTRICTED_HEADERS.contains(&name.to_ascii_lowercase().as_str()) {
+            lines.push(format!(
+                "            // {name} is managed by HttpClient and cannot be set"
+            ));
+            continue;
+        }
+        lines.push(format!(
+            "            .header({}, {})",
+            quote(name),
+            string(value)
+        ));
+    }
+    let publisher = if let Some(body_file) = &template.body_file {
+        format!(
+            "HttpRequest.BodyPublishers.ofFile(Path.of({}))",
+            quote(&body_file.to_string_lossy())
+        )
+    } else if let Some(body) = &template.body_text {
+        format!("HttpRequest.BodyPublishers.ofString({})", string(body))
+    } else {
+        "HttpRequest.BodyPublishers.noBody()".to_string()
+    };
+    lines.push(format!(
+        "            .method({}, {publisher})",
+        quote(&template.method.to_uppercase())
+    ));
+    lines.push("            .build();".to_string());
+    lines.push("        return HttpClient.newHttpClient()".to_string());
+    lines.push("            .send(request, HttpResponse.BodyHandlers.ofString());".to_string());
+    lines.push("    }".to_string());
+
+    if !referenced_env_vars(template).is_empty() {
+        lines.extend(
+            [
+                "",
+                "    private static String env(String name) {",
+                "        String value = System.getenv(name);",
+                "        if (value == null) {",
+                "            throw new IllegalStateException(name + \" is not set\");",
+                "        }",
+                "        return value;",
+                "    }",
+            ]
+            .map(String::from),
+        );
+    }
+    lines.push("}".to_string());
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// JSON string literals are valid Java string literals.
+fn quote(value: &str) -> String {
+    serde_json::to_string(value).expect("strings serialize")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn template() -> RequestTemplate {
+        RequestTemplate {
+            method: "post".to_string(),
+            url: "{API_BASE}/items".to_string(),
+            headers: vec![
+                ("Content-Type".to_string(), "application/json".to_string()),
+                ("Accept".to_string(), "application/json".to_string()),
+                ("Accept".to_string(), "text/plain".to_string()),
+                ("Host".to_string(), "api.example.com".to_string()),
+            ],
+            body_text: Some("{\"name\":\"demo\"}".to_string()),
+            body_file: None,
+            env_vars: vec!["API_BASE".to_string()],
+            params: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn json_body_snapshot() -> Result<()> {
+        assert_eq!(
+            render_java_httpclient(&template())?,
+            r#"// Generated by curlpit
+import java.net.URI;
+import java.net.http.HttpClient;
+import java.net.http.HttpRequest;
+import java.net.http.HttpResponse;
+
+public class CurlpitRequest {
+    public static HttpResponse<String> runRequest() throws Exception {
+        HttpRequest request = HttpRequest.newBuilder()
+            .uri(URI.create(env("API_BASE") + "/items"))
+            .header("Content-Type", "application/json")
+            .header("Accept", "application/json")
+            .header("Accept", "text/plain")
+            // Host is managed by HttpClient and cannot be set
+            .method("POST", HttpRequest.BodyPublishers.ofString("{\"name\":\"demo\"}"))
+            .build();
+        return HttpClient.newHttpClient()
+            .send(request, HttpResponse.BodyHandlers.ofString());
+    }
+
+    private static String env(String name) {
+        String value = System.getenv(name);
+        if (value == null) {
+            throw new IllegalStateException(name + " is not set");
+        }
+        return value;
+    }
+}
+"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn file_bodies_use_file_publishers() -> Result<()> {
+        let mut template = template();
+        template.env_vars.clear();
+        template.body_text = None;
+        template.body_file = Some(PathBuf::from("payload.bin"));
+        let rendered = render_java_httpclient(&template)?;
+        assert!(rendered.contains("import java.nio.file.Path;\n"));
+        assert!(rendered.contains(
+            ".method(\"POST\", HttpRequest.BodyPublishers.ofFile(Path.of(\"payload.bin\")))"
+        ));
+        assert!(!rendered.contains("private static String env"));
+        Ok(())
+    }
+
+    #[test]
+    fn params_named_like_keywords_are_renamed() -> Result<()> {
+        let template = RequestTemplate {
+            method: "get".to_string(),
+            url: "{CLASS}/items?new={NEW}".to_string(),
+            params: vec!["CLASS".to_string(), "NEW".to_string()],
+            ..RequestTemplate::default()
+        };
+        let rendered = render_java_httpclient(&template)?;
+        assert!(rendered.contains("runRequest(String class2, String new2) throws Exception {"));
+        Ok(())
+    }
+}
@@ -1,16 +1,78 @@
-use std::collections::HashMap;
-
 use anyhow::Result;
 
 use crate::parser::RequestTemplate;
 
+use super::{camel_case, combined_headers, expression, ParamNames};
+
+/// Identifiers the generated function declares or calls.
+const RESERVED: &[&str] = &["fetch", "process", "response"];
+
+/// Words that cannot name a binding in strict-mode JavaScript.
+const KEYWORDS: &[&str] = &[
+    "arguments",
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "eval",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
 pub(super) fn render_js_fetch(template: &RequestTemplate) -> Result<String> {
-    let headers_map: HashMap<_, _> = template.headers.iter().cloned().collect();
-    let headers_block = if headers_map.is_empty() {
+    let params = ParamNames::new(template, camel_case, &[RESERVED, KEYWORDS].concat());
+    let string =
+        |input: &str| expression(input, template, quote, env, |name| params.get(name), " + ");
+
+    let headers = combined_headers(&template.headers);
+    let headers_block = if headers.is_empty() {
         String::new()
     } else {
-        let json = serde_json::to_string_pretty(&headers_map)?;
-        format!(",\n    headers: {json}")
+        let entries: Vec<String> = headers
+            .iter()
+            .map(|(name, value)| format!("      {}: {}", quote(name), string(value)))
+            .collect();
+        format!(",\n    headers: {{\n{}\n    }}", entries.join(",\n"))
     };
 
     let body_block = if let Some(body_file) = &template.body_file {
@@ -19,18 +81,31 @@ pub(super) fn render_js_fetch(template: &RequestTemplate) -> Result<String> {
             body_file.display()
         )
     } else if let Some(body_text) = &template.body_text {
-        let json = serde_json::to_string(body_text)?;
-        format!(",\n    body: {json}")
+        format!(",\n    body: {}", string(body_text))
     } else {
         String::new()
     };
 
     let method = template.method.to_uppercase();
-    let url = serde_json::to_string(&template.url)?;
+    let url = string(&template.url);
+    // Profile variables arrive as props, so callers can pass them per call
+    // A prop keeps its plain name even when its local has to be renamed
+    let props: Vec<String> = params
+        .iter()
+        .map(|(name, identifier)| match camel_case(name) {
+            prop if prop == identifier => prop,
+            prop => format!("{prop}: {identifier}"),
+        })
+        .collect();
+    let props = if props.is_empty() {
+        String::new()
+    } else {
+        format!("{{ {} }}", props.join(", "))
+    };
 
     Ok(format!(
         r#"// Generated by curlpit
-export async function runRequest() {{
+export async function runRequest({props}) {{
   const response = await fetch({url}, {{
     method: "{method}"{headers_block}{body_block}
   }});
@@ -44,3 +119,23 @@ export async function runRequest() {{
 "#
     ))
 }
+
+/// JSON string literals are valid JavaScript string literals.
+fn quote(value: &str) -> String {
+    serde_json::to_string(value).expect("strings serialize")
+}
+
+fn env(name: &str) -> String {
+    let identifier = name
+        .chars()
+        .next()
+        .is_some_and(|first| !first.is_ascii_digit())
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_');
+    if identifier {
+        format!("process.env.{name}")
+    } else {
+        format!("process.env[{}]", quote(name))
+    }
+}
@@ -0,0 +1,253 @@
+use anyhow::Result;
+
+use crate::parser::RequestTemplate;
+
+use super::{combined_headers, expression, referenced_env_vars, referenced_params, NamedRequest};
+
+/// Renders a k6 script that sends each request once per iteration, inside a `group` named
+/// after it. `default_headers` are shared by every request.
+pub(super) fn render_k6(
+    requests: &[NamedRequest],
+    default_headers: &[(String, String)],
+) -> Result<String> {
+    let mut requests: Vec<&NamedRequest> = requests.iter().collect();
+    requests.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut variables: Vec<String> = Vec::new();
+    for request in &requests {
+        let template = &request.template;
+        for name in referenced_params(template)
+            .into_iter()
+            .chain(referenced_env_vars(template))
+        {
+            if !variables.contains(&name) {
+                variables.push(name);
+            }
+        }
+    }
+
+    let mut lines = vec!["// Generated by curlpit".to_string()];
+    if !variables.is_empty() {
+        let flags: Vec<String> = variables
+            .iter()
+            .map(|name| format!("-e {name}=..."))
+            .collect();
+        lines.push(format!("// Run with: k6 run {} script.js", flags.join(" ")));
+    }
+    lines.push("import http from \"k6/http\";".to_string());
+    lines.push("import { check, group } from \"k6\";".to_string());
+    lines.push(String::new());
+
+    // k6 only allows open() in the init context, so file bodies are read up front
+    let mut files = Vec::new();
+    for request in &requests {
+        if let Some(body_file) = &request.template.body_file {
+            let name = format!("body{}", files.len());
+            lines.push(format!(
+                "const {name} = open({}, \"b\");",
+                quote(&body_file.to_string_lossy())
+            ));
+            files.push((request.name.as_str(), name));
+        }
+    }
+    if !files.is_empty() {
+        lines.push(String::new());
+    }
+
+    let mut defaults = combined_headers(default_headers);
+    defaults.sort_by(|a, b| a.0.cmp(&b.0));
+    if !defaults.is_empty() {
+        lines.push("const defaultHeaders = {".to_string());
+        for (name, value) in &defaults {
+            lines.push(format!("  {}: {},", quote(name), quote(value)));
+        }
+        lines.push("};".to_string());
+        lines.push(String::new());
+    }
+
+    lines.push("export default function () {".to_string());
+    for request in &requests {
+        let template = &request.template;
+        let string = |input: &str| expression(input, template, quote, env, env, " + ");
+        let body = if template.body_file.is_some() {
+            files
+                .iter()
+                .find(|(name, _)| *name == request.name)
+                .map(|(_, variable)| variable.clone())
+                .unwrap_or_else(|| "null".to_string())
+        } else if let Some(text) = &template.body_text {
+            string(text)
+        } else {
+            "null".to_string()
+        };
+
+        lines.push(format!("  group({}, () => {{", quote(&request.name)));
+        lines.push("    const res = http.request(".to_string());
+        lines.push(format!("      {},", quote(&template.method.to_uppercase())));
+        lines.push(format!("      {},", string(&template.url)));
+        lines.push(format!("      {body},"));
+        lines.push("      {".to_string());
+        let headers = combined_headers(&template.headers);
+        if defaults.is_empty() && headers.is_empty() {
+            lines.push("        headers: {},".to_string());
+        } else {
+            lines.push("        headers: {".to_string());
+            if !defaults.is_empty() {
+                lines.push("          ...defaultHeaders,".to_string());
+            }
+            for (name, value) in &headers {
+                lines.push(format!("          {}: {},", quote(name), string(value)));
+            }
+            lines.push("        },".to_string());
+        }
+        lines.push("      },".to_string());
+        lines.push("    );".to_string());
+        lines.push("    check(res, {".to_string());
+        lines
+            .push("      \"status is 2xx\": (r) => r.status >= 200 && r.status < 300,".to_string());
+        lines.push("    });".to_string());
+        lines.push("  });".to_string());
+    }
+    lines.push("}".to_string());
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Renders a single request as a k6 script.
+pub(super) fn render_k6_request(template: &RequestTemplate) -> Result<String> {
+    render_k6(
+        &[NamedRequest {
+            name: "request".to_string(),
+            template: template.clone(),
+        }],
+        &[],
+    )
+}
+
+/// Profile variables and env values alike are passed to k6 with `-e NAME=value`.
+fn env(name: &str) -> String {
+    let identifier = !name.starts_with(|ch: char| ch.is_ascii_digit())
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_');
+    if identifier {
+        format!("__ENV.{name}")
+    } else {
+        format!("__ENV[{}]", quote(name))
+    }
+}
+
+/// JSON string literals are valid JavaScript string literals.
+fn quote(value: &str) -> String {
+    serde_json::to_string(value).expect("strings serialize")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    #[test]
+    fn folder_snapshot() -> Result<()> {
+        let requests = vec![
+            NamedRequest {
+                name: "users/create.curl".to_string(),
+                template: RequestTemplate {
+                    method: "post".to_string(),
+                    url: "{API_BASE}/users".to_string(),
+                    headers: vec![
+                        ("Content-Type".to_string(), "application/json".to_string()),
+                        (
+                            "Authorization".to_string(),
+                            "Bearer {API_TOKEN}".to_string(),
+                        ),
+                    ],
+                    body_text: Some("{\"name\":\"demo\"}".to_string()),
+                    env_vars: vec!["API_TOKEN".to_string()],
+                    params: vec!["API_BASE".to_string()],
+                    ..RequestTemplate::default()
+                },
+            },
+            NamedRequest {
+                name: "upload.curl".to_string(),
+                template: RequestTemplate {
+                    method: "put".to_string(),
+                    url: "{API_BASE}/upload".to_string(),
+                    body_file: Some(PathBuf::from("fixtures/payload.bin")),
+                    params: vec!["API_BASE".to_string()],
+                    ..RequestTemplate::default()
+                },
+            },
+        ];
+        let defaults = vec![("X-Env".to_string(), "load".to_string())];
+
+        assert_eq!(
+            render_k6(&requests, &defaults)?,
+            r#"// Generated by curlpit
+// Run with: k6 run -e API_BASE=... -e API_TOKEN=... script.js
+import http from "k6/http";
+import { check, group } from "k6";
+
+const body0 = open("fixtures/payload.bin", "b");
+
+const defaultHeaders = {
+  "X-Env": "load",
+};
+
+export default function () {
+  group("upload.curl", () => {
+    const res = http.request(
+      "PUT",
+      __ENV.API_BASE + "/upload",
+      body0,
+      {
+        headers: {
+          ...defaultHeaders,
+        },
+      },
+    );
+    check(res, {
+      "status is 2xx": (r) => r.status >= 200 && r.status < 300,
+    });
+  });
+  group("users/create.curl", () => {
+    const res = http.request(
+      "POST",
+      __ENV.API_BASE + "/users",
+      "{\"name\":\"demo\"}",
+      {
+        headers: {
+          ...defaultHeaders,
+          "Content-Type": "application/json",
+          "Authorization": "Bearer " + __ENV.API_TOKEN,
+        },
+      },
+    );
+    check(res, {
+      "status is 2xx": (r) => r.status >= 200 && r.status < 300,
+    });
+  });
+}
+"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn single_request_without_variables() -> Result<()> {
+        let template = RequestTemplate {
+            method: "get".to_string(),
+            url: "https://example.com/{trace-id}".to_string(),
+            env_vars: vec!["trace-id".to_string()],
+            ..RequestTemplate::default()
+        };
+        let rendered = render_k6_request(&template)?;
+        assert!(rendered.contains("  group(\"request\", () => {\n"));
+        assert!(rendered
+            .contains("      \"https://example.com/\" + __ENV[\"trace-id\"],\n      null,\n"));
+        assert!(rendered.contains("        headers: {},\n"));
+        assert!(!rendered.contains("defaultHeaders"));
+        Ok(())
+    }
+}
@@ -0,0 +1,229 @@
+use anyhow::Result;
+
+use crate::parser::RequestTemplate;
+
+use super::{camel_case, content_type, expression, referenced_env_vars, ParamNames};
+
+/// Methods OkHttp only accepts with a request body.
+const BODY_REQUIRED: &[&str] = &["POST", "PUT", "PATCH", "PROPPATCH", "REPORT"];
+
+/// Identifiers the generated function declares or calls.
+const RESERVED: &[&str] = &["body", "env", "request"];
+
+/// Kotlin hard keywords.
+const KEYWORDS: &[&str] = &[
+    "as",
+    "break",
+    "class",
+    "continue",
+    "do",
+    "else",
+    "false",
+    "for",
+    "fun",
+    "if",
+    "in",
+    "interface",
+    "is",
+    "null",
+    "object",
+    "package",
+    "return",
+    "super",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typealias",
+    "typeof",
+    "val",
+    "var",
+    "when",
+    "while",
+];
+
+pub(super) fn render_kotlin_okhttp(template: &RequestTemplate) -> Result<String> {
+    let params = ParamNames::new(template, camel_case, &[RESERVED, KEYWORDS].concat());
+    let string = |input: &str| {
+        expression(
+            input,
+            template,
+            quote,
+            |name| format!("env({})", quote(name)),
+            |name| params.get(name),
+            " + ",
+        )
+    };
+
+    let method = template.method.to_uppercase();
+    // OkHttp derives Content-Type from the body's media type and overrides any header
+    let media_type = content_type(template)
+        .map(|value| format!("{}.toMediaType()", string(value)))
+        .unwrap_or_else(|| "null".to_string());
+    let body = if let Some(body_file) = &template.body_file {
+        Some(format!(
+            "File({}).asRequestBody({media_type})",
+            quote(&body_file.to_string_lossy())
+        ))
+    } else if let Some(text) = &template.body_text {
+        Some(format!("{}.toRequestBody({media_type})", string(text)))
+    } else if BODY_REQUIRED.contains(&method.as_str()) {
+        Some(format!("ByteArray(0).toRequestBody({media_type})"))
+    } else {
+        None
+    };
+
+    let mut imports = Vec::new();
+    if template.body_file.is_some() {
+        imports.push("java.io.File");
+    }
+    if content_type(template).is_some() && body.is_some() {
+        imports.push("okhttp3.MediaType.Companion.toMediaType");
+    }
+    imports.extend(["okhttp3.OkHttpClient", "okhttp3.Request"]);
+    if template.body_file.is_some() {
+        imports.push("okhttp3.RequestBody.Companion.asRequestBody");
+    } else if body.is_some() {
+        imports.push("okhttp3.RequestBody.Companion.toRequestBody");
+    }
+    imports.push("okhttp3.Response");
+
+    let mut lines = vec!["// Generated by curlpit".to_string()];
+    lines.extend(imports.iter().map(|import| format!("import {import}")));
+    lines.push(String::new());
+    let signature: Vec<String> = params
+        .iter()
+        .map(|(_, identifier)| format!("{identifier}: String"))
+        .collect();
+    lines.push(format!(
+        "fun runRequest({}): Response {{",
+        signature.join(", ")
+    ));
+    if let Some(body) = &body {
+        lines.push(format!("    val body = {body}"));
+    }
+    lines.push("    val request = Request.Builder()".to_string());
+    lines.push(format!("        .url({})", string(&template.url)));
+    for (name, value) in &template.headers {
+        if body.is_some() && name.eq_ignore_ascii_case("content-type") {
+            continue;
+        }
+        lines.push(format!(
+            "        .addHeader({}, {})",
+            quote(name),
+            string(value)
+        ));
+    }
+    let body_arg = if body.is_some() { "body" } else { "null" };
+    lines.push(format!("        .method({}, {body_arg})", quote(&method)));
+    lines.push("        .build()".to_string());
+    lines.push("    return OkHttpClient().newCall(request).execute()".to_string());
+    lines.push("}".to_string());
+
+    if !referenced_env_vars(template).is_empty() {
+        lines.push(String::new());
+        lines.push(
+            r#"private fun env(name: String): String = System.getenv(name) ?: error("$name is not set")"#
+                .to_string(),
+        );
+    }
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// A Kotlin string literal; `$` is escaped so it is not read as a template.
+fn quote(value: &str) -> String {
+    serde_json::to_string(value)
+        .expect("strings serialize")
+        .replace('$', "\\$")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn template() -> RequestTemplate {
+        RequestTemplate {
+            method: "post".to_string(),
+            url: "https://api.example.com/items".to_string(),
+            headers: vec![
+                ("Content-Type".to_string(), "application/json".to_string()),
+                ("X-Tag".to_string(), "a".to_string()),
+                ("X-Tag".to_string(), "b".to_string()),
+                ("Authorization".to_string(), "Bearer {TOKEN}".to_string()),
+            ],
+            body_text: Some("{\"price\":\"$5\"}".to_string()),
+            body_file: None,
+            env_vars: vec!["TOKEN".to_string()],
+            params: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn json_body_snapshot() -> Result<()> {
+        assert_eq!(
+            render_kotlin_okhttp(&template())?,
+            r#"// Generated by curlpit
+import okhttp3.MediaType.Companion.toMediaType
+import okhttp3.OkHttpClient
+import okhttp3.Request
+import okhttp3.RequestBody.Companion.toRequestBody
+import okhttp3.Response
+
+fun runRequest(): Response {
+    val body = "{\"price\":\"\$5\"}".toRequestBody("application/json".toMediaType())
+    val request = Request.Builder()
+        .url("https://api.example.com/items")
+        .addHeader("X-Tag", "a")
+        .addHeader("X-Tag", "b")
+        .addHeader("Authorization", "Bearer " + env("TOKEN"))
+        .method("POST", body)
+        .build()
+    return OkHttpClient().newCall(request).execute()
+}
+
+private fun env(name: String): String = System.getenv(name) ?: error("$name is not set")
+"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn file_and_empty_bodies() -> Result<()> {
+        let mut file = template();
+        file.headers.truncate(1);
+        file.env_vars.clear();
+        file.body_text = None;
+        file.body_file = Some(PathBuf::from("payload.bin"));
+        let rendered = render_kotlin_okhttp(&file)?;
+        assert!(rendered.contains("import java.io.File\n"));
+        assert!(rendered.contains(
+            "    val body = File(\"payload.bin\").asRequestBody(\"application/json\".toMediaType())\n"
+        ));
+
+        let empty = RequestTemplate {
+            method: "get".to_string(),
+            url: "https://api.example.com".to_string(),
+            ..RequestTemplate::default()
+        };
+        let rendered = render_kotlin_okhttp(&empty)?;
+        assert!(!rendered.contains("val body"));
+        assert!(rendered.contains("        .method(\"GET\", null)\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn params_named_like_keywords_are_renamed() -> Result<()> {
+        let template = RequestTemplate {
+            method: "get".to_string(),
+            url: "{OBJECT}/items?in={IN}".to_string(),
+            params: vec!["OBJECT".to_string(), "IN".to_string()],
+            ..RequestTemplate::default()
+        };
+        let rendered = render_kotlin_okhttp(&template)?;
+        assert!(rendered.contains("fun runRequest(object2: String, in2: String): Response {"));
+        Ok(())
+    }
+}
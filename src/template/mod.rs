@@ -1,16 +1,429 @@
-use anyhow::{bail, Result};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
 
 use crate::parser::RequestTemplate;
 
+mod csharp_httpclient;
+mod curl;
+mod custom;
+mod go_nethttp;
+mod http_file;
+mod httpie;
+mod hurl;
+mod java_httpclient;
 mod js_fetch;
+mod k6;
+mod kotlin_okhttp;
+mod powershell;
+mod python_requests;
+mod rust_reqwest;
+mod ts_client;
+mod wget;
+
+pub use custom::render_custom_template;
+
+/// Names accepted by [`render_export_template`].
+pub const BUILTIN_TEMPLATES: &[&str] = &[
+    "csharp-httpclient",
+    "curl",
+    "go-nethttp",
+    "http-file",
+    "httpie",
+    "hurl",
+    "java-httpclient",
+    "js-fetch",
+    "k6",
+    "kotlin-okhttp",
+    "powershell",
+    "python-requests",
+    "rust-reqwest",
+    "ts-client",
+    "wget",
+];
+
+/// Built-in templates that can also export a whole folder of requests as one file. They always
+/// keep placeholders, since the generated code is meant to run against any profile.
+pub const FOLDER_TEMPLATES: &[&str] = &["http-file", "hurl", "k6", "ts-client"];
+
+/// A request exported as part of a folder, named by its path relative to that folder.
+#[derive(Debug, Clone)]
+pub struct NamedRequest {
+    pub name: String,
+    pub template: RequestTemplate,
+}
 
 pub fn render_export_template(name: &str, template: &RequestTemplate) -> Result<String> {
     match name {
+        "csharp-httpclient" => csharp_httpclient::render_csharp_httpclient(template),
+        "curl" => curl::render_curl(template),
+        "go-nethttp" => go_nethttp::render_go_nethttp(template),
+        "http-file" => http_file::render_http_request(template),
+        "httpie" => httpie::render_httpie(template),
+        "hurl" => hurl::render_hurl_request(template),
+        "java-httpclient" => java_httpclient::render_java_httpclient(template),
         "js-fetch" => js_fetch::render_js_fetch(template),
+        "k6" => k6::render_k6_request(template),
+        "kotlin-okhttp" => kotlin_okhttp::render_kotlin_okhttp(template),
+        "powershell" => powershell::render_powershell(template),
+        "python-requests" => python_requests::render_python_requests(template),
+        "rust-reqwest" => rust_reqwest::render_rust_reqwest(template),
+        "ts-client" => ts_client::render_ts_client(
+            &[NamedRequest {
+                name: "run-request".to_string(),
+                template: template.clone(),
+            }],
+            &[],
+        ),
+        "wget" => wget::render_wget(template),
         other => bail!("Unknown export template: {other}"),
     }
 }
 
+/// Renders several requests into one file; `default_headers` are the headers they all share.
+pub fn render_folder_template(
+    name: &str,
+    requests: &[NamedRequest],
+    default_headers: &[(String, String)],
+) -> Result<String> {
+    match name {
+        "http-file" => http_file::render_http_file(requests, default_headers),
+        "hurl" => hurl::render_hurl(requests, default_headers),
+        "k6" => k6::render_k6(requests, default_headers),
+        "ts-client" => ts_client::render_ts_client(requests, default_headers),
+        other if BUILTIN_TEMPLATES.contains(&other) => {
+            bail!("Export template {other} cannot export a folder of requests")
+        }
+        other => bail!("Unknown export template: {other}"),
+    }
+}
+
+/// Renders a built-in template, falling back to the custom template files registered by name.
+pub fn render_named_template(
+    name: &str,
+    template: &RequestTemplate,
+    custom: &BTreeMap<String, PathBuf>,
+) -> Result<String> {
+    if BUILTIN_TEMPLATES.contains(&name) {
+        return render_export_template(name, template);
+    }
+    match custom.get(name) {
+        Some(path) => render_template_file(path, template),
+        None => render_export_template(name, template),
+    }
+}
+
+fn render_template_file(path: &Path, template: &RequestTemplate) -> Result<String> {
+    let source = std::fs::read_to_string(path)
+        .with_context(|| format!("reading export template {}", path.display()))?;
+    render_custom_template(&source, template)
+        .with_context(|| format!("rendering export template {}", path.display()))
+}
+
+/// Part of a request string: literal text, a variable read from the environment at runtime, or
+/// a variable passed in as a parameter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Text(String),
+    Env(String),
+    Param(String),
+}
+
+/// Splits `input` on `{NAME}` placeholders for the template's `env_vars` and `params`.
+fn segments(input: &str, template: &RequestTemplate) -> Vec<Segment> {
+    let mut result = Vec::new();
+    let mut text = String::new();
+    let mut rest = input;
+    while let Some(start) = rest.find('{') {
+        let after = &rest[start + 1..];
+        let placeholder = after.find('}').and_then(|end| {
+            let name = &after[..end];
+            if template.env_vars.iter().any(|var| var == name) {
+                Some(Segment::Env(name.to_string()))
+            } else if template.params.iter().any(|param| param == name) {
+                Some(Segment::Param(name.to_string()))
+            } else {
+                None
+            }
+        });
+        match placeholder {
+            Some(segment) => {
+                text.push_str(&rest[..start]);
+                if !text.is_empty() {
+                    result.push(Segment::Text(std::mem::take(&mut text)));
+                }
+                let (Segment::Env(name) | Segment::Param(name) | Segment::Text(name)) = &segment;
+                rest = &after[name.len() + 1..];
+                result.push(segment);
+            }
+            None => {
+                text.push_str(&rest[..=start]);
+                rest = after;
+            }
+        }
+    }
+    text.push_str(rest);
+    if !text.is_empty() || result.is_empty() {
+        result.push(Segment::Text(text));
+    }
+    result
+}
+
+/// Renders `input` as an expression joining quoted literals, environment lookups and parameters.
+fn expression(
+    input: &str,
+    template: &RequestTemplate,
+    literal: impl Fn(&str) -> String,
+    env: impl Fn(&str) -> String,
+    param: impl Fn(&str) -> String,
+    separator: &str,
+) -> String {
+    segments(input, template)
+        .iter()
+        .map(|segment| match segment {
+            Segment::Text(text) => literal(text),
+            Segment::Env(name) => env(name),
+            Segment::Param(name) => param(name),
+        })
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// Quotes `input` as one shell word; environment variables and parameters both become shell
+/// variables expanded at runtime. Names the shell cannot expand, such as `trace-id`, are read
+/// with `printenv` instead.
+fn shell_word(input: &str, template: &RequestTemplate) -> String {
+    let parts = segments(input, template);
+    if let [Segment::Text(text)] = parts.as_slice() {
+        return shell_words::quote(text).into_owned();
+    }
+    let mut quoted = String::from("\"");
+    for part in parts {
+        match part {
+            Segment::Text(text) => {
+                for ch in text.chars() {
+                    if matches!(ch, '"' | '\\' | '$' | '`') {
+                        quoted.push('\\');
+                    }
+                    quoted.push(ch);
+                }
+            }
+            Segment::Env(name) | Segment::Param(name) if is_shell_name(&name) => {
+                quoted.push_str(&format!("${{{name}}}"))
+            }
+            Segment::Env(name) | Segment::Param(name) => {
+                quoted.push_str(&format!("$(printenv {})", shell_words::quote(&name)))
+            }
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn is_shell_name(name: &str) -> bool {
+    name.chars()
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+}
+
+/// Environment variables referenced anywhere in the request, in order of first use.
+fn referenced_env_vars(template: &RequestTemplate) -> Vec<String> {
+    referenced(template, |segment| match segment {
+        Segment::Env(name) => Some(name),
+        _ => None,
+    })
+}
+
+/// Parameters referenced anywhere in the request, in order of first use.
+fn referenced_params(template: &RequestTemplate) -> Vec<String> {
+    referenced(template, |segment| match segment {
+        Segment::Param(name) => Some(name),
+        _ => None,
+    })
+}
+
+/// Identifiers for the request's parameters in one target language.
+///
+/// A name that clashes with a keyword, with an identifier the template declares itself, or with
+/// an earlier parameter once re-cased, gets a numeric suffix: `URL` becomes `url2` in Go.
+struct ParamNames(Vec<(String, String)>);
+
+impl ParamNames {
+    fn new(template: &RequestTemplate, case: impl Fn(&str) -> String, reserved: &[&str]) -> Self {
+        let mut names: Vec<(String, String)> = Vec::new();
+        for param in referenced_params(template) {
+            let base = case(&param);
+            let mut identifier = base.clone();
+            let mut suffix = 2;
+            while reserved.contains(&identifier.as_str())
+                || names.iter().any(|(_, taken)| *taken == identifier)
+            {
+                identifier = format!("{base}{suffix}");
+                suffix += 1;
+            }
+            names.push((param, identifier));
+        }
+        Self(names)
+    }
+
+    /// The identifier for the parameter `{name}`.
+    fn get(&self, name: &str) -> String {
+        self.0
+            .iter()
+            .find(|(param, _)| param == name)
+            .map(|(_, identifier)| identifier.clone())
+            .unwrap_or_else(|| name.to_string())
+    }
+
+    /// Parameters and their identifiers, in order of first use.
+    fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(param, identifier)| (param.as_str(), identifier.as_str()))
+    }
+}
+
+fn referenced(template: &RequestTemplate, pick: impl Fn(Segment) -> Option<String>) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let texts = std::iter::once(&template.url)
+        .chain(
+            template
+                .headers
+                .iter()
+                .flat_map(|(name, value)| [name, value]),
+        )
+        .chain(template.body_text.iter());
+    for text in texts {
+        for name in segments(text, template).into_iter().filter_map(&pick) {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    names
+}
+
+/// `API_BASE` as `api_base`, for languages that name parameters in snake case.
+fn snake_case(name: &str) -> String {
+    words(name).join("_")
+}
+
+/// `API_BASE` as `apiBase`, for languages that name parameters in camel case.
+fn camel_case(name: &str) -> String {
+    let mut result = String::new();
+    for (index, word) in words(name).iter().enumerate() {
+        if index == 0 {
+            result.push_str(word);
+        } else {
+            let mut chars = word.chars();
+            if let Some(first) = chars.next() {
+                result.push(first.to_ascii_uppercase());
+                result.push_str(chars.as_str());
+            }
+        }
+    }
+    result
+}
+
+/// `API_BASE` as `ApiBase`, for languages that name parameters in Pascal case.
+fn pascal_case(name: &str) -> String {
+    let camel = camel_case(name);
+    let mut chars = camel.chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+        None => camel,
+    }
+}
+
+fn words(name: &str) -> Vec<String> {
+    name.split(|ch: char| !ch.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect()
+}
+
+fn content_type(template: &RequestTemplate) -> Option<&str> {
+    template
+        .headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+        .map(|(_, value)| value.as_str())
+}
+
+/// Parses the text body when the request declares a JSON content type.
+fn json_body<T: serde::de::DeserializeOwned>(template: &RequestTemplate) -> Option<T> {
+    let is_json = content_type(template)
+        .map(|value| value.to_ascii_lowercase().contains("json"))
+        .unwrap_or(false);
+    if !is_json {
+        return None;
+    }
+    let body = template.body_text.as_deref()?;
+    if segments(body, template)
+        .iter()
+        .any(|segment| !matches!(segment, Segment::Text(_)))
+    {
+        return None;
+    }
+    serde_json::from_str(body).ok()
+}
+
+/// Renders `input` with placeholders in the `{{NAME}}` syntax shared by `.http` files and Hurl.
+fn double_braced(input: &str, template: &RequestTemplate, text: impl Fn(&str) -> String) -> String {
+    expression(
+        input,
+        template,
+        text,
+        |name| format!("{{{{{name}}}}}"),
+        |name| format!("{{{{{name}}}}}"),
+        "",
+    )
+}
+
+/// The request's headers followed by any `defaults` it does not set itself.
+fn with_default_headers(
+    headers: &[(String, String)],
+    defaults: &[(String, String)],
+) -> Vec<(String, String)> {
+    let mut merged = headers.to_vec();
+    let mut defaults = defaults.to_vec();
+    defaults.sort();
+    for (name, value) in defaults {
+        if !headers
+            .iter()
+            .any(|(existing, _)| existing.eq_ignore_ascii_case(&name))
+        {
+            merged.push((name, value));
+        }
+    }
+    merged
+}
+
+/// Merges headers that share a name (case-insensitively) into one comma-separated value, for
+/// targets whose header collections are maps.
+fn combined_headers(headers: &[(String, String)]) -> Vec<(String, String)> {
+    let mut combined: Vec<(String, String)> = Vec::new();
+    for (name, value) in headers {
+        match combined
+            .iter_mut()
+            .find(|(existing, _)| existing.eq_ignore_ascii_case(name))
+        {
+            Some((_, existing)) => {
+                existing.push_str(", ");
+                existing.push_str(value);
+            }
+            None => combined.push((name.clone(), value.clone())),
+        }
+    }
+    combined
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -23,6 +436,8 @@ mod tests {
             headers: vec![("accept".to_string(), "application/json".to_string())],
             body_text: None,
             body_file: None,
+            env_vars: Vec::new(),
+            params: Vec::new(),
         }
     }
 
@@ -37,6 +452,34 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn render_js_fetch_keeps_prop_names_for_renamed_params() -> Result<()> {
+        let mut template = base_template();
+        template.url = "{API_BASE}/{RESPONSE}".to_string();
+        template.params = vec!["API_BASE".to_string(), "RESPONSE".to_string()];
+        let rendered = render_export_template("js-fetch", &template)?;
+
+        assert!(rendered.contains("runRequest({ apiBase, response: response2 })"));
+        assert!(rendered.contains("fetch(apiBase + \"/\" + response2, {"));
+        Ok(())
+    }
+
+    #[test]
+    fn render_js_fetch_renames_params_named_like_keywords() -> Result<()> {
+        let mut template = base_template();
+        template.url = "{API_BASE}/{CLASS}?delete={DELETE}".to_string();
+        template.params = vec![
+            "API_BASE".to_string(),
+            "CLASS".to_string(),
+            "DELETE".to_string(),
+        ];
+        let rendered = render_export_template("js-fetch", &template)?;
+
+        assert!(rendered.contains("runRequest({ apiBase, class: class2, delete: delete2 })"));
+        assert!(rendered.contains("fetch(apiBase + \"/\" + class2 + \"?delete=\" + delete2, {"));
+        Ok(())
+    }
+
     #[test]
     fn render_js_fetch_handles_body_variants() -> Result<()> {
         let mut with_text = base_template();
@@ -51,10 +494,103 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn render_js_fetch_reads_env_and_props() -> Result<()> {
+        let template = RequestTemplate {
+            method: "post".to_string(),
+            url: "{API_BASE}/items".to_string(),
+            headers: vec![
+                ("Authorization".to_string(), "Bearer {TOKEN}".to_string()),
+                ("X-Trace".to_string(), "{trace-id}".to_string()),
+            ],
+            body_text: Some("{\"ok\":true}".to_string()),
+            env_vars: vec!["TOKEN".to_string(), "trace-id".to_string()],
+            params: vec!["API_BASE".to_string()],
+            ..RequestTemplate::default()
+        };
+        let rendered = render_export_template("js-fetch", &template)?;
+
+        assert!(rendered.contains("export async function runRequest({ apiBase }) {"));
+        assert!(rendered.contains("await fetch(apiBase + \"/items\", {"));
+        assert!(rendered.contains(
+            "    headers: {\n      \"Authorization\": \"Bearer \" + process.env.TOKEN,\n      \"X-Trace\": process.env[\"trace-id\"]\n    }"
+        ));
+        assert!(rendered.contains("body: \"{\\\"ok\\\":true}\""));
+        Ok(())
+    }
+
+    #[test]
+    fn segments_split_only_known_env_vars() {
+        let template = RequestTemplate {
+            env_vars: vec!["TOKEN".to_string()],
+            params: vec!["ACCOUNT_ID".to_string()],
+            ..RequestTemplate::default()
+        };
+        assert_eq!(
+            segments("Bearer {TOKEN} {other} {ACCOUNT_ID}{TOKEN", &template),
+            vec![
+                Segment::Text("Bearer ".to_string()),
+                Segment::Env("TOKEN".to_string()),
+                Segment::Text(" {other} ".to_string()),
+                Segment::Param("ACCOUNT_ID".to_string()),
+                Segment::Text("{TOKEN".to_string()),
+            ]
+        );
+        assert_eq!(segments("", &template), vec![Segment::Text(String::new())]);
+    }
+
+    #[test]
+    fn parameter_names_follow_language_conventions() {
+        assert_eq!(snake_case("API_BASE"), "api_base");
+        assert_eq!(camel_case("API_BASE"), "apiBase");
+        assert_eq!(camel_case("account.id-v2"), "accountIdV2");
+        assert_eq!(pascal_case("api_base"), "ApiBase");
+    }
+
+    #[test]
+    fn combined_headers_merge_duplicate_names() {
+        let headers = vec![
+            ("Accept".to_string(), "text/html".to_string()),
+            ("X-Trace".to_string(), "1".to_string()),
+            ("accept".to_string(), "application/json".to_string()),
+        ];
+        assert_eq!(
+            combined_headers(&headers),
+            vec![
+                (
+                    "Accept".to_string(),
+                    "text/html, application/json".to_string()
+                ),
+                ("X-Trace".to_string(), "1".to_string()),
+            ]
+        );
+    }
+
     #[test]
     fn render_export_template_rejects_unknown_templates() {
         let template = base_template();
         let err = render_export_template("unknown", &template).unwrap_err();
         assert!(err.to_string().contains("Unknown export template"));
     }
+
+    #[test]
+    fn render_named_template_falls_back_to_custom_files() -> Result<()> {
+        let temp = tempfile::tempdir()?;
+        let path = temp.path().join("ruby.mustache");
+        std::fs::write(&path, "Net::HTTP.get(URI({{json url}}))\n")?;
+        let custom = BTreeMap::from([
+            ("ruby".to_string(), path),
+            ("curl".to_string(), temp.path().join("ignored.mustache")),
+        ]);
+        let template = base_template();
+
+        assert_eq!(
+            render_named_template("ruby", &template, &custom)?,
+            "Net::HTTP.get(URI(\"https://example.com\"))\n"
+        );
+        assert!(render_named_template("curl", &template, &custom)?.starts_with("curl -X GET"));
+        let err = render_named_template("missing", &template, &custom).unwrap_err();
+        assert!(err.to_string().contains("Unknown export template"));
+        Ok(())
+    }
 }
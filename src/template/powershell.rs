@@ -0,0 +1,197 @@
+use anyhow::Result;
+
+use crate::parser::RequestTemplate;
+
+use super::{combined_headers, pascal_case, segments, ParamNames, Segment};
+
+/// Variables the script assigns, and automatic variables PowerShell reserves.
+const RESERVED: &[&str] = &[
+    "Args", "Body", "Error", "False", "Headers", "Home", "Host", "Input", "Null", "Pid", "Pwd",
+    "True",
+];
+
+/// Methods accepted by `-Method`; anything else needs `-CustomMethod`.
+const WEB_REQUEST_METHODS: &[&str] = &[
+    "Get", "Head", "Post", "Put", "Delete", "Trace", "Options", "Merge", "Patch",
+];
+
+pub(super) fn render_powershell(template: &RequestTemplate) -> Result<String> {
+    let method = template.method.to_uppercase();
+    let method_arg = match WEB_REQUEST_METHODS
+        .iter()
+        .find(|known| known.eq_ignore_ascii_case(&method))
+    {
+        Some(known) => format!("-Method {known}"),
+        None => format!("-CustomMethod {}", quote(&method)),
+    };
+
+    let mut lines = vec!["# Generated by curlpit".to_string()];
+    let params = ParamNames::new(template, pascal_case, RESERVED);
+    let declarations: Vec<String> = params
+        .iter()
+        .map(|(_, identifier)| format!("[string]${identifier}"))
+        .collect();
+    if !declarations.is_empty() {
+        lines.push(format!("param({})", declarations.join(", ")));
+        lines.push(String::new());
+    }
+    let mut args = vec![
+        method_arg,
+        format!("-Uri {}", string(&template.url, template, &params)),
+    ];
+
+    // Invoke-RestMethod rejects Content-Type among -Headers on older PowerShell versions
+    let (content_types, headers): (Vec<_>, Vec<_>) = combined_headers(&template.headers)
+        .into_iter()
+        .partition(|(name, _)| name.eq_ignore_ascii_case("content-type"));
+    if !headers.is_empty() {
+        lines.push("$headers = @{".to_string());
+        for (name, value) in &headers {
+            lines.push(format!(
+                "    {} = {}",
+                quote(name),
+                string(value, template, &params)
+            ));
+        }
+        lines.push("}".to_string());
+        args.push("-Headers $headers".to_string());
+    }
+    if let Some((_, value)) = content_types.first() {
+        args.push(format!("-ContentType {}", string(value, template, &params)));
+    }
+
+    if let Some(body_file) = &template.body_file {
+        args.push(format!("-InFile {}", quote(&body_file.to_string_lossy())));
+    } else if let Some(body) = &template.body_text {
+        lines.push(format!("$body = {}", string(body, template, &params)));
+        args.push("-Body $body".to_string());
+    }
+
+    lines.push("Invoke-RestMethod `".to_string());
+    let last = args.len() - 1;
+    for (index, arg) in args.iter().enumerate() {
+        let continuation = if index == last { "" } else { " `" };
+        lines.push(format!("    {arg}{continuation}"));
+    }
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// A single-quoted literal, or an expandable string when environment variables or parameters
+/// are involved.
+fn string(input: &str, template: &RequestTemplate, params: &ParamNames) -> String {
+    let parts = segments(input, template);
+    if let [Segment::Text(text)] = parts.as_slice() {
+        return quote(text);
+    }
+    let mut expandable = String::from("\"");
+    for part in parts {
+        match part {
+            Segment::Text(text) => {
+                for ch in text.chars() {
+                    if matches!(ch, '`' | '"' | '$') {
+                        expandable.push('`');
+                    }
+                    expandable.push(ch);
+                }
+            }
+            Segment::Env(name) => expandable.push_str(&format!("${{env:{name}}}")),
+            Segment::Param(name) => expandable.push_str(&format!("${{{}}}", params.get(&name))),
+        }
+    }
+    expandable.push('"');
+    expandable
+}
+
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn template() -> RequestTemplate {
+        RequestTemplate {
+            method: "post".to_string(),
+            url: "{API_BASE}/items".to_string(),
+            headers: vec![
+                ("Content-Type".to_string(), "application/json".to_string()),
+                (
+                    "Authorization".to_string(),
+                    "Bearer {API_TOKEN}".to_string(),
+                ),
+                ("Accept".to_string(), "text/plain".to_string()),
+                ("accept".to_string(), "application/json".to_string()),
+            ],
+            body_text: Some("{\"name\":\"O'Brien\",\"cost\":\"$5\"}".to_string()),
+            body_file: None,
+            env_vars: vec!["API_BASE".to_string(), "API_TOKEN".to_string()],
+            params: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn text_body_snapshot() -> Result<()> {
+        assert_eq!(
+            render_powershell(&template())?,
+            r#"# Generated by curlpit
+$headers = @{
+    'Authorization' = "Bearer ${env:API_TOKEN}"
+    'Accept' = 'text/plain, application/json'
+}
+$body = '{"name":"O''Brien","cost":"$5"}'
+Invoke-RestMethod `
+    -Method Post `
+    -Uri "${env:API_BASE}/items" `
+    -Headers $headers `
+    -ContentType 'application/json' `
+    -Body $body
+"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn file_body_snapshot() -> Result<()> {
+        let mut template = template();
+        template.method = "purge".to_string();
+        template.url = "https://example.com/cache".to_string();
+        template.headers.clear();
+        template.body_text = None;
+        template.body_file = Some(PathBuf::from("C:\\data\\it's.bin"));
+        assert_eq!(
+            render_powershell(&template)?,
+            r#"# Generated by curlpit
+Invoke-RestMethod `
+    -CustomMethod 'PURGE' `
+    -Uri 'https://example.com/cache' `
+    -InFile 'C:\data\it''s.bin'
+"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn config_variables_become_script_parameters() -> Result<()> {
+        let template = RequestTemplate {
+            method: "get".to_string(),
+            url: "{api_base}/items".to_string(),
+            params: vec!["api_base".to_string()],
+            ..RequestTemplate::default()
+        };
+        assert_eq!(
+            render_powershell(&template)?,
+            r#"# Generated by curlpit
+param([string]$ApiBase)
+
+Invoke-RestMethod `
+    -Method Get `
+    -Uri "${ApiBase}/items"
+"#
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,315 @@
+use std::fmt;
+
+use anyhow::Result;
+use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde_json::Number;
+
+use crate::parser::RequestTemplate;
+
+use super::{combined_headers, expression, json_body, referenced_env_vars, snake_case, ParamNames};
+
+/// Identifiers the generated function declares or imports.
+const RESERVED: &[&str] = &[
+    "body", "headers", "open", "os", "payload", "requests", "response", "url",
+];
+
+/// Python keywords.
+const KEYWORDS: &[&str] = &[
+    "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del", "elif",
+    "else", "except", "finally", "for", "from", "global", "if", "import", "in", "is", "lambda",
+    "nonlocal", "not", "or", "pass", "raise", "return", "try", "while", "with", "yield",
+];
+
+pub(super) fn render_python_requests(template: &RequestTemplate) -> Result<String> {
+    let params = ParamNames::new(template, snake_case, &[RESERVED, KEYWORDS].concat());
+    let string = |input: &str| {
+        expression(
+            input,
+            template,
+            quote,
+            |name| format!("os.environ[{}]", quote(name)),
+            |name| params.get(name),
+            " + ",
+        )
+    };
+
+    let mut lines = vec!["# Generated by curlpit".to_string()];
+    if !referenced_env_vars(template).is_empty() {
+        lines.push("import os".to_string());
+        lines.push(String::new());
+    }
+    lines.push("import requests".to_string());
+    lines.push(String::new());
+    lines.push(String::new());
+    let signature: Vec<&str> = params.iter().map(|(_, identifier)| identifier).collect();
+    lines.push(format!("def run_request({}):", signature.join(", ")));
+    lines.push(format!("    url = {}", string(&template.url)));
+
+    let mut args = vec![quote(&template.method.to_uppercase()), "url".to_string()];
+
+    let headers = combined_headers(&template.headers);
+    if !headers.is_empty() {
+        lines.push("    headers = {".to_string());
+        for (name, value) in &headers {
+            lines.push(format!("        {}: {},", quote(name), string(value)));
+        }
+        lines.push("    }".to_string());
+        args.push("headers=headers".to_string());
+    }
+
+    if template.body_file.is_none() {
+        if let Some(payload) = json_body::<Json>(template) {
+            lines.push(format!("    payload = {}", python_value(&payload, 1)));
+            args.push("json=payload".to_string());
+        } else if let Some(body) = &template.body_text {
+            lines.push(format!("    body = {}", string(body)));
+            args.push("data=body.encode(\"utf-8\")".to_string());
+        }
+    }
+
+    lines.push(String::new());
+    match &template.body_file {
+        Some(body_file) => {
+            lines.push(format!(
+                "    with open({}, \"rb\") as body:",
+                quote(&body_file.to_string_lossy())
+            ));
+            args.push("data=body".to_string());
+            push_call(&mut lines, &args, "        ");
+        }
+        None => push_call(&mut lines, &args, "    "),
+    }
+    lines.push("    response.raise_for_status()".to_string());
+    lines.push("    return response".to_string());
+
+    Ok(lines.join("\n") + "\n")
+}
+
+fn push_call(lines: &mut Vec<String>, args: &[String], indent: &str) {
+    lines.push(format!("{indent}response = requests.request("));
+    for arg in args {
+        lines.push(format!("{indent}    {arg},"));
+    }
+    lines.push(format!("{indent})"));
+}
+
+/// Python accepts JSON string literals, escapes included.
+fn quote(value: &str) -> String {
+    serde_json::to_string(value).expect("strings serialize")
+}
+
+/// A parsed JSON body that keeps object keys in the order they were written.
+enum Json {
+    Null,
+    Bool(bool),
+    Number(Number),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl<'de> Deserialize<'de> for Json {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(JsonVisitor)
+    }
+}
+
+struct JsonVisitor;
+
+impl<'de> Visitor<'de> for JsonVisitor {
+    type Value = Json;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a JSON value")
+    }
+
+    fn visit_unit<E>(self) -> Result<Json, E> {
+        Ok(Json::Null)
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<Json, E> {
+        Ok(Json::Bool(value))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Json, E> {
+        Ok(Json::Number(value.into()))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Json, E> {
+        Ok(Json::Number(value.into()))
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Json, E> {
+        Ok(Number::from_f64(value).map_or(Json::Null, Json::Number))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Json, E> {
+        Ok(Json::String(value.to_string()))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Json, A::Error> {
+        let mut items = Vec::new();
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(Json::Array(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Json, A::Error> {
+        let mut entries: Vec<(String, Json)> = Vec::new();
+        while let Some((key, value)) = map.next_entry::<String, Json>()? {
+            // Like Python's json module, a repeated key keeps its first position and last value
+            match entries.iter_mut().find(|(existing, _)| *existing == key) {
+                Some(entry) => entry.1 = value,
+                None => entries.push((key, value)),
+            }
+        }
+        Ok(Json::Object(entries))
+    }
+}
+
+fn python_value(value: &Json, depth: usize) -> String {
+    let indent = "    ".repeat(depth + 1);
+    let closing = "    ".repeat(depth);
+    match value {
+        Json::Null => "None".to_string(),
+        Json::Bool(true) => "True".to_string(),
+        Json::Bool(false) => "False".to_string(),
+        Json::Number(number) => number.to_string(),
+        Json::String(text) => quote(text),
+        Json::Array(items) if items.is_empty() => "[]".to_string(),
+        Json::Array(items) => {
+            let entries: Vec<String> = items
+                .iter()
+                .map(|item| format!("{indent}{},\n", python_value(item, depth + 1)))
+                .collect();
+            format!("[\n{}{closing}]", entries.concat())
+        }
+        Json::Object(map) if map.is_empty() => "{}".to_string(),
+        Json::Object(map) => {
+            let entries: Vec<String> = map
+                .iter()
+                .map(|(key, item)| {
+                    format!(
+                        "{indent}{}: {},\n",
+                        quote(key),
+                        python_value(item, depth + 1)
+                    )
+                })
+                .collect();
+            format!("{{\n{}{closing}}}", entries.concat())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn template() -> RequestTemplate {
+        RequestTemplate {
+            method: "post".to_string(),
+            url: "{API_BASE}/items".to_string(),
+            headers: vec![
+                ("Content-Type".to_string(), "application/json".to_string()),
+                (
+                    "Authorization".to_string(),
+                    "Bearer {API_TOKEN}".to_string(),
+                ),
+            ],
+            body_text: Some(
+                r#"{"name":"demo","tags":["a"],"active":true,"parent":null}"#.to_string(),
+            ),
+            body_file: None,
+            env_vars: vec!["API_BASE".to_string(), "API_TOKEN".to_string()],
+            params: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn json_bodies_use_json_argument_and_environ_lookups() -> Result<()> {
+        let rendered = render_python_requests(&template())?;
+        assert_eq!(
+            rendered,
+            r#"# Generated by curlpit
+import os
+
+import requests
+
+
+def run_request():
+    url = os.environ["API_BASE"] + "/items"
+    headers = {
+        "Content-Type": "application/json",
+        "Authorization": "Bearer " + os.environ["API_TOKEN"],
+    }
+    payload = {
+        "name": "demo",
+        "tags": [
+            "a",
+        ],
+        "active": True,
+        "parent": None,
+    }
+
+    response = requests.request(
+        "POST",
+        url,
+        headers=headers,
+        json=payload,
+    )
+    response.raise_for_status()
+    return response
+"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn text_and_file_bodies() -> Result<()> {
+        let mut text = template();
+        text.headers = vec![("Content-Type".to_string(), "text/plain".to_string())];
+        text.body_text = Some("hello \"world\"".to_string());
+        text.env_vars.clear();
+        let rendered = render_python_requests(&text)?;
+        assert!(!rendered.contains("import os"));
+        assert!(rendered.contains("    body = \"hello \\\"world\\\"\"\n"));
+        assert!(rendered.contains("data=body.encode(\"utf-8\"),"));
+
+        let mut file = template();
+        file.body_text = None;
+        file.body_file = Some(PathBuf::from("fixtures/payload.json"));
+        let rendered = render_python_requests(&file)?;
+        assert!(rendered.contains("    with open(\"fixtures/payload.json\", \"rb\") as body:\n        response = requests.request(\n"));
+        assert!(rendered.contains("            data=body,\n        )\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn config_variables_become_parameters() -> Result<()> {
+        let mut template = template();
+        template.env_vars = vec!["API_TOKEN".to_string()];
+        template.params = vec!["API_BASE".to_string()];
+        let rendered = render_python_requests(&template)?;
+        assert!(rendered.contains("def run_request(api_base):\n    url = api_base + \"/items\"\n"));
+        assert!(rendered.contains("\"Bearer \" + os.environ[\"API_TOKEN\"],"));
+        Ok(())
+    }
+
+    #[test]
+    fn params_named_like_keywords_are_renamed() -> Result<()> {
+        let template = RequestTemplate {
+            method: "get".to_string(),
+            url: "{TYPE}/items?from={FROM}".to_string(),
+            params: vec!["TYPE".to_string(), "FROM".to_string()],
+            ..RequestTemplate::default()
+        };
+        let rendered = render_python_requests(&template)?;
+        assert!(rendered.contains(
+            "def run_request(type, from2):\n    url = type + \"/items?from=\" + from2\n"
+        ));
+        Ok(())
+    }
+}
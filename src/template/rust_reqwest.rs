@@ -0,0 +1,205 @@
+use anyhow::Result;
+
+use crate::parser::RequestTemplate;
+
+use super::{segments, snake_case, ParamNames, Segment};
+
+/// Identifiers the generated function declares or uses unqualified.
+const RESERVED: &[&str] = &["client", "reqwest", "response", "std"];
+
+/// Rust keywords, including the ones reserved for future use.
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+const STANDARD_METHODS: &[&str] = &[
+    "CONNECT", "DELETE", "GET", "HEAD", "OPTIONS", "PATCH", "POST", "PUT", "TRACE",
+];
+
+pub(super) fn render_rust_reqwest(template: &RequestTemplate) -> Result<String> {
+    let method = template.method.to_uppercase();
+    let method = if STANDARD_METHODS.contains(&method.as_str()) {
+        format!("reqwest::Method::{method}")
+    } else {
+        format!("reqwest::Method::from_bytes(b{})?", quote(&method))
+    };
+
+    let params = ParamNames::new(template, snake_case, &[RESERVED, KEYWORDS].concat());
+    let signature: Vec<String> = params
+        .iter()
+        .map(|(_, identifier)| format!("{identifier}: &str"))
+        .collect();
+    let mut lines = vec![
+        "// Generated by curlpit".to_string(),
+        format!(
+            "pub async fn run_request({}) -> Result<reqwest::Response, Box<dyn std::error::Error>> {{",
+            signature.join(", ")
+        ),
+        "    let client = reqwest::Client::new();".to_string(),
+        "    let response = client".to_string(),
+        format!(
+            "        .request({method}, {})",
+            string(&template.url, template, &params)
+        ),
+    ];
+    for (name, value) in &template.headers {
+        lines.push(format!(
+            "        .header({}, {})",
+            quote(name),
+            string(value, template, &params)
+        ));
+    }
+    if let Some(body_file) = &template.body_file {
+        lines.push(format!(
+            "        .body(std::fs::read({})?)",
+            quote(&body_file.to_string_lossy())
+        ));
+    } else if let Some(body) = &template.body_text {
+        lines.push(format!(
+            "        .body({})",
+            string(body, template, &params)
+        ));
+    }
+    lines.push("        .send()".to_string());
+    lines.push("        .await?".to_string());
+    lines.push("        .error_for_status()?;".to_string());
+    lines.push("    Ok(response)".to_string());
+    lines.push("}".to_string());
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// A string literal, or a `format!` call when the text reads environment variables or
+/// parameters.
+fn string(input: &str, template: &RequestTemplate, params: &ParamNames) -> String {
+    let parts = segments(input, template);
+    if let [Segment::Text(text)] = parts.as_slice() {
+        return quote(text);
+    }
+    let mut format = String::new();
+    let mut args = Vec::new();
+    for part in parts {
+        match part {
+            Segment::Text(text) => format.push_str(&text.replace('{', "{{").replace('}', "}}")),
+            Segment::Env(name) => {
+                format.push_str("{}");
+                args.push(format!("std::env::var({})?", quote(&name)));
+            }
+            Segment::Param(name) => {
+                format.push_str("{}");
+                args.push(params.get(&name));
+            }
+        }
+    }
+    format!("format!({}, {})", quote(&format), args.join(", "))
+}
+
+/// Prefers a raw string literal for text full of quotes or backslashes, such as JSON bodies.
+fn quote(value: &str) -> String {
+    let awkward = value.contains('"') || value.contains('\\');
+    let raw_safe = !value.contains("\"#")
+        && !value
+            .chars()
+            .any(|ch| ch.is_control() && ch != '\n' && ch != '\t');
+    if awkward && raw_safe {
+        format!("r#\"{value}\"#")
+    } else {
+        format!("{value:?}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    #[test]
+    fn renders_env_lookups_headers_and_json_body() -> Result<()> {
+        let template = RequestTemplate {
+            method: "post".to_string(),
+            url: "{API_BASE}/items".to_string(),
+            headers: vec![
+                ("Content-Type".to_string(), "application/json".to_string()),
+                (
+                    "Authorization".to_string(),
+                    "Bearer {API_TOKEN}".to_string(),
+                ),
+            ],
+            body_text: Some("{\"name\":\"demo\"}".to_string()),
+            body_file: None,
+            env_vars: vec!["API_BASE".to_string(), "API_TOKEN".to_string()],
+            params: Vec::new(),
+        };
+        assert_eq!(
+            render_rust_reqwest(&template)?,
+            r##"// Generated by curlpit
+pub async fn run_request() -> Result<reqwest::Response, Box<dyn std::error::Error>> {
+    let client = reqwest::Client::new();
+    let response = client
+        .request(reqwest::Method::POST, format!("{}/items", std::env::var("API_BASE")?))
+        .header("Content-Type", "application/json")
+        .header("Authorization", format!("Bearer {}", std::env::var("API_TOKEN")?))
+        .body(r#"{"name":"demo"}"#)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(response)
+}
+"##
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn file_bodies_custom_methods_and_escaping() -> Result<()> {
+        let template = RequestTemplate {
+            method: "purge".to_string(),
+            url: "https://example.com/{literal}".to_string(),
+            body_file: Some(PathBuf::from("payload.bin")),
+            ..RequestTemplate::default()
+        };
+        let rendered = render_rust_reqwest(&template)?;
+        assert!(rendered.contains(
+            ".request(reqwest::Method::from_bytes(b\"PURGE\")?, \"https://example.com/{literal}\")"
+        ));
+        assert!(rendered.contains(".body(std::fs::read(\"payload.bin\")?)"));
+
+        let variables = RequestTemplate {
+            url: "{API_BASE}/items".to_string(),
+            env_vars: vec!["TOKEN".to_string()],
+            params: vec!["API_BASE".to_string()],
+            ..RequestTemplate::default()
+        };
+        let params = ParamNames::new(&variables, snake_case, RESERVED);
+        assert_eq!(
+            string("{TOKEN}{braces}", &variables, &params),
+            "format!(\"{}{{braces}}\", std::env::var(\"TOKEN\")?)"
+        );
+        assert_eq!(
+            string("{API_BASE}/items", &variables, &params),
+            "format!(\"{}/items\", api_base)"
+        );
+        assert_eq!(quote("line\u{1}\"x\""), "\"line\\u{1}\\\"x\\\"\"");
+        Ok(())
+    }
+
+    #[test]
+    fn params_named_like_keywords_are_renamed() -> Result<()> {
+        let template = RequestTemplate {
+            method: "get".to_string(),
+            url: "{TYPE}/items?self={SELF}".to_string(),
+            params: vec!["TYPE".to_string(), "SELF".to_string()],
+            ..RequestTemplate::default()
+        };
+        let rendered = render_rust_reqwest(&template)?;
+        assert!(rendered.contains(
+            "pub async fn run_request(type2: &str, self2: &str) -> Result<reqwest::Response, Box<dyn std::error::Error>> {"
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,415 @@
+use anyhow::Result;
+
+use super::{camel_case, combined_headers, expression, words, NamedRequest, ParamNames};
+
+/// Words that cannot name a function in strict-mode TypeScript, and the module's other top-level
+/// names: a request function must neither redeclare them nor shadow `fetch` inside `send`.
+const RESERVED_WORDS: &[&str] = &[
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "import",
+    "in",
+    "instanceof",
+    "new",
+    "null",
+    "return",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+    "let",
+    "static",
+    "implements",
+    "interface",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "await",
+    "baseConfig",
+    "ClientConfig",
+    "fetch",
+    "send",
+];
+
+/// Renders one module with a typed async function per request. `default_headers` are shared by
+/// every request and go into `baseConfig`.
+pub(super) fn render_ts_client(
+    requests: &[NamedRequest],
+    default_headers: &[(String, String)],
+) -> Result<String> {
+    let mut requests: Vec<&NamedRequest> = requests.iter().collect();
+    requests.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut lines: Vec<String> = [
+        "// Generated by curlpit",
+        "export interface ClientConfig {",
+        "  fetch?: typeof fetch;",
+        "  headers?: Record<string, string>;",
+        "}",
+        "",
+        "export const baseConfig: { headers: Record<string, string> } = {",
+    ]
+    .map(String::from)
+    .to_vec();
+    let mut defaults = combined_headers(default_headers);
+    defaults.sort_by(|a, b| a.0.cmp(&b.0));
+    push_object(&mut lines, "  headers: ", &defaults, "  ", quote);
+    if let Some(last) = lines.last_mut() {
+        last.push(',');
+    }
+    lines.extend(
+        [
+            "};",
+            "",
+            "async function send(",
+            "  config: ClientConfig,",
+            "  method: string,",
+            "  url: string,",
+            "  headers: Record<string, string>,",
+            "  body?: BodyInit,",
+            "): Promise<Response> {",
+            "  const response = await (config.fetch ?? fetch)(url, {",
+            "    method,",
+            "    headers: { ...baseConfig.headers, ...config.headers, ...headers },",
+            "    body,",
+            "  });",
+            "  if (!response.ok) {",
+            "    throw new Error(`Request failed: ${response.status} ${response.statusText}`);",
+            "  }",
+            "  return response;",
+            "}",
+        ]
+        .map(String::from),
+    );
+
+    let mut used_names: Vec<String> = Vec::new();
+    for request in requests {
+        let name = function_name(&request.name, &used_names);
+        used_names.push(name.clone());
+        lines.push(String::new());
+        push_function(&mut lines, &name, request);
+    }
+
+    Ok(lines.join("\n") + "\n")
+}
+
+fn push_function(lines: &mut Vec<String>, name: &str, request: &NamedRequest) {
+    // A browser has no environment to read from, so every placeholder is a parameter
+    let mut template = request.template.clone();
+    template.params.append(&mut template.env_vars);
+    // A file body travels as `params.body`, so a placeholder can't use that field too
+    let reserved: &[&str] = if template.body_file.is_some() {
+        &["body"]
+    } else {
+        &[]
+    };
+    let params = ParamNames::new(&template, camel_case, reserved);
+    let string = |input: &str| {
+        expression(
+            input,
+            &template,
+            quote,
+            |_| unreachable!("env vars were moved to params"),
+            |param| format!("params.{}", params.get(param)),
+            " + ",
+        )
+    };
+
+    let mut fields: Vec<String> = params
+        .iter()
+        .map(|(_, field)| format!("{field}: string;"))
+        .collect();
+    if template.body_file.is_some() {
+        fields.push("body: BodyInit;".to_string());
+    }
+
+    lines.push(format!("/** {} */", request.name));
+    let mut args = Vec::new();
+    if !fields.is_empty() {
+        let interface = format!("{}{}Params", name[..1].to_ascii_uppercase(), &name[1..]);
+        lines.push(format!("export interface {interface} {{"));
+        lines.extend(fields.iter().map(|field| format!("  {field}")));
+        lines.push("}".to_string());
+        lines.push(String::new());
+        args.push(format!("params: {interface}"));
+    }
+    args.push("config: ClientConfig = {}".to_string());
+    lines.push(format!(
+        "export async function {name}({}): Promise<Response> {{",
+        args.join(", ")
+    ));
+    lines.push("  return send(".to_string());
+    lines.push("    config,".to_string());
+    lines.push(format!("    {},", quote(&template.method.to_uppercase())));
+    lines.push(format!("    {},", string(&template.url)));
+    let headers = combined_headers(&template.headers);
+    push_object(lines, "    ", &headers, "    ", string);
+    if let Some(last) = lines.last_mut() {
+        last.push(',');
+    }
+    if template.body_file.is_some() {
+        lines.push("    params.body,".to_string());
+    } else if let Some(body) = &template.body_text {
+        lines.push(format!("    {},", string(body)));
+    }
+    lines.push("  );".to_string());
+    lines.push("}".to_string());
+}
+
+/// Pushes an object literal whose opening brace follows `prefix`; the closing brace is left
+/// without punctuation on the last line.
+fn push_object(
+    lines: &mut Vec<String>,
+    prefix: &str,
+    entries: &[(String, String)],
+    indent: &str,
+    value: impl Fn(&str) -> String,
+) {
+    if entries.is_empty() {
+        lines.push(format!("{prefix}{{}}"));
+        return;
+    }
+    lines.push(format!("{prefix}{{"));
+    for (name, entry) in entries {
+        lines.push(format!("{indent}  {}: {},", quote(name), value(entry)));
+    }
+    lines.push(format!("{indent}}}"));
+}
+
+/// Names a function after the request's path, e.g. `users/get-by-id.curl` as `usersGetById`.
+fn function_name(path: &str, taken: &[String]) -> String {
+    let stem = path.strip_suffix(".curl").unwrap_or(path);
+    let mut name = camel_case(stem);
+    if name.is_empty() || name.starts_with(|ch: char| ch.is_ascii_digit()) {
+        name = camel_case(&format!("request {}", words(stem).join(" ")));
+    }
+    if RESERVED_WORDS.contains(&name.as_str()) {
+        name.push_str("Request");
+    }
+    let mut candidate = name.clone();
+    let mut suffix = 2;
+    while taken.contains(&candidate) {
+        candidate = format!("{name}{suffix}");
+        suffix += 1;
+    }
+    candidate
+}
+
+/// JSON string literals are valid TypeScript string literals.
+fn quote(value: &str) -> String {
+    serde_json::to_string(value).expect("strings serialize")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::RequestTemplate;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn named(name: &str, template: RequestTemplate) -> NamedRequest {
+        NamedRequest {
+            name: name.to_string(),
+            template,
+        }
+    }
+
+    #[test]
+    fn folder_snapshot() -> Result<()> {
+        let requests = vec![
+            named(
+                "users/create.curl",
+                RequestTemplate {
+                    method: "POST".to_string(),
+                    url: "{API_BASE}/users".to_string(),
+                    headers: vec![
+                        ("Content-Type".to_string(), "application/json".to_string()),
+                        (
+                            "Authorization".to_string(),
+                            "Bearer {API_TOKEN}".to_string(),
+                        ),
+                    ],
+                    body_text: Some("{\"name\":\"{USER_NAME}\"}".to_string()),
+                    env_vars: vec!["API_TOKEN".to_string(), "USER_NAME".to_string()],
+                    params: vec!["API_BASE".to_string()],
+                    ..RequestTemplate::default()
+                },
+            ),
+            named(
+                "health.curl",
+                RequestTemplate {
+                    method: "GET".to_string(),
+                    url: "https://status.example.com".to_string(),
+                    ..RequestTemplate::default()
+                },
+            ),
+        ];
+        let defaults = vec![
+            ("X-Env".to_string(), "prod".to_string()),
+            ("Accept".to_string(), "application/json".to_string()),
+        ];
+
+        assert_eq!(
+            render_ts_client(&requests, &defaults)?,
+            r#"// Generated by curlpit
+export interface ClientConfig {
+  fetch?: typeof fetch;
+  headers?: Record<string, string>;
+}
+
+export const baseConfig: { headers: Record<string, string> } = {
+  headers: {
+    "Accept": "application/json",
+    "X-Env": "prod",
+  },
+};
+
+async function send(
+  config: ClientConfig,
+  method: string,
+  url: string,
+  headers: Record<string, string>,
+  body?: BodyInit,
+): Promise<Response> {
+  const response = await (config.fetch ?? fetch)(url, {
+    method,
+    headers: { ...baseConfig.headers, ...config.headers, ...headers },
+    body,
+  });
+  if (!response.ok) {
+    throw new Error(`Request failed: ${response.status} ${response.statusText}`);
+  }
+  return response;
+}
+
+/** health.curl */
+export async function health(config: ClientConfig = {}): Promise<Response> {
+  return send(
+    config,
+    "GET",
+    "https://status.example.com",
+    {},
+  );
+}
+
+/** users/create.curl */
+export interface UsersCreateParams {
+  apiBase: string;
+  apiToken: string;
+  userName: string;
+}
+
+export async function usersCreate(params: UsersCreateParams, config: ClientConfig = {}): Promise<Response> {
+  return send(
+    config,
+    "POST",
+    params.apiBase + "/users",
+    {
+      "Content-Type": "application/json",
+      "Authorization": "Bearer " + params.apiToken,
+    },
+    "{\"name\":\"" + params.userName + "\"}",
+  );
+}
+"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn file_bodies_and_awkward_names() -> Result<()> {
+        let upload = RequestTemplate {
+            method: "PUT".to_string(),
+            url: "https://example.com/upload".to_string(),
+            body_file: Some(PathBuf::from("payload.bin")),
+            ..RequestTemplate::default()
+        };
+        let requests = vec![
+            named("delete.curl", RequestTemplate::default()),
+            named("Delete.curl", RequestTemplate::default()),
+            named("2fa/verify.curl", RequestTemplate::default()),
+            named("upload.curl", upload),
+        ];
+        let rendered = render_ts_client(&requests, &[])?;
+        assert!(rendered.contains("export async function deleteRequest(config"));
+        assert!(rendered.contains("export async function deleteRequest2(config"));
+        assert!(rendered.contains("export async function request2faVerify(config"));
+        assert!(rendered.contains("export interface UploadParams {\n  body: BodyInit;\n}"));
+        assert!(rendered.contains("    {},\n    params.body,\n  );"));
+        Ok(())
+    }
+
+    #[test]
+    fn functions_never_reuse_the_module_names() -> Result<()> {
+        let requests = vec![
+            named("base-config.curl", RequestTemplate::default()),
+            named("fetch.curl", RequestTemplate::default()),
+            named("send.curl", RequestTemplate::default()),
+        ];
+        let rendered = render_ts_client(&requests, &[])?;
+        assert!(rendered.contains("export async function baseConfigRequest(config"));
+        assert!(rendered.contains("export async function fetchRequest(config"));
+        assert!(rendered.contains("export async function sendRequest(config"));
+        assert_eq!(rendered.matches("function fetch(").count(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn placeholder_fields_never_share_a_name() -> Result<()> {
+        let upload = RequestTemplate {
+            method: "PUT".to_string(),
+            url: "{API_BASE}/upload/{BODY}".to_string(),
+            body_file: Some(PathBuf::from("payload.bin")),
+            params: vec!["API_BASE".to_string(), "BODY".to_string()],
+            ..RequestTemplate::default()
+        };
+        let rendered = render_ts_client(&[named("upload.curl", upload)], &[])?;
+        assert!(rendered.contains(
+            "export interface UploadParams {\n  apiBase: string;\n  body2: string;\n  body: BodyInit;\n}"
+        ));
+        assert!(rendered.contains("params.apiBase + \"/upload/\" + params.body2,"));
+        assert!(rendered.contains("    params.body,\n  );"));
+        Ok(())
+    }
+
+    #[test]
+    fn placeholders_with_the_same_camel_case_get_distinct_fields() -> Result<()> {
+        let template = RequestTemplate {
+            url: "{API_BASE}/items?mirror={api-base}".to_string(),
+            params: vec!["API_BASE".to_string(), "api-base".to_string()],
+            ..RequestTemplate::default()
+        };
+        let rendered = render_ts_client(&[named("items.curl", template)], &[])?;
+        assert!(rendered.contains(
+            "export interface ItemsParams {\n  apiBase: string;\n  apiBase2: string;\n}"
+        ));
+        assert!(rendered.contains("params.apiBase + \"/items?mirror=\" + params.apiBase2,"));
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+use anyhow::Result;
+
+use crate::parser::RequestTemplate;
+
+use super::shell_word;
+
+pub(super) fn render_wget(template: &RequestTemplate) -> Result<String> {
+    let mut args = vec![
+        "wget --quiet --output-document=-".to_string(),
+        format!("--method {}", template.method.to_uppercase()),
+    ];
+    for (name, value) in &template.headers {
+        args.push(format!(
+            "--header {}",
+            shell_word(&format!("{name}: {value}"), template)
+        ));
+    }
+    if let Some(body_file) = &template.body_file {
+        args.push(format!(
+            "--body-file {}",
+            shell_words::quote(&body_file.to_string_lossy())
+        ));
+    } else if let Some(body) = &template.body_text {
+        args.push(format!("--body-data {}", shell_word(body, template)));
+    }
+    args.push(shell_word(&template.url, template));
+
+    Ok(args.join(" \\\n  ") + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn template() -> RequestTemplate {
+        RequestTemplate {
+            method: "put".to_string(),
+            url: "https://api.example.com/items/1".to_string(),
+            headers: vec![
+                ("Content-Type".to_string(), "text/plain".to_string()),
+                ("X-Token".to_string(), "{API_TOKEN}".to_string()),
+            ],
+            body_text: Some("it's $5".to_string()),
+            body_file: None,
+            env_vars: vec!["API_TOKEN".to_string()],
+            params: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn text_body_snapshot() -> Result<()> {
+        assert_eq!(
+            render_wget(&template())?,
+            r#"wget --quiet --output-document=- \
+  --method PUT \
+  --header 'Content-Type: text/plain' \
+  --header "X-Token: ${API_TOKEN}" \
+  --body-data 'it'\''s $5' \
+  https://api.example.com/items/1
+"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn file_body_snapshot() -> Result<()> {
+        let mut template = template();
+        template.headers.clear();
+        template.body_text = None;
+        template.body_file = Some(PathBuf::from("payload.bin"));
+        assert_eq!(
+            render_wget(&template)?,
+            r#"wget --quiet --output-document=- \
+  --method PUT \
+  --body-file payload.bin \
+  https://api.example.com/items/1
+"#
+        );
+        Ok(())
+    }
+}
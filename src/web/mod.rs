@@ -1,11 +1,12 @@
 use std::collections::{HashMap, HashSet};
 
 use once_cell::sync::Lazy;
-use regex::Regex;
 use serde::Serialize;
 use thiserror::Error;
 
-use crate::env::{expand_placeholders, EnvMap};
+use crate::env::{
+    expand_placeholders_with_usage, resolve_variable, EnvMap, ProvenanceMap, VariableSource,
+};
 use crate::importer::{import_curl_command, ImportOptions, ImportResult};
 use crate::parser::RequestTemplate;
 use crate::template;
@@ -36,6 +37,7 @@ pub struct WebRequest {
 pub struct WebInterpolationDetail {
     pub key: String,
     pub value: String,
+    pub chain: Vec<VariableSource>,
 }
 
 #[derive(Debug, Serialize)]
@@ -52,15 +54,22 @@ static HTTP_METHODS: Lazy<HashSet<&'static str>> = Lazy::new(|| {
     .collect()
 });
 
-static PLACEHOLDER_PATTERN: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"\{([A-Z_][A-Z0-9_\-.]*)\}").expect("valid regex"));
-
 pub fn process_request(
     curl: &str,
     env: &HashMap<String, String>,
 ) -> WebResult<WebProcessedRequest> {
-    let request = parse_request(curl, env)?;
-    let interpolation = collect_interpolation(curl, env);
+    process_request_with_provenance(curl, env, &ProvenanceMap::new())
+}
+
+/// Processes a request, attaching the provenance chain of every placeholder it used.
+pub fn process_request_with_provenance(
+    curl: &str,
+    env: &HashMap<String, String>,
+    provenance: &ProvenanceMap,
+) -> WebResult<WebProcessedRequest> {
+    let mut used = Vec::new();
+    let request = parse_request(curl, env, &mut used)?;
+    let interpolation = collect_interpolation(used, env, provenance);
 
     Ok(WebProcessedRequest {
         request,
@@ -104,18 +113,20 @@ pub fn render_export_template_web(
         headers,
         body_text: processed.request.body.clone(),
         body_file: None,
+        env_vars: Vec::new(),
+        params: Vec::new(),
     };
 
     template::render_export_template(name, &template)
         .map_err(|err| WebProcessError::Message(err.to_string()))
 }
 
-fn parse_request(curl: &str, env: &EnvMap) -> WebResult<WebRequest> {
+fn parse_request(curl: &str, env: &EnvMap, used: &mut Vec<String>) -> WebResult<WebRequest> {
     let mut lines = curl.lines().peekable();
 
-    let (method, url) = parse_request_line(&mut lines, env)?;
-    let headers = parse_headers(&mut lines, env)?;
-    let body = parse_body(&mut lines, env)?;
+    let (method, url) = parse_request_line(&mut lines, env, used)?;
+    let headers = parse_headers(&mut lines, env, used)?;
+    let body = parse_body(&mut lines, env, used)?;
 
     Ok(WebRequest {
         method,
@@ -128,6 +139,7 @@ fn parse_request(curl: &str, env: &EnvMap) -> WebResult<WebRequest> {
 fn parse_request_line<'a, I>(
     lines: &mut std::iter::Peekable<I>,
     env: &EnvMap,
+    used: &mut Vec<String>,
 ) -> WebResult<(String, String)>
 where
     I: Iterator<Item = &'a str>,
@@ -150,7 +162,7 @@ where
         }
 
         lines.next();
-        let expanded = expand_placeholders(trimmed, env)
+        let expanded = expand_placeholders_with_usage(trimmed, env, used)
             .map_err(|err| WebProcessError::Message(err.to_string()))?;
         let mut parts = expanded.split_whitespace();
         if let Some(first) = parts.next() {
@@ -173,6 +185,7 @@ where
 fn parse_headers<'a, I>(
     lines: &mut std::iter::Peekable<I>,
     env: &EnvMap,
+    used: &mut Vec<String>,
 ) -> WebResult<Vec<WebHeader>>
 where
     I: Iterator<Item = &'a str>,
@@ -196,7 +209,7 @@ where
         }
 
         lines.next();
-        let expanded = expand_placeholders(trimmed, env)
+        let expanded = expand_placeholders_with_usage(trimmed, env, used)
             .map_err(|err| WebProcessError::Message(err.to_string()))?;
         let (name, value) = expanded
             .split_once(':')
@@ -210,7 +223,11 @@ where
     Ok(headers)
 }
 
-fn parse_body<'a, I>(lines: &mut std::iter::Peekable<I>, env: &EnvMap) -> WebResult<Option<String>>
+fn parse_body<'a, I>(
+    lines: &mut std::iter::Peekable<I>,
+    env: &EnvMap,
+    used: &mut Vec<String>,
+) -> WebResult<Option<String>>
 where
     I: Iterator<Item = &'a str>,
 {
@@ -235,29 +252,24 @@ where
         return Ok(None);
     }
 
-    let expanded = expand_placeholders(&raw_body, env)
+    let expanded = expand_placeholders_with_usage(&raw_body, env, used)
         .map_err(|err| WebProcessError::Message(err.to_string()))?;
     Ok(Some(expanded))
 }
 
-fn collect_interpolation(curl: &str, env: &EnvMap) -> Vec<WebInterpolationDetail> {
-    let mut seen = HashSet::new();
-    let mut result = Vec::new();
-
-    for caps in PLACEHOLDER_PATTERN.captures_iter(curl) {
-        if let Some(key) = caps.get(1) {
-            let key_str = key.as_str();
-            if seen.insert(key_str.to_string()) {
-                if let Some(value) = env.get(key_str) {
-                    result.push(WebInterpolationDetail {
-                        key: key_str.to_string(),
-                        value: value.clone(),
-                    });
-                }
-            }
-        }
-    }
-    result
+fn collect_interpolation(
+    used: Vec<String>,
+    env: &EnvMap,
+    provenance: &ProvenanceMap,
+) -> Vec<WebInterpolationDetail> {
+    used.into_iter()
+        .filter_map(|key| {
+            let raw = env.get(&key)?;
+            let value = resolve_variable(&key, env).unwrap_or_else(|_| raw.clone());
+            let chain = provenance.chain(&key).to_vec();
+            Some(WebInterpolationDetail { key, value, chain })
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -284,6 +296,29 @@ mod tests {
         assert_eq!(processed.interpolation.len(), 3);
     }
 
+    #[test]
+    fn process_request_with_provenance_attaches_chains() {
+        let mut provenance = ProvenanceMap::new();
+        provenance.record(
+            "TOKEN",
+            VariableSource::EnvFile {
+                path: "dev.env".into(),
+                line: Some(1),
+            },
+        );
+        let processed = process_request_with_provenance(
+            "GET {API_BASE}\nAuthorization: Bearer {TOKEN}",
+            &env_map(),
+            &provenance,
+        )
+        .unwrap();
+
+        assert_eq!(processed.interpolation.len(), 2);
+        assert!(processed.interpolation[0].chain.is_empty());
+        assert_eq!(processed.interpolation[1].key, "TOKEN");
+        assert_eq!(processed.interpolation[1].chain.len(), 1);
+    }
+
     #[test]
     fn errors_on_missing_variable() {
         let curl = "GET {UNKNOWN}";
@@ -62,3 +62,646 @@ fn executes_simple_request() {
 
     mock.assert();
 }
+
+#[test]
+fn dry_run_prints_request_without_sending() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let server = MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.any_request();
+        then.status(200);
+    });
+
+    temp.child("curlpit.json")
+        .write_str(&format!(
+            r#"{{"defaultHeaders": {{"X-Default": "yes"}}, "profiles": {{"local": {{"variables": {{"API_BASE": "{}"}}}}}}}}"#,
+            server.base_url()
+        ))
+        .unwrap();
+    temp.child("sample.curl")
+        .write_str("POST {API_BASE}/items\n\n{\"ok\":true}\n")
+        .unwrap();
+
+    let mut cmd = cargo_bin();
+    cmd.current_dir(temp.path());
+    cmd.arg("sample.curl").arg("--dry-run");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Dry run"))
+        .stdout(predicate::str::contains(server.url("/items")))
+        .stdout(predicate::str::contains("X-Default"))
+        .stdout(predicate::str::contains(
+            "profiles.local.variables.API_BASE",
+        ));
+
+    mock.assert_hits(0);
+}
+
+#[test]
+fn schema_lists_profile_names_when_requested() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    write_profiles(&temp);
+
+    let mut cmd = cargo_bin();
+    cmd.current_dir(temp.path());
+    cmd.arg("schema").arg("--with-profiles");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\"title\": \"Curlpit Configuration\"",
+        ))
+        .stdout(predicate::str::contains("\"enum\""))
+        .stdout(predicate::str::contains("\"prod\""));
+}
+
+#[test]
+fn config_check_reports_unknown_keys() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("curlpit.json")
+        .write_str(r#"{"defaultHeader": {}, "profiles": {"dev": {}}}"#)
+        .unwrap();
+
+    let mut cmd = cargo_bin();
+    cmd.current_dir(temp.path());
+    cmd.arg("config").arg("check");
+
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "defaultHeader: unknown key (did you mean defaultHeaders?)",
+        ))
+        .stderr(predicate::str::contains("1 issue(s) found"));
+
+    let mut cmd = cargo_bin();
+    cmd.current_dir(temp.path());
+    cmd.arg("env").arg("list");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("warning:"))
+        .stderr(predicate::str::contains("defaultHeader: unknown key"));
+}
+
+#[test]
+fn secrets_get_and_env_loading_decrypt_with_passphrase() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let encrypted = curlpit::env::secrets::encrypt_env_with_work_factor(
+        "API_TOKEN=staging-token\n",
+        "team-passphrase",
+        2,
+    )
+    .unwrap();
+    temp.child("staging.env.age").write_str(&encrypted).unwrap();
+    temp.child("curlpit.json")
+        .write_str(r#"{"profiles": {"staging": {"env": "staging.env.age"}}}"#)
+        .unwrap();
+    temp.child("sample.curl")
+        .write_str("GET https://example.com\nAuthorization: Bearer {API_TOKEN}\n")
+        .unwrap();
+
+    let mut cmd = cargo_bin();
+    cmd.current_dir(temp.path())
+        .env("CURLPIT_SECRETS_PASSPHRASE", "team-passphrase")
+        .args(["secrets", "get", "staging.env.age", "API_TOKEN"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("staging-token"));
+
+    let mut cmd = cargo_bin();
+    cmd.current_dir(temp.path())
+        .env("CURLPIT_SECRETS_PASSPHRASE", "team-passphrase")
+        .args(["sample.curl", "--dry-run"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Bearer staging-token"));
+
+    let mut cmd = cargo_bin();
+    cmd.current_dir(temp.path())
+        .env_remove("CURLPIT_SECRETS_PASSPHRASE")
+        .args(["sample.curl", "--dry-run"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("CURLPIT_SECRETS_PASSPHRASE"));
+}
+
+#[cfg(unix)]
+#[test]
+fn command_variables_run_only_when_referenced() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp = assert_fs::TempDir::new().unwrap();
+    let script = temp.child("fake-pass.sh");
+    script
+        .write_str("#!/bin/sh\necho \"$1\" >> calls.log\necho \"secret-$1\"\n")
+        .unwrap();
+    std::fs::set_permissions(script.path(), std::fs::Permissions::from_mode(0o755)).unwrap();
+    temp.child("curlpit.json")
+        .write_str(
+            r#"{"variables": {
+  "API_TOKEN": {"command": "./fake-pass.sh api", "ttl": 3600},
+  "UNUSED": {"command": "./fake-pass.sh unused"}
+}, "profiles": {"default": {}}}"#,
+        )
+        .unwrap();
+    temp.child("sample.curl")
+        .write_str("GET https://example.com\nAuthorization: Bearer {API_TOKEN}\n")
+        .unwrap();
+
+    for _ in 0..2 {
+        let mut cmd = cargo_bin();
+        cmd.current_dir(temp.path())
+            .env("CURLPIT_CACHE_DIR", temp.path().join("cache"))
+            .args(["sample.curl", "--dry-run"]);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("Bearer secret-api"));
+    }
+
+    temp.child("calls.log").assert("api\n");
+}
+
+#[test]
+fn export_uses_custom_templates_from_config() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("curlpit.json")
+        .write_str(r#"{"exportTemplates": {"ruby": "templates/ruby.mustache"}, "profiles": {"default": {}}}"#)
+        .unwrap();
+    temp.child("templates/ruby.mustache")
+        .write_str(
+            "uri = URI({{json url}})\n{{#headers}}\nreq[{{json name}}] = {{json value}}\n{{/headers}}\n",
+        )
+        .unwrap();
+    temp.child("sample.curl")
+        .write_str("GET https://example.com/items\nAccept: application/json\n")
+        .unwrap();
+
+    let mut cmd = cargo_bin();
+    cmd.current_dir(temp.path())
+        .args(["export", "ruby", "sample.curl"]);
+    cmd.assert().success().stdout(predicate::str::contains(
+        "uri = URI(\"https://example.com/items\")\nreq[\"Accept\"] = \"application/json\"\n",
+    ));
+
+    let mut cmd = cargo_bin();
+    cmd.current_dir(temp.path()).args(["export", "--list"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("python-requests"))
+        .stdout(predicate::str::contains("ruby"));
+}
+
+#[test]
+fn export_keep_placeholders_uses_env_lookups_and_props() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    write_profiles(&temp);
+    temp.child("sample.curl")
+        .write_str("GET {API_BASE}/items\nAuthorization: Bearer {API_TOKEN}\n")
+        .unwrap();
+
+    let mut cmd = cargo_bin();
+    cmd.current_dir(temp.path()).args([
+        "--profile",
+        "prod",
+        "export",
+        "js-fetch",
+        "sample.curl",
+        "--keep-placeholders",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "export async function runRequest({ apiBase }) {",
+        ))
+        .stdout(predicate::str::contains("await fetch(apiBase + \"/items\""))
+        .stdout(predicate::str::contains(
+            "\"Authorization\": \"Bearer \" + process.env.API_TOKEN",
+        ))
+        .stdout(predicate::str::contains("super-secret").not());
+}
+
+#[test]
+fn export_ts_client_renders_a_request_folder() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    write_profiles(&temp);
+    temp.child("requests/users/list.curl")
+        .write_str("GET {API_BASE}/users\nAuthorization: Bearer {API_TOKEN}\n")
+        .unwrap();
+    temp.child("requests/health.curl")
+        .write_str("GET {API_BASE}/health\nX-Env: local\n")
+        .unwrap();
+
+    let run = || {
+        let mut cmd = cargo_bin();
+        cmd.current_dir(temp.path())
+            .args(["-P", "prod", "export", "ts-client", "requests"]);
+        let output = cmd.assert().success().get_output().stdout.clone();
+        String::from_utf8(output).unwrap()
+    };
+    let first = run();
+    assert!(first.contains("  headers: {\n    \"X-Env\": \"prod\",\n  },\n"));
+    assert!(first.contains("export async function health(params: HealthParams"));
+    assert!(first.contains("    {\n      \"X-Env\": \"local\",\n    },\n"));
+    assert!(first.contains(
+        "export interface UsersListParams {\n  apiBase: string;\n  apiToken: string;\n}"
+    ));
+    assert!(first.find("function health").unwrap() < first.find("function usersList").unwrap());
+    assert_eq!(first, run());
+
+    let mut cmd = cargo_bin();
+    cmd.current_dir(temp.path())
+        .args(["export", "curl", "requests"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot export a folder"));
+}
+
+#[test]
+fn export_ts_client_shares_the_folder_overlay_headers() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    write_profiles(&temp);
+    temp.child("requests/curlpit.json")
+        .write_str(r#"{"defaultHeaders": {"X-Team": "billing"}}"#)
+        .unwrap();
+    temp.child("requests/invoices.curl")
+        .write_str("GET {API_BASE}/invoices\n")
+        .unwrap();
+
+    let mut cmd = cargo_bin();
+    cmd.current_dir(temp.path())
+        .args(["export", "ts-client", "requests"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let rendered = String::from_utf8(output).unwrap();
+    assert!(rendered.contains("  headers: {\n    \"X-Team\": \"billing\",\n  },\n"));
+    assert!(rendered.contains("    \"GET\",\n    params.apiBase + \"/invoices\",\n    {},\n"));
+}
+
+#[test]
+fn export_k6_reads_profile_variables_from_env() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    write_profiles(&temp);
+    temp.child("sample.curl")
+        .write_str("POST {API_BASE}/items\n\n{\"ok\":true}\n")
+        .unwrap();
+
+    let mut cmd = cargo_bin();
+    cmd.current_dir(temp.path())
+        .args(["export", "k6", "sample.curl"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "// Run with: k6 run -e API_BASE=... script.js",
+        ))
+        .stdout(predicate::str::contains(
+            "      __ENV.API_BASE + \"/items\",\n",
+        ))
+        .stdout(predicate::str::contains("    check(res, {"))
+        .stdout(predicate::str::contains("localhost").not());
+}
+
+#[test]
+fn import_round_trips_an_exported_hurl_file() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("requests/health.curl")
+        .write_str("GET {API_BASE}/health\nAccept: application/json\n")
+        .unwrap();
+    temp.child("requests/notes.curl")
+        .write_str("POST https://example.com/notes\n\nline one\nline two\n")
+        .unwrap();
+
+    let mut export = cargo_bin();
+    export
+        .current_dir(temp.path())
+        .args(["export", "hurl", "requests", "--out", "api.hurl"]);
+    export.assert().success();
+
+    let mut import = cargo_bin();
+    import
+        .current_dir(temp.path())
+        .args(["import", "hurl", "api.hurl", "--out", "imported"]);
+    import
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("health.curl"));
+    temp.child("imported/health.curl")
+        .assert(predicate::str::ends_with(
+            "\nGET {API_BASE}/health\nAccept: application/json\n",
+        ));
+    temp.child("imported/notes.curl")
+        .assert(predicate::str::ends_with(
+            "\nPOST https://example.com/notes\n\nline one\nline two\n",
+        ));
+
+    let mut again = cargo_bin();
+    again
+        .current_dir(temp.path())
+        .args(["import", "hurl", "api.hurl", "--out", "imported"]);
+    again
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Refusing to overwrite"));
+}
+
+#[test]
+fn import_postman_writes_folders_and_environment_profiles() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("collection.json")
+        .write_str(
+            r#"{
+  "info": {"name": "Demo", "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json"},
+  "variable": [{"key": "API_VERSION", "value": "v1"}],
+  "item": [{
+    "name": "Users",
+    "item": [{
+      "name": "List users",
+      "event": [{"listen": "test", "script": {"exec": ["pm.test('ok')"]}}],
+      "request": {"method": "GET", "url": {"raw": "{{API_BASE}}/{{API_VERSION}}/users"}}
+    }]
+  }]
+}"#,
+        )
+        .unwrap();
+    temp.child("staging.postman_environment.json")
+        .write_str(
+            r#"{"name": "Staging", "values": [{"key": "API_BASE", "value": "https://staging.example.com", "enabled": true}]}"#,
+        )
+        .unwrap();
+
+    let mut import = cargo_bin();
+    import.current_dir(temp.path()).args([
+        "import",
+        "postman",
+        "collection.json",
+        "--environment",
+        "staging.postman_environment.json",
+        "--out",
+        "requests",
+    ]);
+    import
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("users/list-users.curl"))
+        .stdout(predicate::str::contains("test script was not imported"));
+    temp.child("requests/users/list-users.curl")
+        .assert(predicate::str::contains(
+            "# WARNING: test script was not imported\nGET {API_BASE}/{API_VERSION}/users\n",
+        ));
+
+    let mut dry_run = cargo_bin();
+    dry_run.current_dir(temp.path()).args([
+        "-P",
+        "staging",
+        "requests/users/list-users.curl",
+        "--dry-run",
+    ]);
+    dry_run.assert().success().stdout(predicate::str::contains(
+        "https://staging.example.com/v1/users",
+    ));
+
+    // Importing the environment again would replace the profile
+    let mut again = cargo_bin();
+    again.current_dir(temp.path()).args([
+        "import",
+        "postman",
+        "collection.json",
+        "--environment",
+        "staging.postman_environment.json",
+        "--out",
+        "other",
+    ]);
+    again
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Profile staging already exists"));
+    temp.child("other").assert(predicate::path::missing());
+}
+
+#[test]
+fn import_postman_edits_existing_config_in_place() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let config = r#"{
+  // team settings
+  "variables": {"API_VERSION": "v1"},
+  "profiles": {"local": {"variables": {"API_BASE": "http://localhost"}}},
+}
+"#;
+    temp.child("curlpit.jsonc").write_str(config).unwrap();
+    temp.child("collection.json")
+        .write_str(
+            r#"{
+  "info": {"name": "Demo", "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json"},
+  "variable": [{"key": "API_VERSION", "value": "v1"}],
+  "item": [{"name": "Ping", "request": {"method": "GET", "url": "{{API_BASE}}/ping"}}]
+}"#,
+        )
+        .unwrap();
+    temp.child("staging.postman_environment.json")
+        .write_str(
+            r#"{"name": "Staging", "values": [{"key": "API_BASE", "value": "https://staging.example.com", "enabled": true}]}"#,
+        )
+        .unwrap();
+
+    let mut import = cargo_bin();
+    import.current_dir(temp.path()).args([
+        "import",
+        "postman",
+        "collection.json",
+        "--environment",
+        "staging.postman_environment.json",
+    ]);
+    import.assert().success();
+    temp.child("curlpit.jsonc").assert(
+        r#"{
+  // team settings
+  "variables": {"API_VERSION": "v1"},
+  "profiles": {"local": {"variables": {"API_BASE": "http://localhost"}}, "staging": {"variables":{"API_BASE":"https://staging.example.com"}}},
+}
+"#,
+    );
+}
+
+#[test]
+fn import_insomnia_adds_environments_to_existing_config() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    write_profiles(&temp);
+    temp.child("insomnia.yaml")
+        .write_str(
+            r#"_type: export
+__export_format: 4
+resources:
+  - _id: wrk_1
+    _type: workspace
+    name: Shop
+  - _id: env_base
+    _type: environment
+    parentId: wrk_1
+    name: Base Environment
+    data:
+      shop_url: https://shop.example.com
+  - _id: env_local
+    _type: environment
+    parentId: env_base
+    name: Local Shop
+    data:
+      shop_url: http://localhost:3000
+  - _id: fld_1
+    _type: request_group
+    parentId: wrk_1
+    name: Carts
+  - _id: req_1
+    _type: request
+    parentId: fld_1
+    name: Get cart
+    method: GET
+    url: "{{ _.shop_url }}/carts/{% prompt 'id' %}"
+"#,
+        )
+        .unwrap();
+
+    let mut import = cargo_bin();
+    import
+        .current_dir(temp.path())
+        .args(["import", "insomnia", "insomnia.yaml"]);
+    import
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("shop/carts/get-cart.curl"))
+        .stdout(predicate::str::contains("template tag {% prompt 'id' %}"));
+    temp.child("shop/carts/get-cart.curl")
+        .assert(predicate::str::contains(
+            "GET {shop_url}/carts/{% prompt 'id' %}\n",
+        ));
+
+    let mut list = cargo_bin();
+    list.current_dir(temp.path()).args(["env", "list"]);
+    list.assert()
+        .success()
+        .stdout(predicate::str::contains("* dev"))
+        .stdout(predicate::str::contains("local-shop"));
+}
+
+#[test]
+fn import_har_filters_entries_and_substitutes_profile_values() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    write_profiles(&temp);
+    temp.child("session.har")
+        .write_str(
+            r#"{"log": {"entries": [
+  {"request": {"method": "GET", "url": "http://localhost/app.js", "headers": []},
+   "response": {"content": {"mimeType": "application/javascript"}}},
+  {"request": {"method": "POST", "url": "http://localhost/api/cart",
+               "headers": [{"name": "Content-Type", "value": "application/json"}],
+               "postData": {"mimeType": "application/json", "text": "{\"sku\":\"abc\"}"}},
+   "response": {"content": {"mimeType": "application/json"}}}
+]}}"#,
+        )
+        .unwrap();
+
+    let mut import = cargo_bin();
+    import.current_dir(temp.path()).args([
+        "import",
+        "har",
+        "session.har",
+        "--url",
+        "/api/",
+        "--content-type",
+        "json",
+        "--out",
+        "captured",
+    ]);
+    import
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("001-post-localhost-api-cart.curl"));
+    temp.child("captured/001-post-localhost-api-cart.curl")
+        .assert(predicate::str::ends_with(
+            "\nPOST {API_BASE}/api/cart\nContent-Type: application/json\n\n{\"sku\":\"abc\"}\n",
+        ));
+    temp.child("captured/002-get-localhost-app-js.curl")
+        .assert(predicate::path::missing());
+
+    let mut wrong_format = cargo_bin();
+    wrong_format.current_dir(temp.path()).args([
+        "import",
+        "hurl",
+        "session.har",
+        "--method",
+        "GET",
+    ]);
+    wrong_format
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "only supported when importing from har",
+        ));
+}
+
+fn write_profiles(temp: &assert_fs::TempDir) {
+    temp.child("curlpit.json")
+        .write_str(
+            r#"{
+  "defaultProfile": "dev",
+  "profiles": {
+    "dev": {"variables": {"API_BASE": "http://localhost", "DEBUG": "1"}},
+    "prod": {
+      "env": "prod.env",
+      "variables": {"API_BASE": "https://api.example.com"},
+      "defaultHeaders": {"X-Env": "prod"}
+    }
+  }
+}"#,
+        )
+        .unwrap();
+    temp.child("prod.env")
+        .write_str("API_TOKEN=super-secret\n")
+        .unwrap();
+}
+
+#[test]
+fn env_list_marks_default_profile() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    write_profiles(&temp);
+
+    let mut cmd = cargo_bin();
+    cmd.current_dir(temp.path()).args(["env", "list"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("* dev"))
+        .stdout(predicate::str::contains("env: prod.env"));
+}
+
+#[test]
+fn env_show_redacts_values_by_default() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    write_profiles(&temp);
+
+    let mut cmd = cargo_bin();
+    cmd.current_dir(temp.path()).args(["env", "show", "prod"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("API_TOKEN"))
+        .stdout(predicate::str::contains("super-secret").not())
+        .stdout(predicate::str::contains("X-Env"));
+
+    let mut cmd = cargo_bin();
+    cmd.current_dir(temp.path())
+        .args(["env", "show", "prod", "--reveal"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("super-secret"));
+}
+
+#[test]
+fn env_diff_lists_missing_variables_and_headers() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    write_profiles(&temp);
+
+    let mut cmd = cargo_bin();
+    cmd.current_dir(temp.path())
+        .args(["env", "diff", "dev", "prod"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("only in dev"))
+        .stdout(predicate::str::contains("API_TOKEN"))
+        .stdout(predicate::str::contains("X-Env"));
+}